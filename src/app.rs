@@ -1,56 +1,168 @@
 
-use crate::core::{AssetManager, LabelComposer};
-use crate::models::{ClassType, Hazard, ImageValidation, OutputFormat, ResizeMethod, LabelConfig, BurnType};
+use crate::core::label_composer::{flatten_for_export, load_user_image, save_label, user_image_region};
+use crate::core::export_history::{self, ExportRecord};
+use crate::core::{batch, legibility, project, AssetManager, LabelComposer, LoadProgress, TextRenderer};
+use crate::models::{BackgroundFillKind, ClassType, Hazard, ImageValidation, OutputFormat, ResizeMethod, LabelConfig, BurnType, EffectSettings, JpegSubsampling, TextureBlendMode, TextureLayer, WatermarkCorner, Rectangle, LABEL_SIZE, MAX_BLEED, MAX_OUTPUT_RESOLUTION};
 use crate::ui;
-use crate::utils::{validate_user_image, LabelError, load_image_robustly};
+use crate::ui::theme::{PreviewBackdrop, UiTheme};
+use crate::ui::ui_state::{self, UiState};
+use crate::utils::{color, validate_dimensions, validate_user_image, LabelError, load_image_robustly};
 use iced::widget::{column, container, text, button, scrollable, row};
 use iced::{Application, Command, Element, Length, Theme, Color, Subscription};
-use image::codecs::jpeg::JpegEncoder;
 use image::DynamicImage;
-use std::path::PathBuf;
-use std::io::{Read, Write};
-fn from_hex(hex: &str) -> Option<Color> {
-    let hex = hex.trim_start_matches('#');
-    if hex.len() != 6 {
-        return None;
+use std::path::{Path, PathBuf};
+
+const PROJECT_THUMBNAIL_SIZE: u32 = 128;
+const BATCH_THUMBNAIL_SIZE: u32 = 160;
+const BATCH_PREVIEW_LIMIT: usize = 24;
+const REGION_OUTLINE_WIDTH: u32 = 2;
+const IMAGE_WATCH_DEBOUNCE_MS: u64 = 300;
+const FONT_SAMPLE_TEXT: &str = "SCP-173 SAFE";
+const FONT_SAMPLE_SIZE: f32 = 36.0;
+const GIF_ALPHA_THRESHOLD: u8 = 128;
+const GIF_TRANSPARENT_INDEX: u8 = 255;
+/// Below this window width the preview moves underneath the inputs.
+const STACKED_LAYOUT_WIDTH: u32 = 960;
+
+struct DecodedAnimation {
+    frames: Vec<image::RgbaImage>,
+    delays_ms: Vec<u32>,
+}
+
+fn outline_region(image: &mut image::RgbaImage, rect: Rectangle) {
+    let color = image::Rgba(ui::theme::palette().accent.into_rgba8());
+    let (right, bottom) = (rect.x + rect.width, rect.y + rect.height);
+    for (x, y, pixel) in image.enumerate_pixels_mut() {
+        let inside = (rect.x..right).contains(&x) && (rect.y..bottom).contains(&y);
+        let on_edge = x < rect.x + REGION_OUTLINE_WIDTH
+            || x + REGION_OUTLINE_WIDTH >= right
+            || y < rect.y + REGION_OUTLINE_WIDTH
+            || y + REGION_OUTLINE_WIDTH >= bottom;
+        if inside && on_edge {
+            *pixel = color;
+        }
     }
-    let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
-    let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
-    let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
-    Some(Color::from_rgb8(r, g, b))
+}
+
+fn cycle<T: Copy + PartialEq>(items: &[T], current: T, step: isize) -> T {
+    let Some(index) = items.iter().position(|item| *item == current) else {
+        return items.first().copied().unwrap_or(current);
+    };
+    let len = items.len() as isize;
+    items[(index as isize + step).rem_euclid(len) as usize]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorTarget {
+    ScpText,
+    ClassText,
+    Flatten,
+    Letterbox,
+    Background,
+    FillStart,
+    FillEnd,
+}
+
+#[derive(Debug, Clone)]
+pub struct FontPreview {
+    pub path: PathBuf,
+    pub name: String,
+    pub sample: iced::widget::image::Handle,
+}
+
+struct BatchPreview {
+    entries: Vec<LabelConfig>,
+    thumbnails: Vec<Option<iced::widget::image::Handle>>,
 }
 
 pub struct App {
     config: LabelConfig,
     assets: Option<AssetManager>,
     composer: Option<LabelComposer>,
+    user_image: Option<DynamicImage>,
     preview_handle: Option<iced::widget::image::Handle>,
+    pinned_class: Option<ClassType>,
+    pinned_preview_handle: Option<iced::widget::image::Handle>,
+    preview_backdrop: PreviewBackdrop,
+    show_image_region: bool,
+    preview_pixelated: bool,
+    previews_in_flight: usize,
+    window_width: u32,
+    ui_theme: UiTheme,
+    watch_image: bool,
+    class_series_use_names: bool,
+    export_history: Option<Vec<ExportRecord>>,
+    recent_colors: Vec<Color>,
+    recent_images: Vec<PathBuf>,
+    recent_projects: Vec<PathBuf>,
+    batch_preview: Option<BatchPreview>,
+    font_preview: Option<FontPreview>,
     validation: Option<ImageValidation>,
     loading: bool,
+    load_progress: Option<LoadProgress>,
     modal_error: Option<String>,
     notification_message: Option<String>,
+    contrast_warning: Option<String>,
     zoom_factor: f32,
     preview_offset: (f32, f32),
+    hover_position: Option<(u32, u32)>,
+    preview_rotation: u16,
     gif_frames: Option<Vec<image::RgbaImage>>,
     current_frame_index: usize,
     gif_playing: bool,
     gif_frame_delays: Vec<u32>,
     advanced_burn_settings_visible: bool,
+    scp_color_draft: Option<String>,
+    class_color_draft: Option<String>,
+    flatten_color_draft: Option<String>,
+    letterbox_color_draft: Option<String>,
+    background_color_draft: Option<String>,
+    fill_color_draft: Option<String>,
+    fill_end_color_draft: Option<String>,
+    resolution_draft: Option<(String, String)>,
+    aspect_locked: bool,
+    preview_as_export: bool,
+    texture_pack_dir: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
+    AssetLoadProgress(LoadProgress),
     AssetsLoaded(Result<AssetManager, LabelError>),
     ScpNumberChanged(String),
     ObjectClassChanged(String),
     ClassTypeSelected(ClassType),
     AlternateStyleToggled(bool),
+    AlternateUserImageToggled(bool),
     SelectImagePressed,
     ImageSelected(Result<PathBuf, LabelError>),
+    WatchImageToggled(bool),
+    WatermarkTextChanged(String),
+    WatermarkSubmitted,
+    WatermarkCornerSelected(WatermarkCorner),
+    WatermarkOpacityChanged(f32),
+    SelectWatermarkImagePressed,
+    WatermarkImageSelected(Option<PathBuf>),
+    ClearWatermarkImage,
+    ImageFileChanged(PathBuf),
     ResizeMethodChanged(ResizeMethod),
     HazardSelected(Hazard),
     ClearHazard,
+    HazardOpacityChanged(f32),
+    CycleClassType(isize),
+    CycleHazard(isize),
     TextureToggled(bool),
+    TextureSelected(String),
+    SelectTexturePackDir,
+    TexturePackDirSelected(Option<PathBuf>),
+    AddTextureLayer,
+    RemoveTextureLayer(usize),
+    TextureLayerNameChanged(usize, String),
+    TextureLayerBlendChanged(usize, TextureBlendMode),
+    TextureBlendChanged(TextureBlendMode),
+    MoveLayerUp(usize),
+    MoveLayerDown(usize),
+    TextureLayerOpacityChanged(usize, f32),
     TextureOpacityChanged(f32),
     BrightnessChanged(f32),
     ContrastChanged(f32),
@@ -66,18 +178,28 @@ pub enum Message {
     ScpTextOffsetYChanged(String),
     ClassTextOffsetXChanged(String),
     ClassTextOffsetYChanged(String),
-    ScpTextColorChanged(Color),
-    ClassTextColorChanged(Color),
+    ScpTextColorChanged(String),
+    ClassTextColorChanged(String),
     ResetText,
+    ResetBurn,
+    ExportBurnMask,
+    CopyEffects,
+    PasteEffects,
+    EffectsPasted(Option<String>),
     SaveConfig,
     LoadConfig,
     ConfigLoaded(Result<LabelConfig, LabelError>),
     SaveProject,
     LoadProject,
+    ImportLayers,
     ProjectSaved(Result<PathBuf, LabelError>),
+    OpenProject(PathBuf),
+    ProjectFileLoaded(PathBuf, Result<LabelConfig, LabelError>),
     ProjectLoaded(Result<LabelConfig, LabelError>),
     ScpNumberSubmitted(String),
     ObjectClassSubmitted(String),
+    UppercaseNumberToggled(bool),
+    UppercaseClassToggled(bool),
     ScpNumberFontSizeSubmitted(String),
     ObjectClassFontSizeSubmitted(String),
     OpacitySubmitted(String),
@@ -87,27 +209,83 @@ pub enum Message {
     ScpTextOffsetYSubmitted(String),
     ClassTextOffsetXSubmitted(String),
     ClassTextOffsetYSubmitted(String),
-    ScpTextColorSubmitted(Color),
-    ClassTextColorSubmitted(Color),
+    ScpTextColorSubmitted,
+    ClassTextColorSubmitted,
+    FlattenToggled(bool),
+    FlattenColorChanged(String),
+    FlattenColorSubmitted,
+    LetterboxColorChanged(String),
+    LetterboxColorSubmitted,
+    BackgroundColorChanged(String),
+    BackgroundColorSubmitted,
+    BackgroundFillChanged(BackgroundFillKind),
+    FillColorChanged(String),
+    FillColorSubmitted,
+    FillEndColorChanged(String),
+    FillEndColorSubmitted,
+    FillAngleChanged(f32),
+    RecentColorPicked(ColorTarget, Color),
     AdvanceFrame,
-    ScrollZoom(f32),
+    ScrollZoom(f32, (f32, f32)),
     ResolutionChanged(u32),
+    SupersampleChanged(u8),
+    NativeResolutionToggled(bool),
+    BleedChanged(u32),
+    PrintMarksToggled(bool),
+    ResolutionWidthChanged(String),
+    ResolutionHeightChanged(String),
+    AspectLockToggled(bool),
+    PreviewAsExportToggled(bool),
     FormatChanged(OutputFormat),
+    WebpLosslessToggled(bool),
+    OptimizePngToggled(bool),
+    JpegSubsamplingChanged(JpegSubsampling),
     ExportPressed,
     RegeneratePreview,
     PreviewGenerated(Result<Vec<u8>, LabelError>),
+    PinnedClassSelected(Option<ClassType>),
+    PreviewBackdropSelected(PreviewBackdrop),
+    UiThemeSelected(UiTheme),
+    ImageRegionToggled(bool),
+    PreviewPixelatedToggled(bool),
+    WindowResized(u32),
+    ShowExportHistory,
+    ClassSeriesNamesToggled(bool),
+    ExportClassSeriesPressed,
+    ClassSeriesExported(Result<usize, LabelError>),
+    CloseExportHistory,
+    ReopenExportConfig(usize),
+    OpenExportFolder(usize),
+    LoadBatch,
+    BatchLoaded(Result<Vec<LabelConfig>, LabelError>),
+    BatchThumbnailGenerated(usize, Result<Vec<u8>, LabelError>),
+    CloseBatchPreview,
+    PreviewFontPressed,
+    FontPreviewRendered(Result<FontPreview, LabelError>),
+    SelectFontPressed,
+    FontSelected(Option<PathBuf>),
+    ResetFontPressed,
+    PinnedPreviewGenerated(Result<Vec<u8>, LabelError>),
     ShowNotification(String),
     ZoomInPressed,
     ZoomOutPressed,
     ZoomResetPressed,
+    ZoomFitPressed,
+    PreviewHovered(Option<(u32, u32)>),
+    PreviewDragged(f32, f32),
+    RotatePreviewPressed,
+    PreviewAreaMeasured(Option<iced::Rectangle>),
     ToggleGifPlayback,
     GifFrameDelayChanged(usize, String),
     ScpLineSpacingChanged(f32),
     ScpLineSpacingTextChanged(String),
+    LineSpacingSubmitted,
     ClassLineSpacingChanged(f32),
     ClassLineSpacingTextChanged(String),
     BurnToggled(bool),
-    BurnAmountChanged(String),
+    BurnAmountChanged(f32),
+    BurnAmountTextChanged(String),
+    BurnAmountSubmitted,
     BurnScaleChanged(f32),
     BurnDetailChanged(f32),
     BurnEdgeSoftnessChanged(f32),
@@ -132,28 +310,59 @@ impl Application for App {
     type Flags = ();
 
     fn new(_flags: ()) -> (Self, Command<Message>) {
+        let ui_state = UiState::load();
+        ui::theme::set_ui_theme(ui_state.ui_theme);
         (
             Self {
-                config: LabelConfig::default(),
+                config: LabelConfig::random(),
                 assets: None,
                 composer: None,
+                user_image: None,
                 preview_handle: None,
+                pinned_class: None,
+                pinned_preview_handle: None,
+                preview_backdrop: PreviewBackdrop::default(),
+                show_image_region: false,
+                preview_pixelated: false,
+                previews_in_flight: 0,
+                window_width: iced::window::Settings::default().size.width as u32,
+                ui_theme: ui_state.ui_theme,
+                watch_image: false,
+                class_series_use_names: false,
+                export_history: None,
+                recent_colors: ui_state.recent_colors.into_iter().map(Color::from).collect(),
+                recent_images: ui_state::existing_paths(ui_state.recent_images),
+                recent_projects: ui_state::existing_paths(ui_state.recent_projects),
+                batch_preview: None,
+                font_preview: None,
                 validation: None,
                 loading: true,
+                load_progress: None,
                 modal_error: None,
                 notification_message: None,
+                contrast_warning: None,
                 zoom_factor: 1.0,
                 preview_offset: (0.0, 0.0),
+                hover_position: None,
+                preview_rotation: 0,
                 gif_frames: None,
                 current_frame_index: 0,
                 gif_playing: true,
                 gif_frame_delays: Vec::new(),
                 advanced_burn_settings_visible: false,
+                scp_color_draft: None,
+                class_color_draft: None,
+                flatten_color_draft: None,
+                letterbox_color_draft: None,
+                background_color_draft: None,
+                fill_color_draft: None,
+                fill_end_color_draft: None,
+                resolution_draft: None,
+                aspect_locked: true,
+                preview_as_export: false,
+                texture_pack_dir: ui_state.texture_pack_dir.filter(|dir| dir.is_dir()),
             },
-            Command::perform(
-                async { crate::core::AssetManager::load_all() },
-                Message::AssetsLoaded,
-            ),
+            Command::none(),
         )
     }
 
@@ -167,11 +376,15 @@ impl Application for App {
                 self.config.apply_burn = enabled;
                 Command::perform(async {}, |_| Message::RegeneratePreview)
             }
-            Message::BurnAmountChanged(s) => {
+            Message::BurnAmountChanged(value) => {
+                self.config.burn_amount = value;
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+            Message::BurnAmountTextChanged(s) => {
                 if let Ok(value) = s.parse::<f32>() {
                     self.config.burn_amount = value.clamp(0.0, 1.0);
                 }
-                Command::perform(async {}, |_| Message::RegeneratePreview)
+                Command::none()
             }
             Message::BurnScaleChanged(value) => {
                 self.config.burn_scale = value;
@@ -254,12 +467,14 @@ impl Application for App {
             Message::ProjectSaved(result) => {
                 match result {
                     Ok(path) => {
-                        if let Err(e) = self.save_project(path.clone()) {
+                        let thumbnail = self.project_thumbnail();
+                        if let Err(e) = project::save_project(&self.config, self.config.image_path.as_deref(), thumbnail.as_ref(), &path) {
                             log::error!("Failed to save project to {:?}: {}", path, e);
                             self.modal_error = Some(e.to_string());
                         } else {
                             log::info!("Project saved successfully to {:?}", path);
                             self.notification_message = Some("Project Saved!".to_string());
+                            self.remember_project(&path);
                         }
                     }
                     Err(e) => {
@@ -273,23 +488,62 @@ impl Application for App {
             Message::LoadProject => {
                 return Command::perform(
                     async {
-                        let handle = rfd::AsyncFileDialog::new()
+                        rfd::AsyncFileDialog::new()
                             .add_filter("SCP Project", &["scp", "zip"])
                             .pick_file()
                             .await
-                            .ok_or_else(|| LabelError::Io("Load cancelled".to_string()))?;
-                        
-                        Self::load_project(handle.path().to_path_buf())
+                            .map(|handle| handle.path().to_path_buf())
+                    },
+                    |path| match path {
+                        Some(path) => Message::OpenProject(path),
+                        None => Message::ProjectLoaded(Err(LabelError::Io("Load cancelled".to_string()))),
                     },
-                    Message::ProjectLoaded
                 );
             }
 
+            Message::OpenProject(path) => Command::perform(
+                async move {
+                    let result = project::load_project(&path).map(project::ProjectData::into_config);
+                    (path, result)
+                },
+                |(path, result)| Message::ProjectFileLoaded(path, result),
+            ),
+
+            Message::ProjectFileLoaded(path, result) => {
+                if result.is_ok() {
+                    self.remember_project(&path);
+                } else {
+                    self.recent_projects.retain(|recent| recent != &path);
+                    self.save_ui_state();
+                }
+                self.update(Message::ProjectLoaded(result))
+            }
+
+            Message::ImportLayers => Command::perform(
+                async {
+                    let handle = rfd::AsyncFileDialog::new()
+                        .add_filter("Layer JSON", &["json"])
+                        .pick_file()
+                        .await
+                        .ok_or_else(|| LabelError::Io("Import cancelled".to_string()))?;
+
+                    LabelConfig::import_layers(handle.path())
+                },
+                Message::ProjectLoaded,
+            ),
+
             Message::ProjectLoaded(result) => {
                 match result {
                     Ok(config) => {
                         log::info!("Project loaded successfully.");
+                        let image_path = config.image_path.clone();
                         self.config = config;
+                        self.rebuild_composer();
+                        self.user_image = None;
+                        self.clear_input_drafts();
+                        if let Some(path) = image_path {
+                            return self.load_image(path, false);
+                        }
                         return Command::perform(async {}, |_| Message::RegeneratePreview);
                     }
                     Err(e) => {
@@ -307,7 +561,7 @@ impl Application for App {
                 if let Ok(value) = s.parse::<f32>() {
                     self.config.scp_line_spacing = value;
                 }
-                Command::perform(async {}, |_| Message::RegeneratePreview)
+                Command::none()
             }
             Message::ClassLineSpacingChanged(value) => {
                 self.config.class_line_spacing = value;
@@ -317,18 +571,22 @@ impl Application for App {
                 if let Ok(value) = s.parse::<f32>() {
                     self.config.class_line_spacing = value;
                 }
-                Command::perform(async {}, |_| Message::RegeneratePreview)
+                Command::none()
             }
 
 
+            Message::AssetLoadProgress(progress) => {
+                self.load_progress = Some(progress);
+                Command::none()
+            }
+
             Message::AssetsLoaded(result) => {
+                self.load_progress = None;
                 match result {
                     Ok(assets) => {
                         log::info!("Assets loaded successfully.");
                         self.assets = Some(assets);
-                        if let Ok(composer) = LabelComposer::new() {
-                            self.composer = Some(composer);
-                        }
+                        self.rebuild_composer();
                         self.loading = false;
                         return Command::perform(async {}, |_| Message::RegeneratePreview);
                     }
@@ -346,6 +604,16 @@ impl Application for App {
                 Command::none()
             }
 
+            Message::UppercaseNumberToggled(enabled) => {
+                self.config.uppercase_number = enabled;
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::UppercaseClassToggled(enabled) => {
+                self.config.uppercase_class = enabled;
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
             Message::ScpNumberSubmitted(text) => {
                 if text.is_empty() {
                     self.config.scp_number = "000".to_string();
@@ -377,6 +645,81 @@ impl Application for App {
                 return Command::perform(async {}, |_| Message::RegeneratePreview);
             }
 
+            Message::AlternateUserImageToggled(enabled) => {
+                self.config.alternate_user_image = enabled;
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::SelectTexturePackDir => {
+                Command::perform(
+                    async {
+                        rfd::AsyncFileDialog::new()
+                            .pick_folder()
+                            .await
+                            .map(|h| h.path().to_path_buf())
+                    },
+                    |dir| match dir {
+                        Some(dir) => Message::TexturePackDirSelected(Some(dir)),
+                        None => Message::ShowNotification("No folder selected".to_string()),
+                    },
+                )
+            }
+
+            Message::TexturePackDirSelected(dir) => {
+                self.texture_pack_dir = dir;
+                self.save_ui_state();
+                self.loading = true;
+                self.load_progress = None;
+                Command::none()
+            }
+
+            Message::WatermarkTextChanged(text) => {
+                self.config.watermark = Some(text).filter(|text| !text.is_empty());
+                Command::none()
+            }
+
+            Message::WatermarkSubmitted => Command::perform(async {}, |_| Message::RegeneratePreview),
+
+            Message::WatermarkCornerSelected(corner) => {
+                self.config.watermark_corner = corner;
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::WatermarkOpacityChanged(value) => {
+                self.config.watermark_opacity = value;
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::SelectWatermarkImagePressed => Command::perform(
+                async {
+                    rfd::AsyncFileDialog::new()
+                        .add_filter("Images", &["png", "webp", "gif", "jpg", "jpeg"])
+                        .pick_file()
+                        .await
+                        .map(|h| h.path().to_path_buf())
+                },
+                Message::WatermarkImageSelected,
+            ),
+
+            Message::WatermarkImageSelected(None) => Command::none(),
+
+            Message::WatermarkImageSelected(Some(path)) => match crate::utils::load_image_robustly(&path) {
+                Ok(_) => {
+                    self.config.watermark_image = Some(path);
+                    Command::perform(async {}, |_| Message::RegeneratePreview)
+                }
+                Err(e) => {
+                    log::error!("Failed to load watermark image {}: {}", path.display(), e);
+                    self.modal_error = Some(e.to_string());
+                    Command::none()
+                }
+            },
+
+            Message::ClearWatermarkImage => {
+                self.config.watermark_image = None;
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
             Message::SelectImagePressed => {
                 return Command::perform(
                     async {
@@ -391,48 +734,35 @@ impl Application for App {
                 );
             }
 
-            Message::ImageSelected(result) => {
-                match result {
-                    Ok(path) => {
-                        log::info!("Image selected: {:?}", path);
-                        if path.extension().and_then(|s| s.to_str()) == Some("gif") {
-                            match self.decode_gif(&path) {
-                                Ok(_) => {
-                                    self.config.image_path = Some(path);
-                                    self.validation = None;
-                                    return Command::perform(async {}, |_| Message::RegeneratePreview);
-                                }
-                                Err(e) => {
-                                    log::error!("Failed to load GIF: {}", e);
-                                    self.modal_error = Some(format!("Failed to load GIF: {}", e));
-                                }
-                            }
-                        } else {
-                            match load_image_robustly(&path) {
-                                Ok(img) => {
-                                    self.gif_frames = None;
-                                    self.gif_frame_delays.clear();
-                                    self.current_frame_index = 0;
-                                    self.validation = Some(validate_user_image(&img));
-                                    self.config.image_path = Some(path);
-                                    return Command::perform(async {}, |_| Message::RegeneratePreview);
-                                }
-                                Err(e) => {
-                                    log::error!("Could not open image: {}", e);
-                                    self.modal_error = Some(format!("Could not open image: {}", e));
-                                }
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        log::warn!("Image selection failed: {}", e);
-                        self.modal_error = Some(e.to_string());
-                    }
+            Message::ImageSelected(result) => match result {
+                Ok(path) => {
+                    log::info!("Image selected: {:?}", path);
+                    self.load_image(path, true)
                 }
+                Err(e) => {
+                    log::warn!("Image selection failed: {}", e);
+                    self.modal_error = Some(e.to_string());
+                    Command::none()
+                }
+            },
+            Message::WatchImageToggled(enabled) => {
+                self.watch_image = enabled;
                 Command::none()
             }
+
+            Message::ImageFileChanged(path) => {
+                if self.config.image_path.as_ref() != Some(&path) {
+                    return Command::none();
+                }
+                log::info!("Image changed on disk, reloading {}", path.display());
+                self.load_image(path, false)
+            }
+
             Message::ResizeMethodChanged(method) => {
                 self.config.resize_method = method;
+                if let Some(validation) = &self.validation {
+                    self.validation = Some(validate_dimensions(validation.source_dimensions, method));
+                }
                 return Command::perform(async {}, |_| Message::RegeneratePreview);
             }
 
@@ -441,16 +771,81 @@ impl Application for App {
                 return Command::perform(async {}, |_| Message::RegeneratePreview);
             }
 
+            Message::HazardOpacityChanged(value) => {
+                self.config.hazard_opacity = value;
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
             Message::ClearHazard => {
                 self.config.selected_hazard = None;
                 return Command::perform(async {}, |_| Message::RegeneratePreview);
             }
 
+            Message::CycleClassType(step) => {
+                self.config.class_type = cycle(&ClassType::all(), self.config.class_type, step);
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::CycleHazard(step) => {
+                let hazards: Vec<Option<Hazard>> = std::iter::once(None)
+                    .chain(Hazard::all().into_iter().map(Some))
+                    .collect();
+                self.config.selected_hazard = cycle(&hazards, self.config.selected_hazard, step);
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
             Message::TextureToggled(enabled) => {
                 self.config.apply_texture = enabled;
                 return Command::perform(async {}, |_| Message::RegeneratePreview);
             }
 
+            Message::TextureSelected(name) => {
+                self.config.texture_name = name;
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::AddTextureLayer => {
+                self.config.texture_layers.push(TextureLayer::default());
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::RemoveTextureLayer(index) => {
+                if index < self.config.texture_layers.len() {
+                    self.config.texture_layers.remove(index);
+                }
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::TextureLayerNameChanged(index, name) => {
+                if let Some(layer) = self.config.texture_layers.get_mut(index) {
+                    layer.name = name;
+                }
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::MoveLayerUp(index) => self.swap_layers(index, index + 1),
+
+            Message::MoveLayerDown(index) => self.swap_layers(index, index.wrapping_sub(1)),
+
+            Message::TextureBlendChanged(blend) => {
+                self.config.texture_blend = blend;
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::TextureLayerBlendChanged(index, blend) => {
+                if let Some(layer) = self.config.texture_layers.get_mut(index) {
+                    layer.blend = blend;
+                }
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::TextureLayerOpacityChanged(index, opacity) => {
+                if let Some(layer) = self.config.texture_layers.get_mut(index) {
+                    layer.opacity = opacity.clamp(0.0, 1.0);
+                }
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
             Message::TextureOpacityChanged(value) => {
                 self.config.texture_opacity = value;
                 return Command::perform(async {}, |_| Message::RegeneratePreview);
@@ -483,10 +878,8 @@ impl Application for App {
                         return Command::perform(async {}, move |_| Message::ShowNotification(format!("SCP Number Font Size must be between 24.0 and 72.0. Adjusted to {}.", clamped_val)));
                     }
                     self.config.scp_number_font_size = clamped_val;
-                    return Command::perform(async {}, |_| Message::RegeneratePreview);
                 } else if value.is_empty() {
                     self.config.scp_number_font_size = 60.0;
-                    return Command::perform(async {}, |_| Message::RegeneratePreview);
                 }
                 Command::none()
             }
@@ -503,10 +896,8 @@ impl Application for App {
                         return Command::perform(async {}, move |_| Message::ShowNotification(format!("Object Class Font Size must be between 24.0 and 72.0. Adjusted to {}.", clamped_val)));
                     }
                     self.config.object_class_font_size = clamped_val;
-                    return Command::perform(async {}, |_| Message::RegeneratePreview);
                 } else if value.is_empty() {
                     self.config.object_class_font_size = 60.0;
-                    return Command::perform(async {}, |_| Message::RegeneratePreview);
                 }
                 Command::none()
             }
@@ -518,10 +909,8 @@ impl Application for App {
                         return Command::perform(async {}, move |_| Message::ShowNotification(format!("Texture Opacity must be between 0.0 and 1.0. Adjusted to {}.", clamped_val)));
                     }
                     self.config.texture_opacity = clamped_val;
-                    return Command::perform(async {}, |_| Message::RegeneratePreview);
                 } else if value.is_empty() {
                     self.config.texture_opacity = 0.3;
-                    return Command::perform(async {}, |_| Message::RegeneratePreview);
                 }
                 Command::none()
             }
@@ -533,10 +922,8 @@ impl Application for App {
                         return Command::perform(async {}, move |_| Message::ShowNotification(format!("Brightness must be between -1.0 and 1.0. Adjusted to {}.", clamped_val)));
                     }
                     self.config.brightness = clamped_val;
-                    return Command::perform(async {}, |_| Message::RegeneratePreview);
                 } else if value.is_empty() {
                     self.config.brightness = 0.0;
-                    return Command::perform(async {}, |_| Message::RegeneratePreview);
                 }
                 Command::none()
             }
@@ -548,10 +935,8 @@ impl Application for App {
                         return Command::perform(async {}, move |_| Message::ShowNotification(format!("Contrast must be between 0.0 and 2.0. Adjusted to {}.", clamped_val)));
                     }
                     self.config.contrast = clamped_val;
-                    return Command::perform(async {}, |_| Message::RegeneratePreview);
                 } else if value.is_empty() {
                     self.config.contrast = 1.0;
-                    return Command::perform(async {}, |_| Message::RegeneratePreview);
                 }
                 Command::none()
             }
@@ -559,10 +944,8 @@ impl Application for App {
             Message::ScpTextOffsetXChanged(value) => {
                 if let Ok(val) = value.parse::<f32>() {
                     self.config.scp_text_offset.0 = val;
-                    return Command::perform(async {}, |_| Message::RegeneratePreview);
                 } else if value.is_empty() {
                     self.config.scp_text_offset.0 = 2.0;
-                    return Command::perform(async {}, |_| Message::RegeneratePreview);
                 }
                 Command::none()
             }
@@ -570,10 +953,8 @@ impl Application for App {
             Message::ScpTextOffsetYChanged(value) => {
                 if let Ok(val) = value.parse::<f32>() {
                     self.config.scp_text_offset.1 = val;
-                    return Command::perform(async {}, |_| Message::RegeneratePreview);
                 } else if value.is_empty() {
                     self.config.scp_text_offset.1 = -7.0;
-                    return Command::perform(async {}, |_| Message::RegeneratePreview);
                 }
                 Command::none()
             }
@@ -581,10 +962,8 @@ impl Application for App {
             Message::ClassTextOffsetXChanged(value) => {
                 if let Ok(val) = value.parse::<f32>() {
                     self.config.class_text_offset.0 = val;
-                    return Command::perform(async {}, |_| Message::RegeneratePreview);
                 } else if value.is_empty() {
                     self.config.class_text_offset.0 = 2.0;
-                    return Command::perform(async {}, |_| Message::RegeneratePreview);
                 }
                 Command::none()
             }
@@ -592,22 +971,168 @@ impl Application for App {
             Message::ClassTextOffsetYChanged(value) => {
                 if let Ok(val) = value.parse::<f32>() {
                     self.config.class_text_offset.1 = val;
-                    return Command::perform(async {}, |_| Message::RegeneratePreview);
                 } else if value.is_empty() {
                     self.config.class_text_offset.1 = -7.0;
-                    return Command::perform(async {}, |_| Message::RegeneratePreview);
                 }
                 Command::none()
             }
 
-            Message::ScpTextColorChanged(color) => {
-                self.config.scp_text_color = color.into();
-                return Command::perform(async {}, |_| Message::RegeneratePreview);
+            Message::ScpTextColorChanged(value) => {
+                let parsed = color::parse_hex(&value).ok();
+                self.scp_color_draft = Some(value);
+                if let Some(color) = parsed {
+                    self.config.scp_text_color = color.into();
+                }
+                Command::none()
             }
 
-            Message::ClassTextColorChanged(color) => {
-                self.config.class_text_color = color.into();
-                return Command::perform(async {}, |_| Message::RegeneratePreview);
+            Message::ClassTextColorChanged(value) => {
+                let parsed = color::parse_hex(&value).ok();
+                self.class_color_draft = Some(value);
+                if let Some(color) = parsed {
+                    self.config.class_text_color = color.into();
+                }
+                Command::none()
+            }
+
+            Message::ScpTextColorSubmitted => {
+                self.scp_color_draft = None;
+                self.remember_color(self.config.scp_text_color.into());
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::ClassTextColorSubmitted => {
+                self.class_color_draft = None;
+                self.remember_color(self.config.class_text_color.into());
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::FlattenToggled(enabled) => {
+                self.config.flatten_to = enabled.then(|| Color::WHITE.into());
+                self.flatten_color_draft = None;
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::FlattenColorChanged(value) => {
+                let parsed = color::parse_hex(&value).ok();
+                self.flatten_color_draft = Some(value);
+                if let Some(color) = parsed {
+                    self.config.flatten_to = Some(color.into());
+                }
+                Command::none()
+            }
+
+            Message::FlattenColorSubmitted => {
+                self.flatten_color_draft = None;
+                if let Some(color) = self.config.flatten_to {
+                    self.remember_color(color.into());
+                }
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::LetterboxColorChanged(value) => {
+                let parsed = color::parse_hex(&value).ok();
+                self.letterbox_color_draft = Some(value);
+                if let Some(color) = parsed {
+                    self.config.letterbox_color = color.into();
+                }
+                Command::none()
+            }
+
+            Message::LetterboxColorSubmitted => {
+                self.letterbox_color_draft = None;
+                self.remember_color(self.config.letterbox_color.into());
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::BackgroundColorChanged(value) => {
+                let parsed = color::parse_hex(&value).ok();
+                self.background_color_draft = Some(value);
+                if let Some(color) = parsed {
+                    self.config.background_color = color.into();
+                }
+                Command::none()
+            }
+
+            Message::BackgroundColorSubmitted => {
+                self.background_color_draft = None;
+                self.remember_color(self.config.background_color.into());
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::BackgroundFillChanged(kind) => {
+                self.config.background_fill = self.config.background_fill.with_kind(kind);
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::FillColorChanged(value) => {
+                let parsed = color::parse_hex(&value).ok();
+                self.fill_color_draft = Some(value);
+                if let Some(color) = parsed {
+                    self.config.background_fill = self.config.background_fill.with_start(color.into());
+                }
+                Command::none()
+            }
+
+            Message::FillColorSubmitted => {
+                self.fill_color_draft = None;
+                self.remember_color(self.config.background_fill.parts().0.into());
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::FillEndColorChanged(value) => {
+                let parsed = color::parse_hex(&value).ok();
+                self.fill_end_color_draft = Some(value);
+                if let Some(color) = parsed {
+                    self.config.background_fill = self.config.background_fill.with_end(color.into());
+                }
+                Command::none()
+            }
+
+            Message::FillEndColorSubmitted => {
+                self.fill_end_color_draft = None;
+                self.remember_color(self.config.background_fill.parts().1.into());
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::FillAngleChanged(angle) => {
+                self.config.background_fill = self.config.background_fill.with_angle(angle);
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::RecentColorPicked(target, color) => {
+                match target {
+                    ColorTarget::ScpText => {
+                        self.config.scp_text_color = color.into();
+                        self.scp_color_draft = None;
+                    }
+                    ColorTarget::ClassText => {
+                        self.config.class_text_color = color.into();
+                        self.class_color_draft = None;
+                    }
+                    ColorTarget::Flatten => {
+                        self.config.flatten_to = Some(color.into());
+                        self.flatten_color_draft = None;
+                    }
+                    ColorTarget::Letterbox => {
+                        self.config.letterbox_color = color.into();
+                        self.letterbox_color_draft = None;
+                    }
+                    ColorTarget::Background => {
+                        self.config.background_color = color.into();
+                        self.background_color_draft = None;
+                    }
+                    ColorTarget::FillStart => {
+                        self.config.background_fill = self.config.background_fill.with_start(color.into());
+                        self.fill_color_draft = None;
+                    }
+                    ColorTarget::FillEnd => {
+                        self.config.background_fill = self.config.background_fill.with_end(color.into());
+                        self.fill_end_color_draft = None;
+                    }
+                }
+                self.remember_color(color);
+                Command::perform(async {}, |_| Message::RegeneratePreview)
             }
 
             Message::ResetText => {
@@ -615,11 +1140,90 @@ impl Application for App {
                 self.config.class_text_offset = (2.0, -7.0);
                 self.config.scp_text_color = Color::BLACK.into();
                 self.config.class_text_color = Color::BLACK.into();
+                self.scp_color_draft = None;
+                self.class_color_draft = None;
                 self.config.scp_number_font_size = 60.0;
                 self.config.object_class_font_size = 60.0;
                 return Command::perform(async {}, |_| Message::RegeneratePreview);
             }
 
+            Message::ResetBurn => {
+                let defaults = LabelConfig::default();
+                self.config.burn_type = defaults.burn_type;
+                self.config.burn_amount = defaults.burn_amount;
+                self.config.burn_scale = defaults.burn_scale;
+                self.config.burn_detail = defaults.burn_detail;
+                self.config.burn_edge_softness = defaults.burn_edge_softness;
+                self.config.burn_irregularity = defaults.burn_irregularity;
+                self.config.burn_char = defaults.burn_char;
+                self.config.burn_seed = defaults.burn_seed;
+                self.config.burn_scale_multiplier = defaults.burn_scale_multiplier;
+                self.config.burn_detail_blend = defaults.burn_detail_blend;
+                self.config.burn_turbulence_freq = defaults.burn_turbulence_freq;
+                self.config.burn_turbulence_strength = defaults.burn_turbulence_strength;
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::ExportBurnMask => {
+                let config = self.config.clone();
+                Command::perform(
+                    async move {
+                        let Some(file) = rfd::AsyncFileDialog::new()
+                            .set_file_name("burn_mask.png")
+                            .add_filter("PNG", &["png"])
+                            .save_file()
+                            .await
+                        else {
+                            return Message::ShowNotification("Save cancelled".to_string());
+                        };
+
+                        match crate::core::label_composer::save_burn_mask(&config, file.path()) {
+                            Ok(_) => Message::ShowNotification("Burn mask exported successfully!".to_string()),
+                            Err(e) => {
+                                log::error!("Burn mask export failed: {}", e);
+                                Message::ShowNotification(format!("Export failed: {}", e))
+                            }
+                        }
+                    },
+                    |msg| msg,
+                )
+            }
+
+            Message::CopyEffects => {
+                match serde_json::to_string_pretty(&EffectSettings::from_config(&self.config)) {
+                    Ok(json) => {
+                        self.notification_message = Some("Effects copied to clipboard.".to_string());
+                        iced::clipboard::write(json)
+                    }
+                    Err(e) => {
+                        log::error!("Failed to serialize effect settings: {}", e);
+                        self.modal_error = Some(e.to_string());
+                        Command::none()
+                    }
+                }
+            }
+
+            Message::PasteEffects => iced::clipboard::read(Message::EffectsPasted),
+
+            Message::EffectsPasted(contents) => {
+                let Some(json) = contents else {
+                    self.notification_message = Some("Clipboard is empty.".to_string());
+                    return Command::none();
+                };
+                match serde_json::from_str::<EffectSettings>(&json) {
+                    Ok(effects) => {
+                        effects.apply_to(&mut self.config);
+                        self.notification_message = Some("Effects pasted.".to_string());
+                        Command::perform(async {}, |_| Message::RegeneratePreview)
+                    }
+                    Err(e) => {
+                        log::warn!("Clipboard does not contain effect settings: {}", e);
+                        self.notification_message = Some("Clipboard does not contain effect settings.".to_string());
+                        Command::none()
+                    }
+                }
+            }
+
             Message::SaveConfig => {
                 let config = self.config.clone();
                 return Command::perform(
@@ -651,6 +1255,9 @@ impl Application for App {
                 match result {
                     Ok(config) => {
                         self.config = config;
+                        self.rebuild_composer();
+                        self.user_image = None;
+                        self.clear_input_drafts();
                         return Command::perform(async {}, |_| Message::RegeneratePreview);
                     }
                     Err(e) => {
@@ -659,13 +1266,82 @@ impl Application for App {
                 }
             }
 
-            Message::ResolutionChanged(res) => {
-                self.config.output_resolution = res;
-                Command::none()
+            Message::SupersampleChanged(factor) => {
+                self.config.supersample = factor;
+                Command::perform(async {}, |_| Message::RegeneratePreview)
             }
 
-            Message::FormatChanged(format) => {
+            Message::BleedChanged(bleed) => {
+                self.config.bleed = bleed.min(MAX_BLEED);
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::PrintMarksToggled(enabled) => {
+                self.config.print_marks = enabled;
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::NativeResolutionToggled(enabled) => {
+                self.config.native_resolution = enabled;
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::ResolutionChanged(res) => {
+                self.config.output_resolution = res;
+                self.resolution_draft = None;
+                Command::none()
+            }
+
+            Message::ResolutionWidthChanged(width) => {
+                let (_, height) = self.resolution_inputs();
+                let height = if self.aspect_locked { width.clone() } else { height };
+                self.apply_resolution_draft(width, height);
+                Command::none()
+            }
+
+            Message::ResolutionHeightChanged(height) => {
+                let (width, _) = self.resolution_inputs();
+                let width = if self.aspect_locked { height.clone() } else { width };
+                self.apply_resolution_draft(width, height);
+                Command::none()
+            }
+
+            Message::AspectLockToggled(locked) => {
+                self.aspect_locked = locked;
+                if locked {
+                    self.resolution_draft = None;
+                }
+                Command::none()
+            }
+
+            Message::FormatChanged(format) => {
                 self.config.output_format = format;
+                if self.preview_as_export {
+                    return Command::perform(async {}, |_| Message::RegeneratePreview);
+                }
+                Command::none()
+            }
+
+            Message::JpegSubsamplingChanged(subsampling) => {
+                self.config.jpeg_subsampling = subsampling;
+                if self.preview_as_export {
+                    return Command::perform(async {}, |_| Message::RegeneratePreview);
+                }
+                Command::none()
+            }
+
+            Message::PreviewAsExportToggled(enabled) => {
+                self.preview_as_export = enabled;
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::WebpLosslessToggled(enabled) => {
+                self.config.webp_lossless = enabled;
+                Command::none()
+            }
+
+            Message::OptimizePngToggled(enabled) => {
+                self.config.optimize_png = enabled;
                 Command::none()
             }
 
@@ -677,6 +1353,7 @@ impl Application for App {
                     let composer = composer.clone();
                     let gif_frames = self.gif_frames.clone();
                     let gif_frame_delays = self.gif_frame_delays.clone();
+                    let user_image = self.current_user_image();
                     
                     return Command::perform(
                         async move {
@@ -684,53 +1361,83 @@ impl Application for App {
                                 rfd::AsyncFileDialog::new()
                                     .set_file_name("scp_label.gif")
                                     .add_filter("GIF", &["gif"])
-                                    .add_filter("PNG", &["png"])
+                                    .add_filter("Animated PNG", &["png", "apng"])
+                                    .add_filter("Animated WebP", &["webp"])
                                     .add_filter("JPEG", &["jpg", "jpeg"])
                             } else {
                                 rfd::AsyncFileDialog::new()
                                     .set_file_name("scp_label.png")
                                     .add_filter("PNG", &["png"])
                                     .add_filter("JPEG", &["jpg", "jpeg"])
+                                    .add_filter("WebP", &["webp"])
+                                    .add_filter("SVG", &["svg"])
                             };
                             
                             if let Some(file) = dialog.save_file().await {
                                 let path = file.path();
                                 let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("png");
                                 
-                                if extension == "gif" && gif_frames.is_some() {
-                                    match Self::export_gif_static(
-                                        &gif_frames.unwrap(),
-                                        &gif_frame_delays,
-                                        &config,
-                                        &assets,
-                                        &composer,
-                                        path
-                                    ) {
-                                        Ok(_) => Message::ShowNotification("GIF exported successfully!".to_string()),
+                                let animated_export = match (&gif_frames, extension) {
+                                    (Some(frames), "gif") => Some(
+                                        Self::export_gif_static(frames, &gif_frame_delays, &config, &assets, &composer, path)
+                                            .map(|_| "GIF"),
+                                    ),
+                                    (Some(frames), "png" | "apng") => Some(
+                                        Self::export_apng_static(frames, &gif_frame_delays, &config, &assets, &composer, path)
+                                            .map(|_| "APNG"),
+                                    ),
+                                    (Some(frames), "webp") => Some(
+                                        Self::export_webp_static(frames, &gif_frame_delays, &config, &assets, &composer, path)
+                                            .map(|_| "WebP"),
+                                    ),
+                                    (_, "svg") => Some(
+                                        crate::core::svg_export::compose_svg(&composer, &config, &assets, user_image.as_ref())
+                                            .and_then(|svg| std::fs::write(path, svg).map_err(|e| crate::utils::LabelError::Io(e.to_string())))
+                                            .map(|_| "SVG"),
+                                    ),
+                                    _ => None,
+                                };
+
+                                if let Some(result) = animated_export {
+                                    match result {
+                                        Ok(kind) => {
+                                            Self::record_export(path, kind, &config);
+                                            Message::ShowNotification(format!("{} exported successfully!", kind))
+                                        }
                                         Err(e) => {
-                                            log::error!("GIF export failed: {}", e);
+                                            log::error!("{} export failed: {}", extension.to_uppercase(), e);
                                             Message::ShowNotification(format!("Export failed: {}", e))
                                         },
                                     }
                                 } else {
-                                    match composer.compose(&config, &assets, None) {
+                                    match composer.compose(&config, &assets, user_image.as_ref()).map(|img| flatten_for_export(img, &config)) {
                                         Ok(img) => {
                                             let output_format = config.output_format;
                                             let output_quality = config.output_quality;
 
                                             let write_result = match output_format {
-                                                OutputFormat::Png => img.save(path).map_err(|e| crate::utils::LabelError::ImageSaving(e.to_string())),
+                                                OutputFormat::Png | OutputFormat::Svg if config.optimize_png => {
+                                                    crate::core::png_optimizer::encode_png(&img, true)
+                                                        .and_then(|bytes| std::fs::write(path, bytes).map_err(|e| crate::utils::LabelError::Io(e.to_string())))
+                                                }
+                                                OutputFormat::Png | OutputFormat::Svg => img.save(path).map_err(|e| crate::utils::LabelError::ImageSaving(e.to_string())),
                                                 OutputFormat::Jpeg => {
-                                                    let mut buf = std::io::Cursor::new(Vec::new());
-                                                    let mut encoder = JpegEncoder::new_with_quality(&mut buf, output_quality);
-                                                    match encoder.encode_image(&img) {
-                                                        Ok(_) => std::fs::write(path, buf.into_inner()).map_err(|e| crate::utils::LabelError::Io(e.to_string())),
-                                                        Err(e) => Err(crate::utils::LabelError::ImageSaving(e.to_string()))
-                                                    }
+                                                    crate::core::jpeg::encode_jpeg(&img, output_quality, config.jpeg_subsampling)
+                                                        .and_then(|bytes| std::fs::write(path, bytes).map_err(|e| crate::utils::LabelError::Io(e.to_string())))
+                                                }
+                                                OutputFormat::Webp => {
+                                                    crate::core::webp::encode_webp(&img, output_quality, config.webp_lossless)
+                                                        .and_then(|bytes| std::fs::write(path, bytes).map_err(|e| crate::utils::LabelError::Io(e.to_string())))
                                                 }
                                             };
 
                                             if write_result.is_ok() {
+                                                let kind = match output_format {
+                                                    OutputFormat::Jpeg => "JPEG".to_string(),
+                                                    OutputFormat::Webp => "WebP".to_string(),
+                                                    _ => extension.to_uppercase(),
+                                                };
+                                                Self::record_export(path, &kind, &config);
                                                 Message::ShowNotification("Label exported successfully!".to_string())
                                             } else {
                                                 let err_msg = format!("Failed to save: {}", write_result.unwrap_err());
@@ -756,34 +1463,313 @@ impl Application for App {
             }
 
             Message::RegeneratePreview => {
+                self.check_legibility();
                 if let (Some(assets), Some(composer)) = (&self.assets, &self.composer) {
                     let config = self.config.clone();
                     let assets = assets.clone();
                     let composer = composer.clone();
                     
-                    let image_override = self.gif_frames.as_ref().map(|frames| {
-                        let frame = &frames[self.current_frame_index % frames.len()];
-                        DynamicImage::ImageRgba8(frame.clone())
+                    let user_image = self.current_user_image();
+                    let preview_as_export = self.preview_as_export;
+                    let show_image_region = self.show_image_region;
+                    let rotation = self.preview_rotation;
+
+                    let pinned = self.pinned_class.map(|class_type| {
+                        let config = LabelConfig { class_type, ..config.clone() };
+                        let (assets, composer, user_image) = (assets.clone(), composer.clone(), user_image.clone());
+                        Command::perform(
+                            async move {
+                                Self::render_preview(
+                                    &composer,
+                                    &config,
+                                    &assets,
+                                    user_image.as_ref(),
+                                    preview_as_export,
+                                    show_image_region,
+                                    rotation,
+                                )
+                            },
+                            Message::PinnedPreviewGenerated,
+                        )
                     });
 
-                    return Command::perform(
+                    let live = Command::perform(
                         async move {
-                            let img = composer.compose(&config, &assets, image_override.as_ref())?;
-
-                            let mut buffer = Vec::new();
-                            if img.write_to(&mut std::io::Cursor::new(&mut buffer), image::ImageFormat::Png).is_ok() {
-                                Ok(buffer)
-                            } else {
-                                Err(crate::utils::LabelError::ImageProcessing("Failed to encode preview".to_string()))
-                            }
+                            Self::render_preview(
+                                    &composer,
+                                    &config,
+                                    &assets,
+                                    user_image.as_ref(),
+                                    preview_as_export,
+                                    show_image_region,
+                                    rotation,
+                                )
                         },
                         Message::PreviewGenerated,
                     );
+                    self.previews_in_flight += 1;
+                    return Command::batch(std::iter::once(live).chain(pinned));
+                }
+                Command::none()
+            }
+
+            Message::PinnedClassSelected(class_type) => {
+                self.pinned_class = class_type;
+                self.pinned_preview_handle = None;
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::ClassSeriesNamesToggled(enabled) => {
+                self.class_series_use_names = enabled;
+                Command::none()
+            }
+
+            Message::ExportClassSeriesPressed => {
+                let (Some(assets), Some(composer)) = (&self.assets, &self.composer) else {
+                    return Command::none();
+                };
+                let configs = batch::class_series(&self.config, self.class_series_use_names);
+                let (assets, composer) = (assets.clone(), composer.clone());
+                Command::perform(
+                    async move {
+                        let dir = rfd::AsyncFileDialog::new()
+                            .pick_folder()
+                            .await
+                            .ok_or_else(|| LabelError::Io("Save cancelled".to_string()))?;
+                        for config in &configs {
+                            let path = dir.path().join(batch::class_series_file_name(config));
+                            save_label(&composer, &assets, config, &path)?;
+                            let kind = match config.output_format {
+                                OutputFormat::Webp => "WebP".to_string(),
+                                format => format.to_string().to_uppercase(),
+                            };
+                            Self::record_export(&path, &kind, config);
+                        }
+                        Ok(configs.len())
+                    },
+                    Message::ClassSeriesExported,
+                )
+            }
+
+            Message::ClassSeriesExported(result) => {
+                let message = match result {
+                    Ok(count) => format!("Exported {} class labels", count),
+                    Err(LabelError::Io(msg)) if msg == "Save cancelled" => return Command::none(),
+                    Err(e) => {
+                        log::error!("Class series export failed: {}", e);
+                        format!("Class series export failed: {}", e)
+                    }
+                };
+                Command::perform(async {}, move |_| Message::ShowNotification(message))
+            }
+
+            Message::ShowExportHistory => {
+                let mut history = export_history::load_history();
+                history.reverse();
+                self.export_history = Some(history);
+                Command::none()
+            }
+
+            Message::CloseExportHistory => {
+                self.export_history = None;
+                Command::none()
+            }
+
+            Message::ReopenExportConfig(index) => {
+                let config = self
+                    .export_history
+                    .as_ref()
+                    .and_then(|history| history.get(index))
+                    .and_then(|record| record.config.clone());
+                match config {
+                    Some(config) => {
+                        self.export_history = None;
+                        Command::perform(async {}, move |_| Message::ConfigLoaded(Ok(config)))
+                    }
+                    None => Command::perform(async {}, |_| {
+                        Message::ShowNotification("No config was stored for this export".to_string())
+                    }),
+                }
+            }
+
+            Message::OpenExportFolder(index) => {
+                let folder = self
+                    .export_history
+                    .as_ref()
+                    .and_then(|history| history.get(index))
+                    .and_then(|record| record.output_path.parent().map(|dir| dir.to_path_buf()));
+                if let Some(folder) = folder {
+                    if let Err(e) = Self::open_folder(&folder) {
+                        log::error!("Failed to open {}: {}", folder.display(), e);
+                        self.notification_message = Some(format!("Failed to open folder: {}", e));
+                    }
+                }
+                Command::none()
+            }
+
+            Message::LoadBatch => {
+                let base = self.config.clone();
+                Command::perform(
+                    async move {
+                        let handle = rfd::AsyncFileDialog::new()
+                            .add_filter("Batch list", &["csv", "json"])
+                            .pick_file()
+                            .await
+                            .ok_or_else(|| LabelError::Io("Load cancelled".to_string()))?;
+                        batch::load_batch(handle.path(), &base)
+                    },
+                    Message::BatchLoaded,
+                )
+            }
+
+            Message::PreviewFontPressed => {
+                let color = image::Rgba(ui::theme::palette().text_primary.into_rgba8());
+                Command::perform(
+                    async move {
+                        let handle = rfd::AsyncFileDialog::new()
+                            .add_filter("Fonts", &["ttf", "otf"])
+                            .pick_file()
+                            .await
+                            .ok_or_else(|| LabelError::Io("Load cancelled".to_string()))?;
+                        let renderer = TextRenderer::from_font_data(handle.read().await)?;
+                        let sample = renderer.render_sample(FONT_SAMPLE_TEXT, FONT_SAMPLE_SIZE, color);
+                        Ok(FontPreview {
+                            path: handle.path().to_path_buf(),
+                            name: handle.file_name(),
+                            sample: iced::widget::image::Handle::from_pixels(
+                                sample.width(),
+                                sample.height(),
+                                sample.into_raw(),
+                            ),
+                        })
+                    },
+                    Message::FontPreviewRendered,
+                )
+            }
+
+            Message::FontPreviewRendered(result) => match result {
+                Ok(preview) => {
+                    self.font_preview = Some(preview);
+                    Command::none()
+                }
+                Err(LabelError::Io(msg)) if msg == "Load cancelled" => Command::none(),
+                Err(e) => {
+                    self.font_preview = None;
+                    Command::perform(async {}, move |_| Message::ShowNotification(format!("Could not preview font: {}", e)))
+                }
+            },
+
+            Message::SelectFontPressed => Command::perform(
+                async {
+                    rfd::AsyncFileDialog::new()
+                        .add_filter("Fonts", &["ttf", "otf"])
+                        .pick_file()
+                        .await
+                        .map(|handle| handle.path().to_path_buf())
+                },
+                Message::FontSelected,
+            ),
+
+            Message::FontSelected(None) => Command::none(),
+
+            Message::FontSelected(Some(path)) => match LabelComposer::with_font_file(&path) {
+                Ok(composer) => {
+                    log::info!("Using font {}", path.display());
+                    self.composer = Some(composer);
+                    self.config.font_path = Some(path);
+                    Command::perform(async {}, |_| Message::RegeneratePreview)
+                }
+                Err(e) => {
+                    log::error!("Failed to load font {}: {}", path.display(), e);
+                    self.modal_error = Some(e.to_string());
+                    Command::none()
+                }
+            },
+
+            Message::ResetFontPressed => {
+                self.config.font_path = None;
+                match LabelComposer::new() {
+                    Ok(composer) => self.composer = Some(composer),
+                    Err(e) => self.modal_error = Some(e.to_string()),
+                }
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::BatchLoaded(result) => match result {
+                Ok(entries) => {
+                    let shown = entries.len().min(BATCH_PREVIEW_LIMIT);
+                    self.batch_preview = Some(BatchPreview {
+                        entries,
+                        thumbnails: vec![None; shown],
+                    });
+                    self.render_batch_thumbnail(0)
+                }
+                Err(LabelError::Io(msg)) if msg == "Load cancelled" => Command::none(),
+                Err(e) => {
+                    self.modal_error = Some(format!("Failed to load batch: {}", e));
+                    Command::none()
+                }
+            },
+
+            Message::BatchThumbnailGenerated(index, result) => {
+                let Some(batch) = &mut self.batch_preview else {
+                    return Command::none();
+                };
+                match result {
+                    Ok(data) => {
+                        if let Some(slot) = batch.thumbnails.get_mut(index) {
+                            *slot = Some(iced::widget::image::Handle::from_memory(data));
+                        }
+                    }
+                    Err(e) => log::warn!("Batch entry {} failed to render: {}", index + 1, e),
+                }
+                self.render_batch_thumbnail(index + 1)
+            }
+
+            Message::CloseBatchPreview => {
+                self.batch_preview = None;
+                Command::none()
+            }
+
+            Message::PreviewBackdropSelected(backdrop) => {
+                self.preview_backdrop = backdrop;
+                Command::none()
+            }
+
+            Message::UiThemeSelected(ui_theme) => {
+                self.ui_theme = ui_theme;
+                ui::theme::set_ui_theme(ui_theme);
+                self.save_ui_state();
+                Command::none()
+            }
+
+            Message::ImageRegionToggled(enabled) => {
+                self.show_image_region = enabled;
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::PreviewPixelatedToggled(enabled) => {
+                self.preview_pixelated = enabled;
+                Command::none()
+            }
+
+            Message::WindowResized(width) => {
+                self.window_width = width;
+                Command::none()
+            }
+
+            Message::PinnedPreviewGenerated(result) => {
+                match result {
+                    Ok(data) => {
+                        self.pinned_preview_handle = Some(iced::widget::image::Handle::from_memory(data));
+                    }
+                    Err(e) => log::error!("Pinned preview generation failed: {}", e),
                 }
                 Command::none()
             }
 
             Message::PreviewGenerated(result) => {
+                self.previews_in_flight = self.previews_in_flight.saturating_sub(1);
                 match result {
                     Ok(data) => {
                         self.preview_handle = Some(iced::widget::image::Handle::from_memory(data));
@@ -826,27 +1812,79 @@ impl Application for App {
             }
 
             Message::ZoomInPressed => {
-                self.zoom_factor = (self.zoom_factor + 0.1).min(4.0);
+                self.zoom_factor = (self.zoom_factor + 0.1).min(ui::preview_panel::MAX_ZOOM);
+                self.preview_offset = ui::preview_panel::clamp_offset(self.preview_offset, self.zoom_factor);
                 Command::perform(async {}, |_| Message::RegeneratePreview)
             }
 
             Message::ZoomOutPressed => {
-                self.zoom_factor = (self.zoom_factor - 0.1).max(0.5);
+                self.zoom_factor = (self.zoom_factor - 0.1).max(ui::preview_panel::MIN_ZOOM);
+                self.preview_offset = ui::preview_panel::clamp_offset(self.preview_offset, self.zoom_factor);
                 Command::perform(async {}, |_| Message::RegeneratePreview)
             }
 
             Message::ZoomResetPressed => {
                 self.zoom_factor = 1.0;
+                self.preview_offset = (0.0, 0.0);
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+
+            Message::PreviewHovered(position) => {
+                self.hover_position = position;
+                Command::none()
+            }
+
+            Message::PreviewDragged(dx, dy) => {
+                self.preview_offset = ui::preview_panel::clamp_offset(
+                    (self.preview_offset.0 + dx, self.preview_offset.1 + dy),
+                    self.zoom_factor,
+                );
+                Command::none()
+            }
+
+            Message::RotatePreviewPressed => {
+                self.preview_rotation = (self.preview_rotation + 90) % 360;
+                self.hover_position = None;
                 Command::perform(async {}, |_| Message::RegeneratePreview)
             }
 
+            Message::ZoomFitPressed => {
+                container::visible_bounds(ui::preview_panel::area_id()).map(Message::PreviewAreaMeasured)
+            }
+
+            Message::PreviewAreaMeasured(bounds) => {
+                match bounds {
+                    Some(bounds) => {
+                        self.zoom_factor = ui::preview_panel::fit_zoom(bounds.size(), self.pinned_class.is_some());
+                        self.preview_offset = (0.0, 0.0);
+                    }
+                    None => log::warn!("Preview area is not visible, cannot fit zoom"),
+                }
+                Command::none()
+            }
+
             // Stubs idk mate
-            Message::ScpNumberFontSizeSubmitted(_) | Message::ObjectClassFontSizeSubmitted(_) => Command::none(),
-            Message::OpacitySubmitted(_) | Message::BrightnessSubmitted(_) | Message::ContrastSubmitted(_) => Command::none(),
-            Message::ScpTextOffsetXSubmitted(_) | Message::ScpTextOffsetYSubmitted(_) => Command::none(),
-            Message::ClassTextOffsetXSubmitted(_) | Message::ClassTextOffsetYSubmitted(_) => Command::none(),
-            Message::ScpTextColorSubmitted(_) | Message::ClassTextColorSubmitted(_) => Command::none(),
-            Message::ScrollZoom(_delta) => Command::none(),
+            Message::ScpNumberFontSizeSubmitted(_)
+            | Message::ObjectClassFontSizeSubmitted(_)
+            | Message::OpacitySubmitted(_)
+            | Message::BrightnessSubmitted(_)
+            | Message::ContrastSubmitted(_)
+            | Message::ScpTextOffsetXSubmitted(_)
+            | Message::ScpTextOffsetYSubmitted(_)
+            | Message::ClassTextOffsetXSubmitted(_)
+            | Message::ClassTextOffsetYSubmitted(_)
+            | Message::LineSpacingSubmitted
+            | Message::BurnAmountSubmitted => Command::perform(async {}, |_| Message::RegeneratePreview),
+            Message::ScrollZoom(lines, cursor) => {
+                let (zoom_factor, preview_offset) =
+                    ui::preview_panel::scroll_zoom(self.zoom_factor, self.preview_offset, lines, cursor);
+                if zoom_factor == self.zoom_factor {
+                    return Command::none();
+                }
+                self.zoom_factor = zoom_factor;
+                self.preview_offset = preview_offset;
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
 
             
         }
@@ -855,7 +1893,7 @@ impl Application for App {
     }
 
     fn subscription(&self) -> Subscription<Message> {
-        if self.gif_frames.is_some() && self.gif_playing {
+        let playback = if self.gif_frames.is_some() && self.gif_playing {
             let delay = if self.current_frame_index < self.gif_frame_delays.len() {
                 self.gif_frame_delays[self.current_frame_index].max(10)
             } else {
@@ -865,7 +1903,33 @@ impl Application for App {
                 .map(|_| Message::AdvanceFrame)
         } else {
             Subscription::none()
-        }
+        };
+
+        let shortcuts = iced::keyboard::on_key_press(|key, _modifiers| match key.as_ref() {
+            iced::keyboard::Key::Character("[") => Some(Message::CycleClassType(-1)),
+            iced::keyboard::Key::Character("]") => Some(Message::CycleClassType(1)),
+            iced::keyboard::Key::Character("{") => Some(Message::CycleHazard(-1)),
+            iced::keyboard::Key::Character("}") => Some(Message::CycleHazard(1)),
+            _ => None,
+        });
+
+        let loader = if self.loading {
+            Self::asset_loader(self.texture_pack_dir.clone())
+        } else {
+            Subscription::none()
+        };
+
+        let watcher = match (&self.config.image_path, self.watch_image) {
+            (Some(path), true) => Self::image_watcher(path.clone()),
+            _ => Subscription::none(),
+        };
+
+        let resizes = iced::event::listen_with(|event, _status| match event {
+            iced::Event::Window(_, iced::window::Event::Resized { width, .. }) => Some(Message::WindowResized(width)),
+            _ => None,
+        });
+
+        Subscription::batch([playback, shortcuts, loader, watcher, resizes])
     }
 
     fn view(&self) -> Element<Message> {
@@ -894,8 +1958,30 @@ impl Application for App {
                 }).into();
         }
 
+        if let Some(batch) = &self.batch_preview {
+            return Self::batch_preview_view(batch);
+        }
+
+        if let Some(history) = &self.export_history {
+            return Self::export_history_view(history);
+        }
+
         if self.loading {
-            return container(text("Loading assets..."))
+            let (stage, fraction) = match &self.load_progress {
+                Some(progress) => (
+                    format!("{} ({}/{})...", progress.stage, progress.current, progress.total),
+                    progress.current as f32 / progress.total.max(1) as f32,
+                ),
+                None => ("Loading assets...".to_string(), 0.0),
+            };
+            return container(
+                column![
+                    text(stage),
+                    iced::widget::progress_bar(0.0..=1.0, fraction).width(300).height(8),
+                ]
+                .spacing(10)
+                .align_items(iced::Alignment::Center),
+            )
                 .width(Length::Fill)
                 .height(Length::Fill)
                 .center_x()
@@ -903,27 +1989,76 @@ impl Application for App {
                 .into();
         }
 
-        let input_panel = ui::input_panel::view(&self.config, &self.validation, self.advanced_burn_settings_visible);
-        
-        let preview_panel = ui::preview_panel::view(
-            &self.preview_handle,
-            self.zoom_factor,
-            self.gif_frames.is_some(),
-            self.gif_playing,
-            self.current_frame_index,
-            self.gif_frames.as_ref().map(|f| f.len()).unwrap_or(0),
+        let input_panel = ui::input_panel::view(
+            &self.config,
+            ui::input_panel::ViewState {
+                validation: &self.validation,
+                advanced_burn_settings_visible: self.advanced_burn_settings_visible,
+                scp_color_draft: self.scp_color_draft.as_deref(),
+                class_color_draft: self.class_color_draft.as_deref(),
+                flatten_color_draft: self.flatten_color_draft.as_deref(),
+                letterbox_color_draft: self.letterbox_color_draft.as_deref(),
+                background_color_draft: self.background_color_draft.as_deref(),
+                fill_color_draft: self.fill_color_draft.as_deref(),
+                fill_end_color_draft: self.fill_end_color_draft.as_deref(),
+                resolution_inputs: self.resolution_inputs(),
+                aspect_locked: self.aspect_locked,
+                preview_as_export: self.preview_as_export,
+                texture_pack_dir: self.texture_pack_dir.as_deref(),
+                texture_names: self.assets.as_ref().map(|a| a.texture_names()).unwrap_or_default(),
+                recent_colors: &self.recent_colors,
+                recent_images: &self.recent_images,
+                recent_projects: &self.recent_projects,
+                watch_image: self.watch_image,
+                class_series_use_names: self.class_series_use_names,
+                font_preview: self.font_preview.clone(),
+            },
         );
-
-        let content = row![
-            container(input_panel)
-                .width(Length::FillPortion(1))
-                .height(Length::Fill),
-            container(preview_panel)
-                .width(Length::FillPortion(1))
-                .height(Length::Fill),
-        ]
-        .spacing(20)
-        .padding(20);
+        
+        let preview_panel = ui::preview_panel::view(ui::preview_panel::ViewState {
+            preview: &self.preview_handle,
+            pinned: self.pinned_class.map(|class_type| (class_type, &self.pinned_preview_handle)),
+            zoom_factor: self.zoom_factor,
+            preview_offset: self.preview_offset,
+            hover_position: self.hover_position,
+            rotation: self.preview_rotation,
+            bleed_pixels: self.config.bleed_pixels(),
+            export_size: self.config.export_size(),
+            pixelated: self.preview_pixelated,
+            is_regenerating: self.is_regenerating(),
+            backdrop: self.preview_backdrop,
+            image_region: self.show_image_region.then_some(user_image_region(&self.config).is_some()),
+            is_gif: self.gif_frames.is_some(),
+            is_playing: self.gif_playing,
+            current_frame: self.current_frame_index,
+            total_frames: self.gif_frames.as_ref().map(|f| f.len()).unwrap_or(0),
+        });
+
+        let content: Element<Message> = if self.window_width < STACKED_LAYOUT_WIDTH {
+            column![
+                container(input_panel)
+                    .width(Length::Fill)
+                    .height(Length::FillPortion(1)),
+                container(preview_panel)
+                    .width(Length::Fill)
+                    .height(Length::FillPortion(1)),
+            ]
+            .spacing(20)
+            .padding(20)
+            .into()
+        } else {
+            row![
+                container(input_panel)
+                    .width(Length::FillPortion(1))
+                    .height(Length::Fill),
+                container(preview_panel)
+                    .width(Length::FillPortion(1))
+                    .height(Length::Fill),
+            ]
+            .spacing(20)
+            .padding(20)
+            .into()
+        };
 
         container(content)
             .width(Length::Fill)
@@ -932,12 +2067,63 @@ impl Application for App {
     }
 
     fn theme(&self) -> Theme {
-        Theme::Dark
+        self.ui_theme.iced_theme()
     }
 }
 
 
 impl App {
+    fn swap_layers(&mut self, a: usize, b: usize) -> Command<Message> {
+        let mut order = self.config.composition_order();
+        if a < order.len() && b < order.len() {
+            order.swap(a, b);
+        }
+        self.config.layer_order = order;
+        Command::perform(async {}, |_| Message::RegeneratePreview)
+    }
+
+    // A playing GIF re-renders every frame, so the badge would just flicker.
+    fn is_regenerating(&self) -> bool {
+        self.previews_in_flight > 0 && !(self.gif_frames.is_some() && self.gif_playing)
+    }
+
+    // Loaded configs never carry a font path, so the composer has to follow
+    // them back to the bundled font.
+    fn rebuild_composer(&mut self) {
+        match LabelComposer::for_config(&self.config) {
+            Ok(composer) => self.composer = Some(composer),
+            Err(e) => self.modal_error = Some(e.to_string()),
+        }
+    }
+
+    fn clear_input_drafts(&mut self) {
+        self.scp_color_draft = None;
+        self.class_color_draft = None;
+        self.flatten_color_draft = None;
+        self.letterbox_color_draft = None;
+        self.background_color_draft = None;
+        self.fill_color_draft = None;
+        self.fill_end_color_draft = None;
+        self.resolution_draft = None;
+    }
+
+    fn resolution_inputs(&self) -> (String, String) {
+        self.resolution_draft.clone().unwrap_or_else(|| {
+            let side = self.config.output_resolution.to_string();
+            (side.clone(), side)
+        })
+    }
+
+    fn apply_resolution_draft(&mut self, width: String, height: String) {
+        if let (Ok(w), Ok(h)) = (width.trim().parse::<u32>(), height.trim().parse::<u32>()) {
+            let side = w.max(h);
+            if (1..=MAX_OUTPUT_RESOLUTION).contains(&side) {
+                self.config.output_resolution = side;
+            }
+        }
+        self.resolution_draft = Some((width, height));
+    }
+
 fn decode_gif(&mut self, path: &PathBuf) -> Result<(), LabelError> {
     use std::fs::File;
 
@@ -952,6 +2138,10 @@ fn decode_gif(&mut self, path: &PathBuf) -> Result<(), LabelError> {
     let mut gif_frames = Vec::new();
     let mut gif_delays = Vec::new();
 
+    let screen_width = decoder.width() as u32;
+    let screen_height = decoder.height() as u32;
+    let mut canvas = image::RgbaImage::new(screen_width, screen_height);
+
     while let Some(frame) = decoder.read_next_frame()
         .map_err(|e| LabelError::ImageProcessing(e.to_string()))? {
         
@@ -960,11 +2150,49 @@ fn decode_gif(&mut self, path: &PathBuf) -> Result<(), LabelError> {
         
         let width = frame.width as u32;
         let height = frame.height as u32;
+
+        let expected_len = width as usize * height as usize * 4;
+        if frame.buffer.len() != expected_len {
+            return Err(LabelError::ImageProcessing(format!(
+                "GIF frame {} has {} bytes, expected {} for {}x{}",
+                gif_frames.len(),
+                frame.buffer.len(),
+                expected_len,
+                width,
+                height
+            )));
+        }
         
-        let rgba_image = image::RgbaImage::from_raw(width, height, frame.buffer.to_vec())
+        let patch = image::RgbaImage::from_raw(width, height, frame.buffer.to_vec())
             .ok_or_else(|| LabelError::ImageProcessing("Failed to create image from GIF frame".to_string()))?;
+
+        let previous = if frame.dispose == gif::DisposalMethod::Previous {
+            Some(canvas.clone())
+        } else {
+            None
+        };
+
+        image::imageops::overlay(&mut canvas, &patch, frame.left as i64, frame.top as i64);
         
-        gif_frames.push(rgba_image);
+        gif_frames.push(canvas.clone());
+
+        match frame.dispose {
+            gif::DisposalMethod::Background => {
+                let right = (frame.left as u32 + width).min(screen_width);
+                let bottom = (frame.top as u32 + height).min(screen_height);
+                for y in (frame.top as u32).min(bottom)..bottom {
+                    for x in (frame.left as u32).min(right)..right {
+                        canvas.put_pixel(x, y, image::Rgba([0, 0, 0, 0]));
+                    }
+                }
+            }
+            gif::DisposalMethod::Previous => {
+                if let Some(previous) = previous {
+                    canvas = previous;
+                }
+            }
+            gif::DisposalMethod::Any | gif::DisposalMethod::Keep => {}
+        }
     }
 
     self.gif_frames = Some(gif_frames);
@@ -974,6 +2202,45 @@ fn decode_gif(&mut self, path: &PathBuf) -> Result<(), LabelError> {
     Ok(())
 }
 
+    // Returns false for single-frame WebP files so they load as still images.
+    fn decode_webp(&mut self, path: &PathBuf) -> Result<bool, LabelError> {
+        let bytes = std::fs::read(path).map_err(|e| LabelError::Io(e.to_string()))?;
+        match Self::webp_frames(&bytes)? {
+            Some(animation) => {
+                self.gif_frames = Some(animation.frames);
+                self.gif_frame_delays = animation.delays_ms;
+                self.current_frame_index = 0;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn webp_frames(bytes: &[u8]) -> Result<Option<DecodedAnimation>, LabelError> {
+        let animation = webp::AnimDecoder::new(bytes)
+            .decode()
+            .map_err(|e| LabelError::ImageProcessing(format!("Failed to decode WebP: {}", e)))?;
+        if !animation.has_animation() {
+            return Ok(None);
+        }
+
+        let mut frames = Vec::with_capacity(animation.len());
+        let mut delays_ms = Vec::with_capacity(animation.len());
+        // libwebp reports when each frame ends rather than how long it lasts.
+        let mut previous_end = 0;
+        for index in 0..animation.len() {
+            let frame = animation
+                .get_frame(index)
+                .ok_or_else(|| LabelError::ImageProcessing(format!("WebP frame {} is missing", index)))?;
+            let image = image::RgbaImage::from_raw(frame.width(), frame.height(), frame.get_image().to_vec())
+                .ok_or_else(|| LabelError::ImageProcessing(format!("WebP frame {} is not RGBA", index)))?;
+            frames.push(image);
+            delays_ms.push(frame.get_time_ms().saturating_sub(previous_end).max(0) as u32);
+            previous_end = frame.get_time_ms();
+        }
+        Ok(Some(DecodedAnimation { frames, delays_ms }))
+    }
+
 
     fn export_gif_static(
         frames: &[image::RgbaImage],
@@ -989,45 +2256,49 @@ fn decode_gif(&mut self, path: &PathBuf) -> Result<(), LabelError> {
             return Err(LabelError::ImageProcessing("No frames to export".to_string()));
         }
 
-        let output_size = config.output_resolution as u16;
-        
-        let mut file = File::create(path).map_err(|e| LabelError::Io(e.to_string()))?;
-        
-        let mut encoder = gif::Encoder::new(&mut file, output_size, output_size, &[])
+        let composed_frames = Self::compose_animation_frames(frames, config, assets, composer)?;
+        let file = File::create(path).map_err(|e| LabelError::Io(e.to_string()))?;
+        Self::write_gif(std::io::BufWriter::new(file), &composed_frames, delays)
+    }
+
+    // Frames after the first only store the rectangle that changed and are
+    // drawn over the frame before. A kept frame can't be erased, so if any
+    // pixel turns transparent every frame is written whole instead.
+    fn write_gif<W: std::io::Write>(output: W, frames: &[image::RgbaImage], delays: &[u32]) -> Result<(), LabelError> {
+        let Some(first) = frames.first() else {
+            return Err(LabelError::ImageProcessing("No frames to export".to_string()));
+        };
+        let (width, height) = (first.width() as u16, first.height() as u16);
+
+        let mut encoder = gif::Encoder::new(output, width, height, &[])
             .map_err(|e| LabelError::ImageProcessing(e.to_string()))?;
-        
         encoder.set_repeat(gif::Repeat::Infinite)
             .map_err(|e| LabelError::ImageProcessing(e.to_string()))?;
 
-        for (i, gif_frame) in frames.iter().enumerate() {
-            let dynamic_frame = DynamicImage::ImageRgba8(gif_frame.clone());
-            
-            let composed_label = composer.compose(&config, assets, Some(&dynamic_frame))?;
-            
-            let final_frame = if composed_label.width() != config.output_resolution {
-                image::imageops::resize(
-                    &composed_label,
-                    config.output_resolution,
-                    config.output_resolution,
-                    image::imageops::FilterType::Lanczos3,
-                )
+        let patch_frames = frames.windows(2).all(|pair| !Self::turns_transparent(&pair[0], &pair[1]));
+
+        for (i, frame) in frames.iter().enumerate() {
+            let delay_ms = delays.get(i).copied().unwrap_or(100);
+
+            let mut gif_frame = match i.checked_sub(1).filter(|_| patch_frames) {
+                Some(previous) => {
+                    let rect = Self::changed_region(&frames[previous], frame)
+                        .unwrap_or(Rectangle { x: 0, y: 0, width: 1, height: 1 });
+                    let patch = image::imageops::crop_imm(frame, rect.x, rect.y, rect.width, rect.height).to_image();
+                    let mut gif_frame = Self::encode_gif_frame(rect.width as u16, rect.height as u16, &patch);
+                    gif_frame.left = rect.x as u16;
+                    gif_frame.top = rect.y as u16;
+                    gif_frame
+                }
+                None => Self::encode_gif_frame(width, height, frame),
+            };
+            gif_frame.delay = (delay_ms / 10).max(1) as u16;
+            gif_frame.dispose = if patch_frames {
+                gif::DisposalMethod::Keep
             } else {
-                composed_label
+                gif::DisposalMethod::Background
             };
-            
-            let delay_ms = delays.get(i).copied().unwrap_or(100);
-            let delay_centisecs = (delay_ms / 10).max(1) as u16;
-            
-            let mut gif_frame = gif::Frame::from_rgba_speed(
-                output_size,
-                output_size,
-                &mut final_frame.as_raw().to_vec(),
-                10,
-            );
-            
-            gif_frame.delay = delay_centisecs;
-            gif_frame.dispose = gif::DisposalMethod::Background;
-            
+
             encoder.write_frame(&gif_frame)
                 .map_err(|e| LabelError::ImageProcessing(e.to_string()))?;
         }
@@ -1035,67 +2306,689 @@ fn decode_gif(&mut self, path: &PathBuf) -> Result<(), LabelError> {
         Ok(())
     }
 
-    fn save_project(&self, path: PathBuf) -> Result<(), LabelError> {
-        let file = std::fs::File::create(&path).map_err(|e| LabelError::Io(e.to_string()))?;
-        let mut zip = zip::ZipWriter::new(file);
-        
-        let options = zip::write::FileOptions::default()
-            .compression_method(zip::CompressionMethod::Deflated);
+    fn turns_transparent(previous: &image::RgbaImage, next: &image::RgbaImage) -> bool {
+        previous
+            .pixels()
+            .zip(next.pixels())
+            .any(|(before, after)| before[3] >= GIF_ALPHA_THRESHOLD && after[3] < GIF_ALPHA_THRESHOLD)
+    }
+
+    /// The smallest rectangle holding every pixel that differs, or `None` if
+    /// the frames are identical.
+    fn changed_region(previous: &image::RgbaImage, next: &image::RgbaImage) -> Option<Rectangle> {
+        let (mut left, mut top, mut right, mut bottom) = (u32::MAX, u32::MAX, 0, 0);
+        for ((x, y, before), after) in previous.enumerate_pixels().zip(next.pixels()) {
+            if before != after {
+                left = left.min(x);
+                top = top.min(y);
+                right = right.max(x);
+                bottom = bottom.max(y);
+            }
+        }
+        (left != u32::MAX).then(|| Rectangle { x: left, y: top, width: right - left + 1, height: bottom - top + 1 })
+    }
+
+    fn export_apng_static(
+        frames: &[image::RgbaImage],
+        delays: &[u32],
+        config: &LabelConfig,
+        assets: &AssetManager,
+        composer: &LabelComposer,
+        path: &std::path::Path,
+    ) -> Result<(), LabelError> {
+        use std::fs::File;
+
+        if frames.is_empty() {
+            return Err(LabelError::ImageProcessing("No frames to export".to_string()));
+        }
+
+        let composed_frames = Self::compose_animation_frames(frames, config, assets, composer)?;
+
+        let file = File::create(path).map_err(|e| LabelError::Io(e.to_string()))?;
+        let mut encoder = png::Encoder::new(
+            std::io::BufWriter::new(file),
+            config.export_size(),
+            config.export_size(),
+        );
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_animated(composed_frames.len() as u32, 0)
+            .map_err(|e| LabelError::ImageSaving(e.to_string()))?;
+
+        let mut writer = encoder.write_header()
+            .map_err(|e| LabelError::ImageSaving(e.to_string()))?;
+
+        for (i, frame) in composed_frames.iter().enumerate() {
+            let delay_ms = delays.get(i).copied().unwrap_or(100).min(u16::MAX as u32) as u16;
+            writer.set_frame_delay(delay_ms, 1000)
+                .map_err(|e| LabelError::ImageSaving(e.to_string()))?;
+            writer.write_image_data(frame.as_raw())
+                .map_err(|e| LabelError::ImageSaving(e.to_string()))?;
+        }
 
-        if let Some(img_path) = &self.config.image_path {
-            zip.start_file("image", options).map_err(|e| LabelError::Io(e.to_string()))?;
-            let img_data = std::fs::read(img_path).map_err(|e| LabelError::Io(e.to_string()))?;
-            zip.write_all(&img_data).map_err(|e| LabelError::Io(e.to_string()))?;
+        writer.finish().map_err(|e| LabelError::ImageSaving(e.to_string()))?;
+        Ok(())
+    }
+
+    fn export_webp_static(
+        frames: &[image::RgbaImage],
+        delays: &[u32],
+        config: &LabelConfig,
+        assets: &AssetManager,
+        composer: &LabelComposer,
+        path: &std::path::Path,
+    ) -> Result<(), LabelError> {
+        if frames.is_empty() {
+            return Err(LabelError::ImageProcessing("No frames to export".to_string()));
         }
 
-        zip.start_file("project.json", options).map_err(|e| LabelError::Io(e.to_string()))?;
-        let json = serde_json::to_string_pretty(&self.config).map_err(|e| LabelError::ConfigLoading(e.to_string()))?;
-        zip.write_all(json.as_bytes()).map_err(|e| LabelError::Io(e.to_string()))?;
+        let composed_frames = Self::compose_animation_frames(frames, config, assets, composer)?;
 
-        zip.finish().map_err(|e| LabelError::Io(e.to_string()))?;
+        let data = crate::core::webp::encode_webp_animation(
+            &composed_frames,
+            delays,
+            config.output_quality,
+            config.webp_lossless,
+        )?;
+        std::fs::write(path, data).map_err(|e| LabelError::Io(e.to_string()))?;
         Ok(())
     }
 
-    fn load_project(path: PathBuf) -> Result<LabelConfig, LabelError> {
-        let file = std::fs::File::open(&path).map_err(|e| LabelError::Io(e.to_string()))?;
-        let mut archive = zip::ZipArchive::new(file).map_err(|e| LabelError::Io(e.to_string()))?;
+    fn compose_animation_frames(
+        frames: &[image::RgbaImage],
+        config: &LabelConfig,
+        assets: &AssetManager,
+        composer: &LabelComposer,
+    ) -> Result<Vec<image::RgbaImage>, LabelError> {
+        use rayon::prelude::*;
+
+        frames
+            .par_iter()
+            .map(|frame| {
+                let dynamic_frame = DynamicImage::ImageRgba8(frame.clone());
+                let composed_label = flatten_for_export(composer.compose(config, assets, Some(&dynamic_frame))?, config);
+
+                Ok(if composed_label.width() != config.export_size() {
+                    image::imageops::resize(
+                        &composed_label,
+                        config.export_size(),
+                        config.export_size(),
+                        image::imageops::FilterType::Lanczos3,
+                    )
+                } else {
+                    composed_label
+                })
+            })
+            .collect()
+    }
 
-        let mut json_str = String::new();
-        {
-            let mut config_file = archive.by_name("project.json")
-                .map_err(|_| LabelError::ConfigLoading("Missing project.json".to_string()))?;
-            config_file.read_to_string(&mut json_str).map_err(|e| LabelError::Io(e.to_string()))?;
+    // Transparent pixels get a reserved palette index so no opaque color can share it.
+    fn encode_gif_frame(width: u16, height: u16, image: &image::RgbaImage) -> gif::Frame<'static> {
+        let has_transparency = image.pixels().any(|p| p[3] < GIF_ALPHA_THRESHOLD);
+        if !has_transparency {
+            return gif::Frame::from_rgba_speed(width, height, &mut image.as_raw().to_vec(), 10);
         }
-        let mut config: LabelConfig = serde_json::from_str(&json_str).map_err(|e| LabelError::ConfigLoading(e.to_string()))?;
 
-        let mut image_name = None;
-        for i in 0..archive.len() {
-            let file = archive.by_index(i).map_err(|e| LabelError::Io(e.to_string()))?;
-            if file.name() != "project.json" {
-                image_name = Some(file.name().to_string());
-                break;
-            }
+        let opaque: Vec<u8> = image
+            .pixels()
+            .filter(|p| p[3] >= GIF_ALPHA_THRESHOLD)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect();
+
+        let mut palette = vec![0u8; 256 * 3];
+        let buffer: Vec<u8> = if opaque.is_empty() {
+            vec![GIF_TRANSPARENT_INDEX; image.pixels().len()]
+        } else {
+            let quantizer = color_quant::NeuQuant::new(10, GIF_TRANSPARENT_INDEX as usize, &opaque);
+            let colors = quantizer.color_map_rgb();
+            palette[..colors.len()].copy_from_slice(&colors);
+
+            image
+                .pixels()
+                .map(|p| {
+                    if p[3] < GIF_ALPHA_THRESHOLD {
+                        GIF_TRANSPARENT_INDEX
+                    } else {
+                        quantizer.index_of(&[p[0], p[1], p[2], 255]) as u8
+                    }
+                })
+                .collect()
+        };
+
+        gif::Frame {
+            width,
+            height,
+            buffer: std::borrow::Cow::Owned(buffer),
+            palette: Some(palette),
+            transparent: Some(GIF_TRANSPARENT_INDEX),
+            ..gif::Frame::default()
+        }
+    }
+
+    fn export_history_view(history: &[ExportRecord]) -> Element<'static, Message> {
+        let entries = history.iter().enumerate().fold(column![].spacing(10), |list, (index, record)| {
+            let timestamp = chrono::DateTime::parse_from_rfc3339(&record.timestamp)
+                .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_else(|_| record.timestamp.clone());
+            list.push(
+                container(
+                    column![
+                        text(format!(
+                            "{}  •  {} {}px  •  SCP-{} ({})",
+                            timestamp, record.format, record.resolution, record.scp_number, record.object_class_text
+                        ))
+                        .size(14),
+                        text(record.output_path.display().to_string())
+                            .size(12)
+                            .style(ui::theme::palette().text_secondary),
+                        row![
+                            button("Load Config")
+                                .on_press_maybe(record.config.as_ref().map(|_| Message::ReopenExportConfig(index)))
+                                .padding([6, 12])
+                                .style(iced::theme::Button::Secondary),
+                            button("Open Folder")
+                                .on_press(Message::OpenExportFolder(index))
+                                .padding([6, 12])
+                                .style(iced::theme::Button::Secondary),
+                        ]
+                        .spacing(8),
+                    ]
+                    .spacing(6),
+                )
+                .padding(10)
+                .width(Length::Fill)
+                .style(ui::theme::inline_panel()),
+            )
+        });
+
+        let body: Element<'static, Message> = if history.is_empty() {
+            text("No exports recorded yet.").size(14).into()
+        } else {
+            scrollable(entries).height(Length::Fixed(420.0)).into()
+        };
+
+        let modal_content = container(
+            column![
+                text("Export History").size(24),
+                body,
+                button("Close").on_press(Message::CloseExportHistory),
+            ]
+            .spacing(12)
+            .padding(20)
+            .align_items(iced::Alignment::Center),
+        )
+        .style(ui::theme::card())
+        .max_width(640);
+
+        container(modal_content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .style(container::Appearance {
+                background: Some(iced::Background::Color(Color::from_rgba(0.1, 0.1, 0.1, 0.7))),
+                ..Default::default()
+            })
+            .into()
+    }
+
+    // Thumbnails are rendered one after another so the grid fills in
+    // progressively instead of stalling on the whole batch.
+    fn render_batch_thumbnail(&self, index: usize) -> Command<Message> {
+        let (Some(batch), Some(assets), Some(composer)) = (&self.batch_preview, &self.assets, &self.composer) else {
+            return Command::none();
+        };
+        if index >= batch.thumbnails.len() {
+            return Command::none();
+        }
+
+        let config = LabelConfig {
+            output_resolution: BATCH_THUMBNAIL_SIZE,
+            ..batch.entries[index].clone()
+        };
+        let (assets, composer) = (assets.clone(), composer.clone());
+        Command::perform(
+            async move {
+                let user_image = load_user_image(&config)?;
+                Self::render_preview(&composer, &config, &assets, user_image.as_ref(), false, false, 0)
+            },
+            move |result| Message::BatchThumbnailGenerated(index, result),
+        )
+    }
+
+    fn batch_preview_view(batch: &BatchPreview) -> Element<'_, Message> {
+        let cells: Vec<Element<'_, Message>> = batch
+            .entries
+            .iter()
+            .zip(&batch.thumbnails)
+            .map(|(entry, thumbnail)| {
+                let preview: Element<'_, Message> = match thumbnail {
+                    Some(handle) => iced::widget::image(handle.clone())
+                        .width(Length::Fixed(BATCH_THUMBNAIL_SIZE as f32))
+                        .height(Length::Fixed(BATCH_THUMBNAIL_SIZE as f32))
+                        .into(),
+                    None => container(text("Rendering...").size(12))
+                        .width(Length::Fixed(BATCH_THUMBNAIL_SIZE as f32))
+                        .height(Length::Fixed(BATCH_THUMBNAIL_SIZE as f32))
+                        .center_x()
+                        .center_y()
+                        .into(),
+                };
+                column![preview, text(format!("SCP-{}", entry.scp_number)).size(12)]
+                    .spacing(4)
+                    .align_items(iced::Alignment::Center)
+                    .into()
+            })
+            .collect();
+
+        let mut grid = column![].spacing(12);
+        let mut cells = cells.into_iter().peekable();
+        while cells.peek().is_some() {
+            grid = grid.push(row(cells.by_ref().take(4).collect::<Vec<_>>()).spacing(12));
         }
 
-        if let Some(name) = image_name {
-            let mut buffer = Vec::new();
-            let mut image_file = archive.by_name(&name).map_err(|e| LabelError::Io(e.to_string()))?;
-            image_file.read_to_end(&mut buffer).map_err(|e| LabelError::Io(e.to_string()))?;
-
-            let format = image::guess_format(&buffer).map_err(|_| LabelError::ImageProcessing("Unknown format".to_string()))?;
-            let ext = match format {
-                image::ImageFormat::Png => "png",
-                image::ImageFormat::Jpeg => "jpg",
-                image::ImageFormat::Gif => "gif",
-                _ => "bin",
+        let summary = if batch.entries.len() > batch.thumbnails.len() {
+            format!("Showing the first {} of {} entries", batch.thumbnails.len(), batch.entries.len())
+        } else {
+            format!("{} entries", batch.entries.len())
+        };
+
+        let modal_content = container(
+            column![
+                text("Batch Preview").size(24),
+                text(summary).size(14).style(ui::theme::palette().text_secondary),
+                scrollable(grid).height(Length::Fixed(460.0)),
+                button("Close").on_press(Message::CloseBatchPreview),
+            ]
+            .spacing(12)
+            .padding(20)
+            .align_items(iced::Alignment::Center),
+        )
+        .style(ui::theme::card())
+        .max_width(760);
+
+        container(modal_content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .style(container::Appearance {
+                background: Some(iced::Background::Color(Color::from_rgba(0.1, 0.1, 0.1, 0.7))),
+                ..Default::default()
+            })
+            .into()
+    }
+
+    // Loading runs on a blocking thread and streams progress back so the
+    // loading screen can show which asset is being read.
+    fn asset_loader(pack_dir: Option<PathBuf>) -> Subscription<Message> {
+        iced::subscription::channel(("asset-loader", pack_dir.clone()), 16, |mut output| async move {
+            use futures::{SinkExt, StreamExt};
+
+            let (sender, mut progress) = futures::channel::mpsc::unbounded();
+            let loader = tokio::task::spawn_blocking(move || {
+                AssetManager::load_with_progress(pack_dir.as_deref(), |step| {
+                    let _ = sender.unbounded_send(step);
+                })
+            });
+
+            while let Some(step) = progress.next().await {
+                let _ = output.send(Message::AssetLoadProgress(step)).await;
+            }
+            let result = loader
+                .await
+                .unwrap_or_else(|e| Err(LabelError::AssetLoading(format!("Asset loader stopped: {}", e))));
+            let _ = output.send(Message::AssetsLoaded(result)).await;
+
+            std::future::pending().await
+        })
+    }
+
+    // The parent directory is watched rather than the file itself because
+    // many editors save by replacing the file, which drops a direct watch.
+    fn image_watcher(path: PathBuf) -> Subscription<Message> {
+        iced::subscription::channel(("image-watcher", path.clone()), 16, |mut output| async move {
+            use futures::{SinkExt, StreamExt};
+            use notify::RecursiveMode;
+
+            let (sender, mut changes) = futures::channel::mpsc::unbounded();
+            let file_name = path.file_name().map(|name| name.to_os_string());
+            let debouncer = notify_debouncer_mini::new_debouncer(
+                std::time::Duration::from_millis(IMAGE_WATCH_DEBOUNCE_MS),
+                move |result: notify_debouncer_mini::DebounceEventResult| match result {
+                    Ok(events) => {
+                        if events.iter().any(|event| event.path.file_name() == file_name.as_deref()) {
+                            let _ = sender.unbounded_send(());
+                        }
+                    }
+                    Err(e) => log::warn!("Image watcher error: {}", e),
+                },
+            );
+
+            let parent = path
+                .parent()
+                .filter(|dir| !dir.as_os_str().is_empty())
+                .unwrap_or(std::path::Path::new("."));
+            let _debouncer = match debouncer {
+                Ok(mut debouncer) => match debouncer.watcher().watch(parent, RecursiveMode::NonRecursive) {
+                    Ok(()) => Some(debouncer),
+                    Err(e) => {
+                        log::warn!("Could not watch {}: {}", parent.display(), e);
+                        None
+                    }
+                },
+                Err(e) => {
+                    log::warn!("Could not start image watcher: {}", e);
+                    None
+                }
             };
 
-            let temp_path = std::env::temp_dir().join(format!("scp_proj_temp.{}", ext));
-            std::fs::write(&temp_path, buffer).map_err(|e| LabelError::Io(e.to_string()))?;
-            config.image_path = Some(temp_path);
+            while changes.next().await.is_some() {
+                let _ = output.send(Message::ImageFileChanged(path.clone())).await;
+            }
+            std::future::pending().await
+        })
+    }
+
+    fn check_legibility(&mut self) {
+        let Some(assets) = &self.assets else {
+            return;
+        };
+        let warning = match legibility::low_contrast_fields(&self.config, assets) {
+            Ok(issues) if !issues.is_empty() => {
+                let fields: Vec<String> = issues
+                    .iter()
+                    .map(|issue| format!("{} text ({:.1}:1)", issue.field, issue.ratio))
+                    .collect();
+                Some(format!("Low contrast: {} may be hard to read", fields.join(", ")))
+            }
+            Ok(_) => None,
+            Err(e) => {
+                log::warn!("Skipping legibility check: {}", e);
+                None
+            }
+        };
+
+        if warning.is_some() && warning != self.contrast_warning {
+            self.notification_message = warning.clone();
         }
+        self.contrast_warning = warning;
+    }
 
-        Ok(config)
+    fn remember_color(&mut self, color: Color) {
+        ui_state::remember_color(&mut self.recent_colors, color);
+        self.save_ui_state();
     }
 
-}
\ No newline at end of file
+    /// Loads a still or animated image into the slot. Only images the user
+    /// picked are `remember`ed; project extracts and reloads are not.
+    fn load_image(&mut self, path: PathBuf, remember: bool) -> Command<Message> {
+        let extension = path.extension().and_then(|s| s.to_str()).map(str::to_ascii_lowercase);
+        let animated = match extension.as_deref() {
+            Some("gif") => self.decode_gif(&path).map(|_| true),
+            Some("webp") => self.decode_webp(&path),
+            _ => Ok(false),
+        };
+        let result = match animated {
+            Ok(true) => {
+                self.user_image = None;
+                self.validation = None;
+                Ok(())
+            }
+            Ok(false) => load_image_robustly(&path).map(|img| {
+                self.gif_frames = None;
+                self.gif_frame_delays.clear();
+                self.current_frame_index = 0;
+                self.validation = Some(validate_user_image(&img, self.config.resize_method));
+                self.user_image = Some(img);
+            }),
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(()) => {
+                if remember {
+                    self.remember_image(&path);
+                }
+                self.config.image_path = Some(path);
+                Command::perform(async {}, |_| Message::RegeneratePreview)
+            }
+            Err(e) => {
+                if remember {
+                    self.forget_image(&path);
+                }
+                log::error!("Could not open image: {}", e);
+                self.modal_error = Some(format!("Could not open image: {}", e));
+                Command::none()
+            }
+        }
+    }
+
+    fn remember_image(&mut self, path: &Path) {
+        ui_state::remember_path(&mut self.recent_images, path);
+        self.save_ui_state();
+    }
+
+    fn forget_image(&mut self, path: &Path) {
+        if self.recent_images.iter().any(|recent| recent == path) {
+            self.recent_images.retain(|recent| recent != path);
+            self.save_ui_state();
+        }
+    }
+
+    fn remember_project(&mut self, path: &Path) {
+        ui_state::remember_path(&mut self.recent_projects, path);
+        self.save_ui_state();
+    }
+
+    fn save_ui_state(&self) {
+        let state = UiState {
+            recent_colors: self.recent_colors.iter().copied().map(Into::into).collect(),
+            ui_theme: self.ui_theme,
+            recent_images: self.recent_images.clone(),
+            recent_projects: self.recent_projects.clone(),
+            texture_pack_dir: self.texture_pack_dir.clone(),
+        };
+        if let Err(e) = state.save() {
+            log::warn!("Failed to save UI state: {}", e);
+        }
+    }
+
+    fn record_export(path: &std::path::Path, format: &str, config: &LabelConfig) {
+        if let Err(e) = export_history::record_export(ExportRecord::new(path, format, config)) {
+            log::warn!("Failed to record export history: {}", e);
+        }
+    }
+
+    fn open_folder(folder: &std::path::Path) -> std::io::Result<()> {
+        let opener = if cfg!(target_os = "windows") {
+            "explorer"
+        } else if cfg!(target_os = "macos") {
+            "open"
+        } else {
+            "xdg-open"
+        };
+        std::process::Command::new(opener).arg(folder).spawn().map(|_| ())
+    }
+
+    fn render_preview(
+        composer: &LabelComposer,
+        config: &LabelConfig,
+        assets: &AssetManager,
+        user_image: Option<&DynamicImage>,
+        preview_as_export: bool,
+        show_image_region: bool,
+        rotation: u16,
+    ) -> Result<Vec<u8>, LabelError> {
+        let mut img = composer.compose(config, assets, user_image)?;
+        if preview_as_export {
+            img = flatten_for_export(img, config);
+        }
+        if let Some(region) = user_image_region(config).filter(|_| show_image_region) {
+            let bleed = config.bleed_pixels();
+            let mut region = region.scaled((img.width() - 2 * bleed) as f32 / LABEL_SIZE as f32);
+            region.x += bleed;
+            region.y += bleed;
+            outline_region(&mut img, region);
+        }
+        img = match rotation {
+            90 => image::imageops::rotate90(&img),
+            180 => image::imageops::rotate180(&img),
+            270 => image::imageops::rotate270(&img),
+            _ => img,
+        };
+        if preview_as_export && config.output_format == OutputFormat::Jpeg {
+            return crate::core::jpeg::encode_jpeg(&img, config.output_quality, config.jpeg_subsampling);
+        }
+
+        let mut buffer = Vec::new();
+        if img.write_to(&mut std::io::Cursor::new(&mut buffer), image::ImageFormat::Png).is_ok() {
+            Ok(buffer)
+        } else {
+            Err(LabelError::ImageProcessing("Failed to encode preview".to_string()))
+        }
+    }
+
+    // The current GIF frame when one is loaded, otherwise the cached still image.
+    fn current_user_image(&self) -> Option<DynamicImage> {
+        match &self.gif_frames {
+            Some(frames) => Some(DynamicImage::ImageRgba8(frames[self.current_frame_index % frames.len()].clone())),
+            None => self.user_image.clone(),
+        }
+    }
+
+    fn project_thumbnail(&self) -> Option<image::RgbaImage> {
+        let (assets, composer) = (self.assets.as_ref()?, self.composer.as_ref()?);
+        let config = LabelConfig {
+            output_resolution: PROJECT_THUMBNAIL_SIZE,
+            ..self.config.clone()
+        };
+        match composer.compose(&config, assets, self.current_user_image().as_ref()) {
+            Ok(thumbnail) => Some(thumbnail),
+            Err(e) => {
+                log::warn!("Saving project without thumbnail: {}", e);
+                None
+            }
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cycle_wraps_in_both_directions() {
+        let classes = ClassType::all();
+        let last = *classes.last().unwrap();
+        assert_eq!(cycle(&classes, last, 1), classes[0]);
+        assert_eq!(cycle(&classes, classes[0], -1), last);
+        assert_eq!(cycle(&classes, classes[0], 1), classes[1]);
+    }
+
+    #[test]
+    fn regenerating_badge_shows_without_a_gif() {
+        let (mut app, _) = App::new(());
+        assert!(!app.is_regenerating());
+
+        app.previews_in_flight = 1;
+        assert!(app.is_regenerating());
+
+        app.gif_frames = Some(vec![image::RgbaImage::new(1, 1)]);
+        assert!(!app.is_regenerating());
+        app.gif_playing = false;
+        assert!(app.is_regenerating());
+    }
+
+    #[test]
+    fn opening_a_project_leaves_recent_images_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let extracted = dir.path().join("scp_proj_label.png");
+        image::RgbaImage::from_pixel(4, 4, image::Rgba([255, 0, 0, 255])).save(&extracted).unwrap();
+
+        let (mut app, _) = App::new(());
+        let recent_images = app.recent_images.clone();
+        let config = LabelConfig { image_path: Some(extracted.clone()), ..LabelConfig::default() };
+        let _ = app.update(Message::ProjectLoaded(Ok(config)));
+
+        assert_eq!(app.config.image_path, Some(extracted));
+        assert!(app.user_image.is_some());
+        assert_eq!(app.recent_images, recent_images);
+    }
+
+    #[test]
+    fn gif_export_stores_later_frames_as_patches() {
+        let first = image::RgbaImage::from_pixel(16, 16, image::Rgba([255, 0, 0, 255]));
+        let mut second = first.clone();
+        for (x, y) in (4..10).flat_map(|x| (6..9).map(move |y| (x, y))) {
+            second.put_pixel(x, y, image::Rgba([0, 0, 255, 255]));
+        }
+        let mut faded = second.clone();
+        faded.put_pixel(0, 0, image::Rgba([0, 0, 0, 0]));
+
+        let decode = |frames: &[image::RgbaImage]| {
+            let mut gif = Vec::new();
+            App::write_gif(&mut gif, frames, &[100, 100]).unwrap();
+            let mut decoder = gif::DecodeOptions::new().read_info(gif.as_slice()).unwrap();
+            let mut decoded = Vec::new();
+            while let Some(frame) = decoder.read_next_frame().unwrap() {
+                decoded.push((frame.left, frame.top, frame.width, frame.height, frame.dispose));
+            }
+            decoded
+        };
+
+        let patched = decode(&[first.clone(), second.clone()]);
+        assert_eq!(patched[0], (0, 0, 16, 16, gif::DisposalMethod::Keep));
+        assert_eq!(patched[1], (4, 6, 6, 3, gif::DisposalMethod::Keep));
+
+        let whole = decode(&[second, faded]);
+        assert_eq!(whole[1], (0, 0, 16, 16, gif::DisposalMethod::Background));
+    }
+
+    #[test]
+    fn gif_export_keeps_every_frame_in_order() {
+        let assets = AssetManager::load_with_pack_dir(None).unwrap();
+        let composer = LabelComposer::new().unwrap();
+        let config = LabelConfig { output_resolution: 64, ..LabelConfig::default() };
+        let frames: Vec<_> = (0..5u8)
+            .map(|i| image::RgbaImage::from_pixel(8, 8, image::Rgba([i * 50, 0, 0, 255])))
+            .collect();
+        let delays = [100, 200, 300, 400, 500];
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("label.gif");
+
+        App::export_gif_static(&frames, &delays, &config, &assets, &composer, &path).unwrap();
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(std::fs::File::open(&path).unwrap()).unwrap();
+        let mut decoded_delays = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            decoded_delays.push(frame.delay);
+        }
+        assert_eq!(decoded_delays, [10, 20, 30, 40, 50]);
+    }
+
+    #[test]
+    fn animated_webp_round_trips_frames_and_delays() {
+        let assets = AssetManager::load_with_pack_dir(None).unwrap();
+        let composer = LabelComposer::new().unwrap();
+        let config = LabelConfig { output_resolution: 64, webp_lossless: true, ..LabelConfig::default() };
+        let frames: Vec<_> = (0..3u8)
+            .map(|i| image::RgbaImage::from_pixel(8, 8, image::Rgba([0, i * 100, 0, 255])))
+            .collect();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("label.webp");
+
+        App::export_webp_static(&frames, &[100, 200, 300], &config, &assets, &composer, &path).unwrap();
+
+        let decoded = App::webp_frames(&std::fs::read(&path).unwrap()).unwrap().unwrap();
+        assert_eq!(decoded.frames.len(), 3);
+        assert!(decoded.frames.iter().all(|frame| frame.dimensions() == (64, 64)));
+        assert_eq!(decoded.delays_ms, [100, 200, 300]);
+
+        let still = crate::core::webp::encode_webp(&frames[0], 90, true).unwrap();
+        assert!(App::webp_frames(&still).unwrap().is_none());
+    }
+}
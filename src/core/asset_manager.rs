@@ -1,6 +1,7 @@
-use crate::models::{ClassType, Hazard, LABEL_SIZE};
-use crate::utils::LabelError;
+use crate::models::{ClassType, Hazard, DEFAULT_TEXTURE, LABEL_SIZE};
+use crate::utils::{asset_path, user_texture_pack_dir, LabelError, TEXTURE_PACK_DIR};
 use image::{RgbaImage, ImageBuffer, DynamicImage};
+use image::imageops::FilterType;
 use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
 use std::fs::{self, File};
@@ -8,6 +9,14 @@ use std::io::Read;
 use std::path::{Path, PathBuf};
 use zip::ZipArchive;
 
+const TEXTURE_DIR: &str = "resources/materials/textures/";
+
+#[cfg(feature = "bundled-assets")]
+#[derive(rust_embed::RustEmbed)]
+#[folder = "resources/materials/"]
+#[prefix = "resources/materials/"]
+struct BundledAssets;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SerializableRgbaImage {
     pub width: u32,
@@ -27,27 +36,65 @@ impl From<RgbaImage> for SerializableRgbaImage {
     }
 }
 
-impl From<SerializableRgbaImage> for RgbaImage {
-    fn from(s_img: SerializableRgbaImage) -> Self {
-        ImageBuffer::from_raw(s_img.width, s_img.height, s_img.pixels)
-            .expect("Failed to create RgbaImage from raw data")
+impl SerializableRgbaImage {
+    pub fn try_into_rgba(self) -> Result<RgbaImage, LabelError> {
+        let expected = self.width as usize * self.height as usize * 4;
+        if self.pixels.len() != expected {
+            return Err(LabelError::ImageProcessing(format!(
+                "Pixel buffer for {}x{} image has {} bytes, expected {}",
+                self.width,
+                self.height,
+                self.pixels.len(),
+                expected
+            )));
+        }
+
+        ImageBuffer::from_raw(self.width, self.height, self.pixels)
+            .ok_or_else(|| LabelError::ImageProcessing("Failed to create RgbaImage from raw data".to_string()))
+    }
+}
+
+impl TryFrom<SerializableRgbaImage> for RgbaImage {
+    type Error = LabelError;
+
+    fn try_from(s_img: SerializableRgbaImage) -> Result<Self, Self::Error> {
+        s_img.try_into_rgba()
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct LoadProgress {
+    pub stage: String,
+    pub current: usize,
+    pub total: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AssetManager {
     pub templates: HashMap<ClassType, (SerializableRgbaImage, SerializableRgbaImage)>,
     pub hazard_icons: HashMap<(ClassType, Hazard), SerializableRgbaImage>,
-    pub texture_overlay: SerializableRgbaImage,
+    pub textures: HashMap<String, SerializableRgbaImage>,
     pub placeholder: SerializableRgbaImage,
     pub burn_overlay: SerializableRgbaImage
 }
 
 impl AssetManager {
-    pub fn load_all() -> Result<Self, LabelError> {
+    pub fn load_with_pack_dir(pack_dir: Option<&Path>) -> Result<Self, LabelError> {
+        Self::load_with_progress(pack_dir, |_| {})
+    }
+
+    pub fn load_with_progress(
+        pack_dir: Option<&Path>,
+        mut on_progress: impl FnMut(LoadProgress),
+    ) -> Result<Self, LabelError> {
         log::info!("Initializing AssetManager (Auto-detecting texture packs)...");
 
-        let mut archives = Self::get_all_texture_packs();
+        let classes = ClassType::all();
+        let total = classes.len() + 2;
+        let mut report = |current: usize, stage: String| on_progress(LoadProgress { stage, current, total });
+
+        report(0, "Scanning texture packs".to_string());
+        let mut archives = Self::get_all_texture_packs(pack_dir)?;
         
         let mut templates = HashMap::new();
         let mut hazard_icons = HashMap::new();
@@ -55,7 +102,8 @@ impl AssetManager {
         let placeholder_rgba = image::RgbaImage::from_pixel(1, 1, image::Rgba([0, 0, 0, 0]));
         let placeholder = SerializableRgbaImage::from(placeholder_rgba);
 
-        for class in ClassType::all() {
+        for (index, class) in classes.into_iter().enumerate() {
+            report(index + 1, format!("Loading {} template", class.as_str()));
             let primary = Self::load_asset(&class.label_path(false), &mut archives, true)?;
             let alternate = Self::load_asset(&class.label_path(true), &mut archives, true)
                 .unwrap_or_else(|_| primary.clone());
@@ -69,16 +117,30 @@ impl AssetManager {
             }
         }
 
-        let texture_path = "resources/materials/textures/dirty_overlay.png";
-        let texture_overlay = Self::load_asset(texture_path, &mut archives, true)
-            .unwrap_or_else(|_| {
-                log::warn!("Texture overlay not found, using transparent placeholder.");
-                placeholder.clone()
-            });
+        report(total - 1, "Loading texture overlays".to_string());
+        let mut textures = HashMap::new();
+        for texture_path in Self::discover_textures(&mut archives) {
+            let Some(name) = Path::new(&texture_path).file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if name == "burn_overlay" || textures.contains_key(name) {
+                continue;
+            }
+            match Self::load_asset(&texture_path, &mut archives, false).and_then(Self::fit_texture) {
+                Ok(texture) => {
+                    textures.insert(name.to_string(), texture);
+                }
+                Err(e) => log::warn!("Skipping texture overlay '{}': {}", texture_path, e),
+            }
+        }
+        if !textures.contains_key(DEFAULT_TEXTURE) {
+            log::warn!("Texture overlay not found, using transparent placeholder.");
+        }
         let burn_path = "resources/materials/textures/burn_overlay.png";
         let burn_overlay = Self::load_asset(burn_path, &mut archives, true)
             .unwrap_or_else(|_| placeholder.clone());
 
+        report(total, "Finishing up".to_string());
         log::info!(
             "Asset loading complete. Loaded from {} texture packs and local resources.", 
             archives.len()
@@ -87,22 +149,35 @@ impl AssetManager {
         Ok(Self {
             templates,
             hazard_icons,
-            texture_overlay,
+            textures,
             placeholder,
             burn_overlay,
         })
     }
 
-    fn get_all_texture_packs() -> Vec<ZipArchive<File>> {
-        let mut archives = Vec::new();
-        let pack_dir = Path::new("texturepacks");
+    fn get_all_texture_packs(custom_dir: Option<&Path>) -> Result<Vec<ZipArchive<File>>, LabelError> {
+        let pack_dirs = match custom_dir {
+            Some(dir) if dir.is_dir() => vec![dir.to_path_buf()],
+            Some(dir) => {
+                return Err(LabelError::AssetLoading(format!(
+                    "Texture pack directory {} does not exist",
+                    dir.display()
+                )))
+            }
+            None => Self::texture_pack_dirs(),
+        };
 
-        if !pack_dir.exists() {
-            let _ = fs::create_dir_all(pack_dir);
-            return archives;
-        }
+        let mut archives = Vec::new();
+        for pack_dir in pack_dirs {
+            log::info!("Loading texture packs from {}", pack_dir.display());
+            let entries = match fs::read_dir(&pack_dir) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    log::warn!("Cannot read texture pack directory {}: {}", pack_dir.display(), e);
+                    continue;
+                }
+            };
 
-        if let Ok(entries) = fs::read_dir(pack_dir) {
             for entry in entries.flatten() {
                 let path = entry.path();
                 if path.extension().and_then(|s| s.to_str()) == Some("zip") {
@@ -115,7 +190,67 @@ impl AssetManager {
                 }
             }
         }
-        archives
+        Ok(archives)
+    }
+
+    // Packs later in the list take priority, so user packs override the local dev directory.
+    fn texture_pack_dirs() -> Vec<PathBuf> {
+        let mut dirs = Vec::new();
+
+        let local = asset_path(TEXTURE_PACK_DIR);
+        if local.is_dir() {
+            dirs.push(local);
+        }
+
+        match user_texture_pack_dir() {
+            Some(user_dir) => match fs::create_dir_all(&user_dir) {
+                Ok(()) if !dirs.contains(&user_dir) => dirs.push(user_dir),
+                Ok(()) => {}
+                Err(e) => log::warn!(
+                    "Texture pack directory {} is not accessible ({}); packs placed there will not be loaded.",
+                    user_dir.display(),
+                    e
+                ),
+            },
+            None => log::warn!("Could not determine a user config directory for texture packs."),
+        }
+
+        dirs
+    }
+
+    fn discover_textures(archives: &mut [ZipArchive<File>]) -> Vec<String> {
+        let is_texture = |path: &str| {
+            path.strip_prefix(TEXTURE_DIR)
+                .map(|name| !name.contains('/'))
+                .unwrap_or(false)
+                && matches!(
+                    Path::new(path).extension().and_then(|s| s.to_str()).map(str::to_lowercase).as_deref(),
+                    Some("png" | "jpg" | "jpeg")
+                )
+        };
+
+        let mut paths = Vec::new();
+        for archive in archives.iter() {
+            paths.extend(archive.file_names().filter(|p| is_texture(p)).map(str::to_string));
+        }
+
+        if let Ok(entries) = fs::read_dir(asset_path(TEXTURE_DIR)) {
+            for entry in entries.flatten() {
+                if let Some(name) = entry.file_name().to_str() {
+                    let path = format!("{}{}", TEXTURE_DIR, name);
+                    if is_texture(&path) {
+                        paths.push(path);
+                    }
+                }
+            }
+        }
+
+        #[cfg(feature = "bundled-assets")]
+        paths.extend(BundledAssets::iter().filter(|p| is_texture(p)).map(|p| p.into_owned()));
+
+        paths.sort();
+        paths.dedup();
+        paths
     }
 
     fn load_asset(
@@ -135,19 +270,34 @@ impl AssetManager {
             }
         }
 
-        let img = image::open(path)
-            .map_err(|e| LabelError::ImageLoading(format!("Asset '{}' not found in ZIPs or Disk: {}", path, e)))?;
+        let img = match image::open(asset_path(path)) {
+            Ok(img) => img,
+            Err(e) => Self::load_bundled(path).ok_or_else(|| {
+                LabelError::ImageLoading(format!("Asset '{}' not found in ZIPs, Disk or bundled assets: {}", path, e))
+            })?,
+        };
         
         Ok(Self::finalize_image(img, should_resize))
     }
 
+    #[cfg(feature = "bundled-assets")]
+    fn load_bundled(path: &str) -> Option<DynamicImage> {
+        let file = BundledAssets::get(path)?;
+        image::load_from_memory(&file.data).ok()
+    }
+
+    #[cfg(not(feature = "bundled-assets"))]
+    fn load_bundled(_path: &str) -> Option<DynamicImage> {
+        None
+    }
+
     fn finalize_image(img: DynamicImage, should_resize: bool) -> SerializableRgbaImage {
         let rgba = if should_resize && (img.width() != LABEL_SIZE || img.height() != LABEL_SIZE) {
             image::imageops::resize(
                 &img, 
                 LABEL_SIZE, 
                 LABEL_SIZE, 
-                image::imageops::FilterType::Lanczos3
+                Self::scaling_filter(img.width(), img.height())
             )
         } else {
             img.to_rgba8()
@@ -155,6 +305,26 @@ impl AssetManager {
         SerializableRgbaImage::from(rgba)
     }
 
+    /// Textures smaller than the label on both sides are kept as tiles; anything
+    /// else is stretched to the label like a template.
+    fn fit_texture(texture: SerializableRgbaImage) -> Result<SerializableRgbaImage, LabelError> {
+        if texture.width < LABEL_SIZE && texture.height < LABEL_SIZE {
+            return Ok(texture);
+        }
+        Ok(Self::finalize_image(DynamicImage::ImageRgba8(texture.try_into_rgba()?), true))
+    }
+
+    fn scaling_filter(width: u32, height: u32) -> FilterType {
+        let factor = LABEL_SIZE / width;
+        let is_integer_upscale = width * factor == LABEL_SIZE && height * factor == LABEL_SIZE;
+
+        if is_integer_upscale {
+            FilterType::Nearest
+        } else {
+            FilterType::Lanczos3
+        }
+    }
+
 
     pub fn get_template(&self, class: &ClassType, alternate: bool) -> &SerializableRgbaImage {
         self.templates
@@ -169,8 +339,17 @@ impl AssetManager {
             .unwrap_or(&self.placeholder)
     }
 
-    pub fn get_texture(&self) -> &SerializableRgbaImage {
-        &self.texture_overlay
+    pub fn get_texture(&self, name: &str) -> &SerializableRgbaImage {
+        self.textures
+            .get(name)
+            .or_else(|| self.textures.get(DEFAULT_TEXTURE))
+            .unwrap_or(&self.placeholder)
+    }
+
+    pub fn texture_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.textures.keys().cloned().collect();
+        names.sort();
+        names
     }
     pub fn get_burn_overlay(&self) -> &SerializableRgbaImage {
         &self.burn_overlay
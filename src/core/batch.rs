@@ -0,0 +1,188 @@
+use crate::models::{ClassType, LabelConfig};
+use crate::utils::{sanitize_filename, LabelError};
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+
+/// Loads a batch manifest, either a headered CSV or a JSON array of partial
+/// configs, and merges every entry over `base`.
+pub fn load_batch(path: &Path, base: &LabelConfig) -> Result<Vec<LabelConfig>, LabelError> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| LabelError::ConfigLoading(format!("Failed to read batch file {}: {}", path.display(), e)))?;
+
+    let is_csv = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+    let mut configs = if is_csv {
+        parse_csv(&contents, base)?
+    } else {
+        parse_json(&contents, base)?
+    };
+
+    let manifest_dir = path.parent().unwrap_or(Path::new("."));
+    for config in &mut configs {
+        for field in [&mut config.image_path, &mut config.font_path, &mut config.watermark_image].into_iter().flatten() {
+            if field.is_relative() {
+                *field = manifest_dir.join(&*field);
+            }
+        }
+    }
+    Ok(configs)
+}
+
+pub fn parse_json(contents: &str, base: &LabelConfig) -> Result<Vec<LabelConfig>, LabelError> {
+    let entries: Vec<Map<String, Value>> = serde_json::from_str(contents)
+        .map_err(|e| LabelError::ConfigLoading(format!("Batch file must be a JSON array of objects: {}", e)))?;
+
+    entries
+        .into_iter()
+        .enumerate()
+        .map(|(index, entry)| merge_entry(base, entry, index))
+        .collect()
+}
+
+pub fn parse_csv(contents: &str, base: &LabelConfig) -> Result<Vec<LabelConfig>, LabelError> {
+    let base_value = base_object(base)?;
+    let mut reader = csv::Reader::from_reader(contents.as_bytes());
+    let headers = reader
+        .headers()
+        .map_err(|e| LabelError::ConfigLoading(format!("Failed to read CSV header: {}", e)))?
+        .clone();
+
+    reader
+        .records()
+        .enumerate()
+        .map(|(index, record)| {
+            let record = record.map_err(|e| LabelError::ConfigLoading(format!("Row {}: {}", index + 1, e)))?;
+            let entry = headers
+                .iter()
+                .zip(record.iter())
+                .filter(|(_, cell)| !cell.trim().is_empty())
+                .map(|(key, cell)| {
+                    let key = key.trim();
+                    (key.to_string(), csv_cell(base_value.get(key), cell.trim()))
+                })
+                .collect();
+            merge_entry(base, entry, index)
+        })
+        .collect()
+}
+
+/// One config per class type. With `use_class_names`, each label's object
+/// class text is replaced by the class's own name.
+pub fn class_series(base: &LabelConfig, use_class_names: bool) -> Vec<LabelConfig> {
+    ClassType::all()
+        .into_iter()
+        .map(|class_type| {
+            let mut config = base.clone();
+            config.class_type = class_type;
+            if use_class_names {
+                config.object_class_text = class_type.as_str().to_string();
+            }
+            config
+        })
+        .collect()
+}
+
+pub fn class_series_file_name(config: &LabelConfig) -> String {
+    let stem = sanitize_filename(&format!("SCP-{}_{}", config.scp_number, config.class_type.as_str()));
+    format!("{}.{}", stem, config.output_format.extension())
+}
+
+// CSV cells are untyped, so the type of the matching base field decides
+// whether a cell like "049" stays a string or becomes a number.
+fn csv_cell(base_field: Option<&Value>, cell: &str) -> Value {
+    match base_field {
+        Some(Value::String(_)) => Value::String(cell.to_string()),
+        _ => serde_json::from_str(cell).unwrap_or_else(|_| Value::String(cell.to_string())),
+    }
+}
+
+fn base_object(base: &LabelConfig) -> Result<Map<String, Value>, LabelError> {
+    match serde_json::to_value(base) {
+        Ok(Value::Object(map)) => Ok(map),
+        Ok(_) => Err(LabelError::ConfigLoading("Base config is not an object".to_string())),
+        Err(e) => Err(LabelError::ConfigLoading(format!("Failed to serialize base config: {}", e))),
+    }
+}
+
+fn merge_entry(base: &LabelConfig, mut entry: Map<String, Value>, index: usize) -> Result<LabelConfig, LabelError> {
+    let image_path = take_path(&mut entry, "image_path");
+    let font_path = take_path(&mut entry, "font_path");
+    let watermark_image = take_path(&mut entry, "watermark_image");
+    if let Some(hazard) = entry.remove("hazard") {
+        entry.insert("selected_hazard".to_string(), hazard);
+    }
+
+    let mut merged = base_object(base)?;
+    merged.extend(entry);
+    let mut config: LabelConfig = serde_json::from_value(Value::Object(merged))
+        .map_err(|e| LabelError::ConfigLoading(format!("Row {}: {}", index + 1, e)))?;
+    config.image_path = image_path.or_else(|| base.image_path.clone());
+    config.font_path = font_path.or_else(|| base.font_path.clone());
+    config.watermark_image = watermark_image.or_else(|| base.watermark_image.clone());
+    Ok(config)
+}
+
+// Path fields are skipped when serializing configs, so they never reach the
+// merged object and have to be picked out of the entry by hand.
+fn take_path(entry: &mut Map<String, Value>, key: &str) -> Option<PathBuf> {
+    match entry.remove(key)? {
+        Value::String(path) if !path.is_empty() => Some(PathBuf::from(path)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Hazard;
+
+    #[test]
+    fn merges_json_entries_over_base() {
+        let base = LabelConfig::default();
+        let configs = parse_json(r#"[{"scp_number": "173"}, {"scp_number": "682", "class_type": "Keter"}]"#, &base).unwrap();
+        assert_eq!(configs.len(), 2);
+        assert_eq!(configs[0].scp_number, "173");
+        assert_eq!(configs[0].class_type, base.class_type);
+        assert_eq!(configs[1].class_type, ClassType::Keter);
+    }
+
+    #[test]
+    fn reads_image_paths_and_hazard_column() {
+        let base = LabelConfig::default();
+        let configs = parse_csv("scp_number,image_path,hazard\n173,statue.png,Cognitohazard\n682,,\n", &base).unwrap();
+        assert_eq!(configs[0].image_path, Some(PathBuf::from("statue.png")));
+        assert_eq!(configs[0].selected_hazard, Some(Hazard::Cognitohazard));
+        assert_eq!(configs[1].image_path, None);
+    }
+
+    #[test]
+    fn csv_cells_follow_base_field_types() {
+        let base = LabelConfig::default();
+        let configs = parse_csv("scp_number,output_resolution,selected_hazard\n049,512,\n173,256,Cognitohazard\n", &base).unwrap();
+        assert_eq!(configs[0].scp_number, "049");
+        assert_eq!(configs[0].output_resolution, 512);
+        assert_eq!(configs[0].selected_hazard, base.selected_hazard);
+        assert_eq!(configs[1].selected_hazard, Some(Hazard::Cognitohazard));
+    }
+
+    #[test]
+    fn class_series_covers_every_class() {
+        let base = LabelConfig::default();
+        let kept = class_series(&base, false);
+        let renamed = class_series(&base, true);
+        assert_eq!(kept.len(), ClassType::all().len());
+        assert!(kept.iter().all(|config| config.object_class_text == base.object_class_text));
+        assert!(renamed.iter().all(|config| config.object_class_text == config.class_type.as_str()));
+
+        let names: std::collections::HashSet<_> = kept.iter().map(class_series_file_name).collect();
+        assert_eq!(names.len(), kept.len());
+    }
+
+    #[test]
+    fn reports_failing_row() {
+        let err = parse_csv("scp_number,class_type\n173,Safe\n096,NotAClass\n", &LabelConfig::default()).unwrap_err();
+        assert!(err.to_string().contains("Row 2"), "{}", err);
+    }
+}
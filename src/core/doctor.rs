@@ -0,0 +1,100 @@
+use super::{AssetManager, TextRenderer};
+use crate::utils::{asset_path, user_config_dir, user_texture_pack_dir};
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorCheck {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+impl DoctorCheck {
+    fn new(name: impl Into<String>, result: Result<String, String>) -> Self {
+        let (passed, detail) = match result {
+            Ok(detail) => (true, detail),
+            Err(detail) => (false, detail),
+        };
+        Self { name: name.into(), passed, detail }
+    }
+}
+
+/// Runs every setup check, continuing past failures so the report is complete.
+pub fn run_checks(pack_dir: Option<&Path>, output_dir: &Path) -> Vec<DoctorCheck> {
+    let mut checks = vec![
+        DoctorCheck::new("Assets directory", check_assets_dir()),
+        DoctorCheck::new("Templates", check_templates(pack_dir)),
+        DoctorCheck::new("Font", check_font()),
+        DoctorCheck::new("Output directory", check_writable(output_dir)),
+    ];
+
+    match user_config_dir() {
+        Some(dir) => checks.push(DoctorCheck::new("Config directory", check_writable(&dir))),
+        None => checks.push(DoctorCheck::new("Config directory", Err("no home directory found".to_string()))),
+    }
+    if let Some(dir) = pack_dir.map(Path::to_path_buf).or_else(user_texture_pack_dir) {
+        checks.push(DoctorCheck::new("Texture pack directory", check_writable(&dir)));
+    }
+    checks
+}
+
+fn check_assets_dir() -> Result<String, String> {
+    let dir = asset_path("resources");
+    match std::fs::read_dir(&dir) {
+        Ok(_) => Ok(format!("{} is readable", dir.display())),
+        Err(_) if cfg!(feature = "bundled-assets") => Ok(format!("{} not found, using bundled assets", dir.display())),
+        Err(e) => Err(format!("{}: {}", dir.display(), e)),
+    }
+}
+
+fn check_templates(pack_dir: Option<&Path>) -> Result<String, String> {
+    let assets = AssetManager::load_with_pack_dir(pack_dir).map_err(|e| e.to_string())?;
+    Ok(format!(
+        "{} class templates, {} hazard icons, {} textures",
+        assets.templates.len(),
+        assets.hazard_icons.len(),
+        assets.textures.len()
+    ))
+}
+
+fn check_font() -> Result<String, String> {
+    let renderer = TextRenderer::new().map_err(|e| e.to_string())?;
+    let (width, height, _) = renderer.measure("SCP-173", 32.0, 1.0);
+    if width == 0 || height == 0 {
+        return Err("the font rendered no glyphs".to_string());
+    }
+    Ok("loaded".to_string())
+}
+
+/// Writes a probe file into `dir`, or into its nearest existing ancestor when
+/// the directory would only be created on first use.
+fn check_writable(dir: &Path) -> Result<String, String> {
+    let existing = dir
+        .ancestors()
+        .map(|ancestor| if ancestor.as_os_str().is_empty() { Path::new(".") } else { ancestor })
+        .find(|ancestor| ancestor.is_dir())
+        .ok_or_else(|| format!("{} has no existing parent", dir.display()))?;
+
+    tempfile::NamedTempFile::new_in(existing)
+        .map_err(|e| format!("cannot write to {}: {}", existing.display(), e))?;
+    if existing == dir {
+        Ok(format!("{} is writable", dir.display()))
+    } else {
+        Ok(format!("{} can be created", dir.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writable_check_accepts_missing_directories_under_writable_parents() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(check_writable(dir.path()), Ok(format!("{} is writable", dir.path().display())));
+
+        let missing = dir.path().join("packs/nested");
+        assert_eq!(check_writable(&missing), Ok(format!("{} can be created", missing.display())));
+    }
+}
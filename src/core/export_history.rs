@@ -0,0 +1,101 @@
+use crate::models::{ClassType, LabelConfig};
+use crate::utils::{user_config_dir, LabelError};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const HISTORY_FILE: &str = "export_history.json";
+const MAX_HISTORY_ENTRIES: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportRecord {
+    pub timestamp: String,
+    pub output_path: PathBuf,
+    pub format: String,
+    pub resolution: u32,
+    pub scp_number: String,
+    pub object_class_text: String,
+    pub class_type: ClassType,
+    #[serde(default)]
+    pub config: Option<LabelConfig>,
+}
+
+impl ExportRecord {
+    pub fn new(output_path: &Path, format: &str, config: &LabelConfig) -> Self {
+        Self {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            output_path: output_path.to_path_buf(),
+            format: format.to_string(),
+            resolution: config.output_resolution,
+            scp_number: config.scp_number.clone(),
+            object_class_text: config.object_class_text.clone(),
+            class_type: config.class_type,
+            config: Some(config.clone()),
+        }
+    }
+}
+
+fn history_path() -> Option<PathBuf> {
+    user_config_dir().map(|dir| dir.join(HISTORY_FILE))
+}
+
+pub fn load_history() -> Vec<ExportRecord> {
+    history_path().map(|path| read_history(&path)).unwrap_or_default()
+}
+
+pub fn record_export(record: ExportRecord) -> Result<(), LabelError> {
+    let path = history_path()
+        .ok_or_else(|| LabelError::Io("Could not determine a user config directory".to_string()))?;
+    append_record(&path, record)
+}
+
+fn read_history(path: &Path) -> Vec<ExportRecord> {
+    match std::fs::read_to_string(path) {
+        Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+            log::warn!("Ignoring unreadable export history {}: {}", path.display(), e);
+            Vec::new()
+        }),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn append_record(path: &Path, record: ExportRecord) -> Result<(), LabelError> {
+    let mut history = read_history(path);
+    history.push(record);
+    if history.len() > MAX_HISTORY_ENTRIES {
+        history.drain(..history.len() - MAX_HISTORY_ENTRIES);
+    }
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| LabelError::Io(e.to_string()))?;
+    }
+    let json = serde_json::to_string_pretty(&history).map_err(|e| LabelError::ConfigLoading(e.to_string()))?;
+    std::fs::write(path, json).map_err(|e| LabelError::Io(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_appends_records_and_keeps_the_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join(HISTORY_FILE);
+        let config = LabelConfig { scp_number: "682".to_string(), output_resolution: 1024, ..LabelConfig::default() };
+
+        for i in 0..MAX_HISTORY_ENTRIES + 2 {
+            let output = PathBuf::from(format!("label-{}.png", i));
+            append_record(&path, ExportRecord::new(&output, "PNG", &config)).unwrap();
+        }
+
+        let history = read_history(&path);
+        assert_eq!(history.len(), MAX_HISTORY_ENTRIES);
+        assert_eq!(history[0].output_path, PathBuf::from("label-2.png"));
+        let last = history.last().unwrap();
+        assert_eq!(last.output_path, PathBuf::from(format!("label-{}.png", MAX_HISTORY_ENTRIES + 1)));
+        assert_eq!((last.format.as_str(), last.resolution, last.scp_number.as_str()), ("PNG", 1024, "682"));
+        assert_eq!(last.config.as_ref().unwrap().scp_number, "682");
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(read_history(&path).is_empty());
+    }
+}
@@ -1,20 +1,67 @@
-use crate::models::{Rectangle, ResizeMethod};
-use image::{DynamicImage, GenericImageView, RgbaImage};
+use crate::models::{BackgroundFill, Rectangle, ResizeMethod, SerializableColor};
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, ImageBuffer, Rgba, RgbaImage};
 
 pub struct ImageProcessor;
 
 impl ImageProcessor {
-    pub fn process_user_image(image: DynamicImage, method: ResizeMethod, rect: Rectangle) -> RgbaImage {
+    pub fn process_user_image(image: DynamicImage, method: ResizeMethod, rect: Rectangle, letterbox_fill: Rgba<u8>) -> RgbaImage {
         let target_width = rect.width;
         let target_height = rect.height;
 
         match method {
             ResizeMethod::CropToFit => Self::crop_to_fit(image, target_width, target_height),
             ResizeMethod::Stretch => Self::stretch(image, target_width, target_height),
-            ResizeMethod::Letterbox => Self::letterbox(image, target_width, target_height),
+            ResizeMethod::Letterbox => Self::letterbox(image, target_width, target_height, letterbox_fill),
+            ResizeMethod::BlurFill => Self::blur_fill(image, target_width, target_height),
         }
     }
 
+    /// Paints a fill into a `width`×`height` image, or returns `None` for
+    /// `BackgroundFill::None`. Gradients are sampled at pixel centres and
+    /// reach their end color at the far edge (linear) or the corners (radial).
+    pub fn render_fill(fill: &BackgroundFill, width: u32, height: u32) -> Option<RgbaImage> {
+        let (cx, cy) = (width as f32 / 2.0, height as f32 / 2.0);
+        let image = match *fill {
+            BackgroundFill::None => return None,
+            BackgroundFill::Solid(color) => RgbaImage::from_pixel(width, height, mix(color, color, 0.0)),
+            BackgroundFill::LinearGradient { from, to, angle } => {
+                let (sin, cos) = angle.to_radians().sin_cos();
+                let half_length = ((cx * cos).abs() + (cy * sin).abs()).max(f32::EPSILON);
+                RgbaImage::from_fn(width, height, |x, y| {
+                    let along = (x as f32 + 0.5 - cx) * cos + (y as f32 + 0.5 - cy) * sin;
+                    mix(from, to, 0.5 + along / (2.0 * half_length))
+                })
+            }
+            BackgroundFill::RadialGradient { inner, outer } => {
+                let radius = cx.hypot(cy).max(f32::EPSILON);
+                RgbaImage::from_fn(width, height, |x, y| {
+                    mix(inner, outer, (x as f32 + 0.5 - cx).hypot(y as f32 + 0.5 - cy) / radius)
+                })
+            }
+        };
+        Some(image)
+    }
+
+    pub fn resize_premultiplied(image: &RgbaImage, width: u32, height: u32, filter: FilterType) -> RgbaImage {
+        let premultiplied: ImageBuffer<Rgba<f32>, Vec<f32>> = ImageBuffer::from_fn(image.width(), image.height(), |x, y| {
+            let [r, g, b, a] = image.get_pixel(x, y).0.map(|c| c as f32 / 255.0);
+            Rgba([r * a, g * a, b * a, a])
+        });
+
+        let resized = image::imageops::resize(&premultiplied, width, height, filter);
+
+        RgbaImage::from_fn(width, height, |x, y| {
+            let [r, g, b, a] = resized.get_pixel(x, y).0;
+            let a = a.clamp(0.0, 1.0);
+            if a <= f32::EPSILON {
+                return Rgba([0, 0, 0, 0]);
+            }
+            let channel = |c: f32| ((c / a).clamp(0.0, 1.0) * 255.0).round() as u8;
+            Rgba([channel(r), channel(g), channel(b), (a * 255.0).round() as u8])
+        })
+    }
+
     fn crop_to_fit(image: DynamicImage, target_w: u32, target_h: u32) -> RgbaImage {
         let (img_w, img_h) = image.dimensions();
         let img_ratio = img_w as f32 / img_h as f32;
@@ -47,7 +94,7 @@ impl ImageProcessor {
         )
     }
 
-    fn letterbox(image: DynamicImage, target_w: u32, target_h: u32) -> RgbaImage {
+    fn letterbox(image: DynamicImage, target_w: u32, target_h: u32, fill: Rgba<u8>) -> RgbaImage {
         let (img_w, img_h) = image.dimensions();
         let img_ratio = img_w as f32 / img_h as f32;
         let target_ratio = target_w as f32 / target_h as f32;
@@ -65,7 +112,7 @@ impl ImageProcessor {
             image::imageops::FilterType::Lanczos3,
         );
 
-        let mut result = RgbaImage::from_pixel(target_w, target_h, image::Rgba([255, 255, 255, 255]));
+        let mut result = RgbaImage::from_pixel(target_w, target_h, fill);
 
         let x = (target_w - scale_w) / 2;
         let y = (target_h - scale_h) / 2;
@@ -73,4 +120,90 @@ impl ImageProcessor {
         image::imageops::overlay(&mut result, &scaled, x as i64, y as i64);
         result
     }
-}
\ No newline at end of file
+
+    /// Letterboxes the image over a blurred, cropped copy of itself so the
+    /// slot is filled without bars or cropping the subject.
+    fn blur_fill(image: DynamicImage, target_w: u32, target_h: u32) -> RgbaImage {
+        let sigma = target_w.max(target_h) as f32 / 24.0;
+        let mut background = image::imageops::blur(&Self::crop_to_fit(image.clone(), target_w, target_h), sigma);
+        let foreground = Self::letterbox(image, target_w, target_h, Rgba([0, 0, 0, 0]));
+        image::imageops::overlay(&mut background, &foreground, 0, 0);
+        background
+    }
+}
+
+fn mix(start: SerializableColor, end: SerializableColor, t: f32) -> Rgba<u8> {
+    let t = t.clamp(0.0, 1.0);
+    let channel = |a: f32, b: f32| ((a + (b - a) * t).clamp(0.0, 1.0) * 255.0).round() as u8;
+    Rgba([
+        channel(start.r, end.r),
+        channel(start.g, end.g),
+        channel(start.b, end.b),
+        channel(start.a, end.a),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn premultiplied_resize_has_no_dark_fringe() {
+        let icon = RgbaImage::from_fn(64, 64, |x, _| {
+            if (16..48).contains(&x) {
+                Rgba([255, 255, 255, 255])
+            } else {
+                Rgba([0, 0, 0, 0])
+            }
+        });
+
+        let resized = ImageProcessor::resize_premultiplied(&icon, 16, 16, FilterType::Lanczos3);
+
+        for pixel in resized.pixels().filter(|p| p[3] > 0) {
+            assert!(pixel[0] > 240 && pixel[1] > 240 && pixel[2] > 240, "fringe pixel {:?}", pixel);
+        }
+    }
+
+    #[test]
+    fn letterbox_bars_use_fill_color() {
+        let wide = DynamicImage::ImageRgba8(RgbaImage::from_pixel(40, 10, Rgba([255, 0, 0, 255])));
+        let rect = Rectangle { x: 0, y: 0, width: 20, height: 20 };
+
+        let transparent = ImageProcessor::process_user_image(wide.clone(), ResizeMethod::Letterbox, rect, Rgba([0, 0, 0, 0]));
+        assert_eq!(transparent.get_pixel(10, 0), &Rgba([0, 0, 0, 0]));
+        assert_eq!(transparent.get_pixel(10, 10), &Rgba([255, 0, 0, 255]));
+
+        let dark = ImageProcessor::process_user_image(wide, ResizeMethod::Letterbox, rect, Rgba([20, 20, 20, 255]));
+        assert_eq!(dark.get_pixel(10, 19), &Rgba([20, 20, 20, 255]));
+    }
+
+    #[test]
+    fn blur_fill_covers_the_slot() {
+        let wide = DynamicImage::ImageRgba8(RgbaImage::from_fn(80, 20, |x, _| {
+            if x < 40 { Rgba([255, 0, 0, 255]) } else { Rgba([0, 0, 255, 255]) }
+        }));
+        let rect = Rectangle { x: 0, y: 0, width: 40, height: 40 };
+
+        let filled = ImageProcessor::process_user_image(wide, ResizeMethod::BlurFill, rect, Rgba([255, 255, 255, 255]));
+        assert!(filled.pixels().all(|p| p[3] == 255));
+        assert_ne!(filled.get_pixel(20, 0), &Rgba([255, 255, 255, 255]));
+        assert_eq!(filled.get_pixel(5, 20), &Rgba([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn gradients_run_from_start_to_end() {
+        let (black, white) = (iced::Color::BLACK.into(), iced::Color::WHITE.into());
+        assert!(ImageProcessor::render_fill(&BackgroundFill::None, 8, 8).is_none());
+
+        let down = BackgroundFill::LinearGradient { from: black, to: white, angle: 90.0 };
+        let image = ImageProcessor::render_fill(&down, 4, 100).unwrap();
+        assert!(image.get_pixel(2, 0)[0] < 5);
+        assert!(image.get_pixel(2, 99)[0] > 250);
+        assert_eq!(image.get_pixel(0, 30), image.get_pixel(3, 30));
+
+        let radial = BackgroundFill::RadialGradient { inner: white, outer: black };
+        let image = ImageProcessor::render_fill(&radial, 100, 100).unwrap();
+        assert!(image.get_pixel(50, 50)[0] > 250);
+        assert!(image.get_pixel(0, 0)[0] < 5);
+    }
+}
@@ -0,0 +1,64 @@
+use crate::models::JpegSubsampling;
+use crate::utils::LabelError;
+use image::RgbaImage;
+
+#[cfg(not(feature = "mozjpeg"))]
+pub fn encode_jpeg(image: &RgbaImage, quality: u8, subsampling: JpegSubsampling) -> Result<Vec<u8>, LabelError> {
+    use image::codecs::jpeg::JpegEncoder;
+    use jpeg_encoder::{ColorType, Encoder, SamplingFactor};
+
+    let mut buf = Vec::new();
+
+    let sampling = match subsampling {
+        JpegSubsampling::Full => {
+            let mut encoder = JpegEncoder::new_with_quality(&mut buf, quality);
+            encoder
+                .encode_image(image)
+                .map_err(|e| LabelError::ImageSaving(format!("Failed to encode JPEG image: {}", e)))?;
+            return Ok(buf);
+        }
+        JpegSubsampling::Half => SamplingFactor::R_4_2_2,
+        JpegSubsampling::Quarter => SamplingFactor::R_4_2_0,
+    };
+
+    let (width, height) = match (u16::try_from(image.width()), u16::try_from(image.height())) {
+        (Ok(w), Ok(h)) => (w, h),
+        _ => return Err(LabelError::ImageSaving("Image is too large for JPEG encoding".to_string())),
+    };
+
+    let mut encoder = Encoder::new(&mut buf, quality);
+    encoder.set_sampling_factor(sampling);
+    encoder
+        .encode(image.as_raw(), width, height, ColorType::Rgba)
+        .map_err(|e| LabelError::ImageSaving(format!("Failed to encode JPEG image: {}", e)))?;
+    Ok(buf)
+}
+
+#[cfg(feature = "mozjpeg")]
+pub fn encode_jpeg(image: &RgbaImage, quality: u8, subsampling: JpegSubsampling) -> Result<Vec<u8>, LabelError> {
+    use mozjpeg::{ColorSpace, Compress};
+
+    let chroma = match subsampling {
+        JpegSubsampling::Full => (1, 1),
+        JpegSubsampling::Half => (2, 1),
+        JpegSubsampling::Quarter => (2, 2),
+    };
+
+    // mozjpeg reports libjpeg errors by unwinding, so they have to be caught here.
+    let result = std::panic::catch_unwind(|| -> std::io::Result<Vec<u8>> {
+        let mut compress = Compress::new(ColorSpace::JCS_EXT_RGBA);
+        compress.set_size(image.width() as usize, image.height() as usize);
+        compress.set_quality(quality as f32);
+        compress.set_chroma_sampling_pixel_sizes(chroma, chroma);
+
+        let mut started = compress.start_compress(Vec::new())?;
+        started.write_scanlines(image.as_raw())?;
+        started.finish()
+    });
+
+    match result {
+        Ok(Ok(buf)) => Ok(buf),
+        Ok(Err(e)) => Err(LabelError::ImageSaving(format!("Failed to encode JPEG image: {}", e))),
+        Err(_) => Err(LabelError::ImageSaving("mozjpeg failed to encode the image".to_string())),
+    }
+}
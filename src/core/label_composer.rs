@@ -1,20 +1,31 @@
+use super::watermark::{self, WatermarkImageCache};
 use super::{AssetManager, ImageProcessor, TextRenderer};
-use crate::core::noise_generator;
+use crate::core::{jpeg, noise_generator, png_optimizer, svg_export, webp};
 use crate::models::{
-    AlternateLayout, CommonLayout, LabelConfig, NormalLayout, LABEL_SIZE,
+    AlternateLayout, BurnType, CommonLayout, LabelConfig, LabelLayer, NormalLayout, OutputFormat, Rectangle, TextRegion, TextureBlendMode,
+    LABEL_SIZE,
 };
-use crate::utils::{LabelError, load_image_robustly};
+use crate::utils::{decode_image, LabelError, load_image_robustly};
 use image::buffer::ConvertBuffer;
-use image::{imageops, Rgba, RgbaImage, DynamicImage};
+use image::{imageops, GrayImage, Rgba, RgbaImage, DynamicImage};
+use imageproc::drawing::draw_filled_rect_mut;
+use imageproc::rect::Rect;
 use iced::Color;
-use std::path::{Path, PathBuf};
-use image::codecs::jpeg::JpegEncoder;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
 use serde::Serialize;
 
+// Mask intensity above which the label is burnt through rather than charred.
+const BURN_THROUGH_START: f32 = 0.9;
+
 #[derive(Clone, Serialize)]
 pub struct LabelComposer {
     #[serde(skip)]
     text_renderer: TextRenderer,
+    #[serde(skip)]
+    burn_masks: BurnMaskCache,
+    #[serde(skip)]
+    watermark_images: WatermarkImageCache,
 }
 
 
@@ -23,66 +34,111 @@ impl LabelComposer {
     pub fn new() -> Result<Self, LabelError> {
         Ok(Self {
             text_renderer: TextRenderer::new().map_err(|e| LabelError::TextRendering(e.to_string()))?,
+            burn_masks: BurnMaskCache::default(),
+            watermark_images: WatermarkImageCache::default(),
+        })
+    }
+
+    pub fn with_font_file(path: &Path) -> Result<Self, LabelError> {
+        let font_data = std::fs::read(path)
+            .map_err(|e| LabelError::Io(format!("Failed to read font {}: {}", path.display(), e)))?;
+        let text_renderer = TextRenderer::from_font_data(font_data).map_err(|_| {
+            LabelError::TextRendering(format!("{} is not a valid TrueType or OpenType font", path.display()))
+        })?;
+        Ok(Self {
+            text_renderer,
+            burn_masks: BurnMaskCache::default(),
+            watermark_images: WatermarkImageCache::default(),
         })
     }
 
+    pub fn for_config(config: &LabelConfig) -> Result<Self, LabelError> {
+        match &config.font_path {
+            Some(path) => Self::with_font_file(path),
+            None => Self::new(),
+        }
+    }
+
     pub fn compose(
         &self,
         config: &LabelConfig,
         assets: &AssetManager,
-        image_override: Option<&DynamicImage>,
+        user_image: Option<&DynamicImage>,
     ) -> Result<RgbaImage, LabelError> {
         log::info!("Beginning label composition.");
-        let mut canvas = assets
+        let working_size = config.working_resolution();
+        let scale = working_size as f32 / LABEL_SIZE as f32;
+        if working_size != LABEL_SIZE {
+            log::info!("Composing at {}px (supersample {}x).", working_size, config.supersample);
+        }
+
+        let template = assets
             .get_template(&config.class_type, config.use_alternate_style)
             .clone()
-            .into();
+            .try_into_rgba()?;
+        let template = fit_to_canvas(template, working_size);
+        let mut canvas = fill_background(template, config.canvas_background.into());
 
-        log::debug!("Rendering SCP number.");
-        self.render_scp_number(&mut canvas, &config);
-        
-        let object_class_region = if config.use_alternate_style {
-            AlternateLayout::OBJECT_CLASS_TEXT
-        } else {
-            CommonLayout::OBJECT_CLASS_TEXT
-        };
-        
-        log::debug!("Rendering object class.");
-        self.text_renderer.render_text(
-            &mut canvas,
-            &config.object_class_text,
-            object_class_region,
-            Rgba([
-                (Color::from(config.class_text_color).r * 255.0) as u8,
-                (Color::from(config.class_text_color).g * 255.0) as u8,
-                (Color::from(config.class_text_color).b * 255.0) as u8,
-                255,
-            ]),
-            config.object_class_font_size,
-            config.class_text_offset,
-            config.class_line_spacing,
-        );
-        
-        log::debug!("Placing user image.");
-        self.place_user_image(&mut canvas, config, image_override)?;
-        
-        log::debug!("Placing hazards.");
-        self.place_hazards(&mut canvas, config, assets);
-        
-        if config.apply_texture {
-            log::debug!("Applying texture overlay.");
-            self.apply_texture(&mut canvas, &assets.get_texture().clone().into(), config.texture_opacity);
+        for layer in config.composition_order() {
+            match layer {
+                LabelLayer::ScpNumber => {
+                    log::debug!("Rendering SCP number.");
+                    self.text_renderer.render_text(
+                        &mut canvas,
+                        &config.display_scp_number(),
+                        scp_number_region(config).scaled(scale),
+                        rgba_from_color(config.scp_text_color.into()),
+                        config.scp_number_font_size * scale,
+                        scale_offset(config.scp_text_offset, scale),
+                        config.class_line_spacing,
+                    );
+                }
+                LabelLayer::ObjectClass => {
+                    log::debug!("Rendering object class.");
+                    self.text_renderer.render_text(
+                        &mut canvas,
+                        &config.display_object_class(),
+                        object_class_region(config).scaled(scale),
+                        rgba_from_color(config.class_text_color.into()),
+                        config.object_class_font_size * scale,
+                        scale_offset(config.class_text_offset, scale),
+                        config.class_line_spacing,
+                    );
+                }
+                LabelLayer::UserImage => {
+                    log::debug!("Placing user image.");
+                    self.place_user_image(&mut canvas, config, user_image, scale)?;
+                }
+                LabelLayer::Hazard => {
+                    log::debug!("Placing hazards.");
+                    self.place_hazards(&mut canvas, config, assets, scale)?;
+                }
+                LabelLayer::Texture => {
+                    for texture_layer in config.texture_stack() {
+                        log::debug!("Applying texture overlay '{}' ({}).", texture_layer.name, texture_layer.blend);
+                        let texture = assets.get_texture(&texture_layer.name).clone().try_into_rgba()?;
+                        let texture = fit_to_canvas(tile_to_label(texture), working_size);
+                        self.apply_texture(&mut canvas, &texture, texture_layer.opacity, texture_layer.blend);
+                    }
+                }
+            }
         }
 
         if config.apply_burn {
             log::debug!("Applying burn overlay with type: {:?}", config.burn_type);
-            let burn_img = noise_generator::generate_burn_mask(config, canvas.width(), canvas.height());
-            let burn_rgba: RgbaImage = burn_img.convert();
+            let burn_rgba: RgbaImage = self.burn_masks.get(config).convert();
             self.apply_burn_overlay(&mut canvas, &burn_rgba);
         }
 
+        if let Some((stamp, x, y)) =
+            watermark::prepare_watermark(&self.text_renderer, &self.watermark_images, config, working_size, scale)?
+        {
+            log::debug!("Placing watermark.");
+            imageops::overlay(&mut canvas, &stamp, x as i64, y as i64);
+        }
+
         
-        if config.output_resolution != LABEL_SIZE {
+        if config.output_resolution != working_size {
             log::info!("Resizing final canvas to {}px.", config.output_resolution);
             canvas = imageops::resize(
                 &canvas,
@@ -91,82 +147,109 @@ impl LabelComposer {
                 imageops::FilterType::Lanczos3,
             );
         }
+
+        let bleed = config.bleed_pixels();
+        if bleed > 0 {
+            log::debug!("Adding {}px bleed.", bleed);
+            canvas = add_bleed(&canvas, bleed);
+            if config.print_marks {
+                draw_crop_marks(&mut canvas, bleed);
+            }
+        }
         
         log::info!("Label composition finished.");
         Ok(canvas)
     }
         
-    fn render_scp_number(&self, canvas: &mut RgbaImage, config: &LabelConfig) {
-        let region = if config.use_alternate_style {
-            AlternateLayout::SCP_NUMBER
-        } else {
-            CommonLayout::SCP_NUMBER
-        };
-        
-        self.text_renderer.render_text(
-            canvas,
-            &config.scp_number,
-            region,
-            Rgba([
-                (Color::from(config.scp_text_color).r * 255.0) as u8,
-                (Color::from(config.scp_text_color).g * 255.0) as u8,
-                (Color::from(config.scp_text_color).b * 255.0) as u8,
-                255,
-            ]),
-            config.scp_number_font_size,
-            config.scp_text_offset,
-            config.class_line_spacing,
-        );    
+    pub fn text_renderer(&self) -> &TextRenderer {
+        &self.text_renderer
     }
 
     fn place_user_image(
         &self,
         canvas: &mut RgbaImage,
         config: &LabelConfig,
-        image_override: Option<&DynamicImage>,
+        user_image: Option<&DynamicImage>,
+        scale: f32,
     ) -> Result<(), LabelError> {
-        if config.use_alternate_style {
+        let Some(rect) = user_image_region(config) else {
             return Ok(());
+        };
+        let rect = rect.scaled(scale);
+        if let Some(processed) = self.prepare_user_image_in(config, user_image, rect)? {
+            imageops::overlay(canvas, &processed, rect.x as i64, rect.y as i64);
         }
+        Ok(())
+    }
 
-        let user_image = match image_override {
-            Some(img) => Some(img.clone()),
-            None => {
-                if let Some(path) = &config.image_path {
-                    Some(load_image_robustly(path)?)
-                } else {
-                    None
-                }
-            }
+    pub(crate) fn prepare_user_image(
+        &self,
+        config: &LabelConfig,
+        user_image: Option<&DynamicImage>,
+    ) -> Result<Option<(RgbaImage, Rectangle)>, LabelError> {
+        let Some(rect) = user_image_region(config) else {
+            return Ok(None);
         };
+        Ok(self.prepare_user_image_in(config, user_image, rect)?.map(|image| (image, rect)))
+    }
 
-        if let Some(mut img) = user_image {
-            if config.grayscale {
-                img = img.grayscale();
-            }
-            img = img.adjust_contrast(config.contrast);
-            img = img.brighten((config.brightness * 100.0) as i32);
-            
-            let processed = ImageProcessor::process_user_image(img, config.resize_method, NormalLayout::USER_IMAGE);
-            
+    fn prepare_user_image_in(
+        &self,
+        config: &LabelConfig,
+        user_image: Option<&DynamicImage>,
+        rect: Rectangle,
+    ) -> Result<Option<RgbaImage>, LabelError> {
+        let Some(img) = user_image else {
+            return Ok(ImageProcessor::render_fill(&config.background_fill, rect.width, rect.height));
+        };
+        let mut img = img.clone();
+        if config.grayscale {
+            img = img.grayscale();
+        }
+        img = img.adjust_contrast(config.contrast);
+        img = img.brighten((config.brightness * 100.0) as i32);
+
+        Ok(Some(ImageProcessor::process_user_image(img, config.resize_method, rect, rgba_from_color(config.letterbox_color.into()))))
+    }
+        
+    fn place_hazards(
+        &self,
+        canvas: &mut RgbaImage,
+        config: &LabelConfig,
+        assets: &AssetManager,
+        scale: f32,
+    ) -> Result<(), LabelError> {
+        if let Some((resized_icon, rect)) = self.prepare_hazard_icon_scaled(config, assets, scale)? {
             imageops::overlay(
                 canvas,
-                &processed,
-                NormalLayout::USER_IMAGE.x as i64,
-                NormalLayout::USER_IMAGE.y as i64,
+                &resized_icon,
+                rect.x as i64,
+                rect.y as i64,
             );
         }
         Ok(())
     }
-        
-    fn place_hazards(
+
+    pub(crate) fn prepare_watermark(&self, config: &LabelConfig) -> Result<Option<(RgbaImage, u32, u32)>, LabelError> {
+        watermark::prepare_watermark(&self.text_renderer, &self.watermark_images, config, LABEL_SIZE, 1.0)
+    }
+
+    pub(crate) fn prepare_hazard_icon(
         &self,
-        canvas: &mut RgbaImage,
         config: &LabelConfig,
         assets: &AssetManager,
-    ) {
+    ) -> Result<Option<(RgbaImage, Rectangle)>, LabelError> {
+        self.prepare_hazard_icon_scaled(config, assets, 1.0)
+    }
+
+    fn prepare_hazard_icon_scaled(
+        &self,
+        config: &LabelConfig,
+        assets: &AssetManager,
+        scale: f32,
+    ) -> Result<Option<(RgbaImage, Rectangle)>, LabelError> {
         if let Some(hazard) = config.selected_hazard {
-            let icon: RgbaImage = assets.get_hazard_icon(&config.class_type, &hazard).clone().into();
+            let icon = assets.get_hazard_icon(&config.class_type, &hazard).clone().try_into_rgba()?;
         
             let (rect, filter) = if config.use_alternate_style {
                 (AlternateLayout::HAZARD_ICON, imageops::FilterType::Lanczos3)
@@ -174,29 +257,31 @@ impl LabelComposer {
                 (NormalLayout::HAZARD_ICON, imageops::FilterType::Lanczos3)
             };
         
-            let resized_icon = imageops::resize(&icon, rect.width, rect.height, filter);
-        
-            imageops::overlay(
-                canvas,
-                &resized_icon,
-                rect.x as i64,
-                rect.y as i64,
-            );
+            let rect = rect.scaled(scale);
+            let mut resized_icon = ImageProcessor::resize_premultiplied(&icon, rect.width, rect.height, filter);
+            let opacity = config.hazard_opacity.clamp(0.0, 1.0);
+            if opacity < 1.0 {
+                for pixel in resized_icon.pixels_mut() {
+                    pixel[3] = (pixel[3] as f32 * opacity).round() as u8;
+                }
+            }
+            return Ok(Some((resized_icon, rect)));
         }
+        Ok(None)
     }
 
-    fn apply_texture(&self, canvas: &mut RgbaImage, texture: &RgbaImage, opacity: f32) {
-        for (x, y, pixel) in canvas.enumerate_pixels_mut() {
-            if let Some(tex_pixel) = texture.get_pixel_checked(x, y) {
-                let alpha = (opacity * 255.0) as u8;
-                let blend = |c: u8, t: u8| -> u8 {
-                    ((c as u16 * (255 - alpha) as u16 + t as u16 * alpha as u16) / 255) as u8
-                };
-
-                pixel[0] = blend(pixel[0], tex_pixel[0]);
-                pixel[1] = blend(pixel[1], tex_pixel[1]);
-                pixel[2] = blend(pixel[2], tex_pixel[2]);
-            }
+    /// Blends `texture` over the canvas pixel for pixel; callers size it to the
+    /// canvas first with `tile_to_label` and `fit_to_canvas`.
+    fn apply_texture(&self, canvas: &mut RgbaImage, texture: &RgbaImage, opacity: f32, mode: TextureBlendMode) {
+        let alpha = (opacity * 255.0) as u8;
+        let blend = |c: u8, t: u8| -> u8 {
+            let t = mode.blend(c, t);
+            ((c as u16 * (255 - alpha) as u16 + t as u16 * alpha as u16) / 255) as u8
+        };
+        for (pixel, tex_pixel) in canvas.pixels_mut().zip(texture.pixels()) {
+            pixel[0] = blend(pixel[0], tex_pixel[0]);
+            pixel[1] = blend(pixel[1], tex_pixel[1]);
+            pixel[2] = blend(pixel[2], tex_pixel[2]);
         }
     }
 
@@ -209,35 +294,610 @@ impl LabelComposer {
                         pixel[i] = (pixel[i] as f32 * (1.0 - alpha)).max(10.0) as u8;
                     }
                 }
+                if alpha > BURN_THROUGH_START {
+                    let remaining = (1.0 - alpha) / (1.0 - BURN_THROUGH_START);
+                    pixel[3] = (pixel[3] as f32 * remaining) as u8;
+                }
+            }
+        }
+    }
+
+}
+
+/// Flattens onto `flatten_to` when set, and always flattens JPEG output onto
+/// `background_color` since JPEG has no alpha channel.
+pub fn flatten_for_export(image: RgbaImage, config: &LabelConfig) -> RgbaImage {
+    match config.flatten_to {
+        Some(color) => fill_background(image, Color { a: 1.0, ..color.into() }),
+        None if config.output_format == OutputFormat::Jpeg => {
+            fill_background(image, Color { a: 1.0, ..config.background_color.into() })
+        }
+        None => image,
+    }
+}
+
+fn fit_to_canvas(image: RgbaImage, size: u32) -> RgbaImage {
+    if image.dimensions() == (size, size) {
+        return image;
+    }
+    imageops::resize(&image, size, size, imageops::FilterType::Lanczos3)
+}
+
+/// Repeats a texture smaller than the label until it covers the whole label.
+pub(crate) fn tile_to_label(texture: RgbaImage) -> RgbaImage {
+    let (width, height) = texture.dimensions();
+    if width == 0 || height == 0 || (width >= LABEL_SIZE && height >= LABEL_SIZE) {
+        return texture;
+    }
+    RgbaImage::from_fn(LABEL_SIZE, LABEL_SIZE, |x, y| *texture.get_pixel(x % width, y % height))
+}
+
+/// Extends the label by `bleed` pixels on every side, repeating its edge pixels.
+fn add_bleed(label: &RgbaImage, bleed: u32) -> RgbaImage {
+    let (width, height) = label.dimensions();
+    RgbaImage::from_fn(width + 2 * bleed, height + 2 * bleed, |x, y| {
+        let x = x.saturating_sub(bleed).min(width - 1);
+        let y = y.saturating_sub(bleed).min(height - 1);
+        *label.get_pixel(x, y)
+    })
+}
+
+/// Draws L-shaped marks in the bleed area lining up with the trim edges,
+/// stopping short of the trim box so none of them end up on the label.
+fn draw_crop_marks(canvas: &mut RgbaImage, bleed: u32) {
+    let (width, height) = canvas.dimensions();
+    let thickness = (width.max(height) / 1024).max(1);
+    let length = bleed - bleed / 4;
+    if length == 0 {
+        return;
+    }
+
+    let trim_left = bleed as i32;
+    let trim_top = bleed as i32;
+    let trim_right = (width - bleed) as i32 - thickness as i32;
+    let trim_bottom = (height - bleed) as i32 - thickness as i32;
+    let far_right = (width - length) as i32;
+    let far_bottom = (height - length) as i32;
+    let color = Rgba([0, 0, 0, 255]);
+
+    for (x, y) in [(0, trim_top), (far_right, trim_top), (0, trim_bottom), (far_right, trim_bottom)] {
+        draw_filled_rect_mut(canvas, Rect::at(x, y).of_size(length, thickness), color);
+    }
+    for (x, y) in [(trim_left, 0), (trim_right, 0), (trim_left, far_bottom), (trim_right, far_bottom)] {
+        draw_filled_rect_mut(canvas, Rect::at(x, y).of_size(thickness, length), color);
+    }
+}
+
+fn scale_offset(offset: (f32, f32), scale: f32) -> (f32, f32) {
+    (offset.0 * scale, offset.1 * scale)
+}
+
+fn fill_background(template: RgbaImage, background: Color) -> RgbaImage {
+    if background.a <= 0.0 {
+        return template;
+    }
+
+    let mut canvas = RgbaImage::from_pixel(template.width(), template.height(), rgba_from_color(background));
+    imageops::overlay(&mut canvas, &template, 0, 0);
+    canvas
+}
+
+pub fn save_debug_output(config: &LabelConfig, output_dir: &Path, texture_pack_dir: Option<&Path>) -> Result<(), LabelError> {
+    let assets = AssetManager::load_with_pack_dir(texture_pack_dir)?;
+    let composer = LabelComposer::for_config(config)?;
+    let user_image = load_user_image(config)?;
+
+    std::fs::create_dir_all(output_dir)
+        .map_err(|e| LabelError::Io(format!("Failed to create debug directory {}: {}", output_dir.display(), e)))?;
+
+    let save = |image: &DynamicImage, name: &str| -> Result<(), LabelError> {
+        let path = output_dir.join(name);
+        log::debug!("Writing debug stage {}", path.display());
+        image
+            .save_with_format(&path, image::ImageFormat::Png)
+            .map_err(|e| LabelError::ImageSaving(format!("Failed to save {}: {}", path.display(), e)))
+    };
+
+    let template = assets
+        .get_template(&config.class_type, config.use_alternate_style)
+        .clone()
+        .try_into_rgba()?;
+    let canvas = fill_background(fit_to_canvas(template, config.working_resolution()), config.canvas_background.into());
+    save(&DynamicImage::ImageRgba8(canvas), "template.png")?;
+
+    if let Some((processed, _)) = composer.prepare_user_image(config, user_image.as_ref())? {
+        save(&DynamicImage::ImageRgba8(processed), "user_image.png")?;
+    }
+
+    // Taken from the composer's cache so `compose` below burns with this exact mask.
+    if config.apply_burn {
+        save(&DynamicImage::ImageLuma8((*composer.burn_masks.get(config)).clone()), "burn_mask.png")?;
+    }
+
+    let label = flatten_for_export(composer.compose(config, &assets, user_image.as_ref())?, config);
+    save(&DynamicImage::ImageRgba8(label), "label.png")
+}
+
+/// The burn mask at the config's working resolution, as `compose` applies it.
+fn working_burn_mask(config: &LabelConfig) -> GrayImage {
+    let working_size = config.working_resolution();
+    if working_size == LABEL_SIZE {
+        noise_generator::generate_burn_mask(config, working_size, working_size)
+    } else {
+        let mask = noise_generator::generate_burn_mask(config, LABEL_SIZE, LABEL_SIZE);
+        imageops::resize(&mask, working_size, working_size, imageops::FilterType::Triangle)
+    }
+}
+
+#[derive(PartialEq)]
+struct BurnMaskKey {
+    burn_type: BurnType,
+    amount: f32,
+    scale: f32,
+    detail: f32,
+    edge_softness: f32,
+    irregularity: f32,
+    char: f32,
+    seed: u32,
+    scale_multiplier: f32,
+    detail_blend: f32,
+    turbulence_freq: f32,
+    turbulence_strength: f32,
+    working_size: u32,
+}
+
+impl BurnMaskKey {
+    fn new(config: &LabelConfig) -> Self {
+        Self {
+            burn_type: config.burn_type,
+            amount: config.burn_amount,
+            scale: config.burn_scale,
+            detail: config.burn_detail,
+            edge_softness: config.burn_edge_softness,
+            irregularity: config.burn_irregularity,
+            char: config.burn_char,
+            seed: config.burn_seed,
+            scale_multiplier: config.burn_scale_multiplier,
+            detail_blend: config.burn_detail_blend,
+            turbulence_freq: config.burn_turbulence_freq,
+            turbulence_strength: config.burn_turbulence_strength,
+            working_size: config.working_resolution(),
+        }
+    }
+}
+
+/// Keeps the last burn mask so edits that don't touch the burn settings or
+/// the working resolution skip regenerating the noise. Clones share the cache,
+/// so every clone of a composer reuses the same mask.
+#[derive(Clone, Default)]
+struct BurnMaskCache(Arc<Mutex<Option<CachedBurnMask>>>);
+
+struct CachedBurnMask {
+    key: BurnMaskKey,
+    mask: Arc<GrayImage>,
+}
+
+impl BurnMaskCache {
+    fn get(&self, config: &LabelConfig) -> Arc<GrayImage> {
+        let key = BurnMaskKey::new(config);
+        let mut cached = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        match cached.as_ref() {
+            Some(cached) if cached.key == key => cached.mask.clone(),
+            _ => {
+                let mask = Arc::new(working_burn_mask(config));
+                *cached = Some(CachedBurnMask { key, mask: mask.clone() });
+                mask
             }
         }
     }
+}
+
+/// The mask `compose` burns the label with, resized to the output resolution
+/// the same way the finished label is.
+pub fn render_burn_mask(config: &LabelConfig) -> GrayImage {
+    let mask = working_burn_mask(config);
+    if mask.width() == config.output_resolution {
+        return mask;
+    }
+    imageops::resize(
+        &mask,
+        config.output_resolution,
+        config.output_resolution,
+        imageops::FilterType::Lanczos3,
+    )
+}
+
+pub fn save_burn_mask(config: &LabelConfig, output_path: &Path) -> Result<(), LabelError> {
+    render_burn_mask(config)
+        .save_with_format(output_path, image::ImageFormat::Png)
+        .map_err(|e| LabelError::ImageSaving(format!("Failed to save burn mask: {}", e)))
+}
+
+/// Where the user image goes, if anywhere. Alternate templates share their
+/// only slot with the hazard icon, so the image is opt-in there rather than
+/// drawn over the artwork by default.
+pub(crate) fn user_image_region(config: &LabelConfig) -> Option<Rectangle> {
+    match (config.use_alternate_style, config.alternate_user_image) {
+        (false, _) => Some(NormalLayout::USER_IMAGE),
+        (true, true) => Some(AlternateLayout::USER_IMAGE),
+        (true, false) => None,
+    }
+}
+
+pub(crate) fn scp_number_region(config: &LabelConfig) -> TextRegion {
+    if config.use_alternate_style {
+        AlternateLayout::SCP_NUMBER
+    } else {
+        CommonLayout::SCP_NUMBER
+    }
+}
+
+pub(crate) fn object_class_region(config: &LabelConfig) -> TextRegion {
+    if config.use_alternate_style {
+        AlternateLayout::OBJECT_CLASS_TEXT
+    } else {
+        CommonLayout::OBJECT_CLASS_TEXT
+    }
+}
 
+pub(crate) fn rgba_from_color(color: Color) -> Rgba<u8> {
+    Rgba([
+        (color.r * 255.0) as u8,
+        (color.g * 255.0) as u8,
+        (color.b * 255.0) as u8,
+        (color.a * 255.0) as u8,
+    ])
 }
 
-pub fn generate_and_save_label(config: &LabelConfig, output_path: &PathBuf) -> Result<(), LabelError> {
-    let assets = AssetManager::load_all()?;
-    let composer = LabelComposer::new()?;
-    let image = composer.compose(config, &assets, None)?;
+/// Reads and decodes the image at `config.image_path`, skipping it when the
+/// label has no image region.
+pub fn load_user_image(config: &LabelConfig) -> Result<Option<DynamicImage>, LabelError> {
+    match &config.image_path {
+        Some(path) if user_image_region(config).is_some() => load_image_robustly(path).map(Some),
+        _ => Ok(None),
+    }
+}
 
+/// Renders a label entirely in memory: the user image comes from
+/// `image_bytes` and `config.image_path` is ignored. Nothing in the app calls
+/// this yet; it is the entry point for embedding the renderer elsewhere.
+#[allow(dead_code)]
+pub fn render_label_to_png(
+    config: &LabelConfig,
+    image_bytes: Option<&[u8]>,
+    assets: &AssetManager,
+) -> Result<Vec<u8>, LabelError> {
+    let user_image = image_bytes.map(decode_image).transpose()?;
+    let image = LabelComposer::for_config(config)?.compose(config, assets, user_image.as_ref())?;
+    png_optimizer::encode_png(&flatten_for_export(image, config), config.optimize_png)
+}
+
+pub fn generate_and_save_label(
+    config: &LabelConfig,
+    output_path: &Path,
+    texture_pack_dir: Option<&Path>,
+) -> Result<(), LabelError> {
+    let assets = AssetManager::load_with_pack_dir(texture_pack_dir)?;
+    let composer = LabelComposer::for_config(config)?;
+    save_label(&composer, &assets, config, output_path)
+}
+
+pub fn save_label(
+    composer: &LabelComposer,
+    assets: &AssetManager,
+    config: &LabelConfig,
+    output_path: &Path,
+) -> Result<(), LabelError> {
+    let user_image = load_user_image(config)?;
     let output_dir = output_path.parent().unwrap_or(Path::new("."));
     std::fs::create_dir_all(output_dir)
         .map_err(|e| LabelError::Io(format!("Failed to create output directory {}: {}", output_dir.display(), e)))?;
 
-    let mut file = std::fs::File::create(output_path)
-        .map_err(|e| LabelError::Io(format!("Failed to create output file {}: {}", output_path.display(), e)))?;
+    if config.output_format == OutputFormat::Svg {
+        let svg = svg_export::compose_svg(composer, config, assets, user_image.as_ref())?;
+        std::fs::write(output_path, svg)
+            .map_err(|e| LabelError::Io(format!("Failed to write SVG file: {}", e)))?;
+        return Ok(());
+    }
+
+    let image = flatten_for_export(composer.compose(config, assets, user_image.as_ref())?, config);
+
     match config.output_format {
-        crate::models::OutputFormat::Png => {
-            image.write_to(&mut file, image::ImageFormat::Png)
-                .map_err(|e| LabelError::ImageSaving(format!("Failed to save PNG image: {}", e)))?;
-        }
-        crate::models::OutputFormat::Jpeg => {
-            let mut buf = std::io::Cursor::new(Vec::new());
-            let mut encoder = JpegEncoder::new_with_quality(&mut buf, config.output_quality);
-            encoder.encode_image(&image).map_err(|e| LabelError::ImageSaving(format!("Failed to encode JPEG image: {}", e)))?;
-            std::fs::write(output_path, buf.into_inner())
+        OutputFormat::Png | OutputFormat::Svg => {
+            let bytes = png_optimizer::encode_png(&image, config.optimize_png)?;
+            std::fs::write(output_path, bytes)
+                .map_err(|e| LabelError::Io(format!("Failed to create output file {}: {}", output_path.display(), e)))?;
+        }
+        OutputFormat::Jpeg => {
+            let bytes = jpeg::encode_jpeg(&image, config.output_quality, config.jpeg_subsampling)?;
+            std::fs::write(output_path, bytes)
                 .map_err(|e| LabelError::Io(format!("Failed to write JPEG file: {}", e)))?;
         }
+        OutputFormat::Webp => {
+            let bytes = webp::encode_webp(&image, config.output_quality, config.webp_lossless)?;
+            std::fs::write(output_path, bytes)
+                .map_err(|e| LabelError::Io(format!("Failed to write WebP file: {}", e)))?;
+        }
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Hazard, WatermarkCorner};
+    use std::path::PathBuf;
+
+    #[test]
+    fn burn_overlay_changes_composed_label() {
+        let assets = AssetManager::load_with_pack_dir(None).unwrap();
+        let composer = LabelComposer::new().unwrap();
+        let config = LabelConfig {
+            burn_amount: 1.0,
+            burn_seed: 42,
+            ..LabelConfig::default()
+        };
+
+        let plain = composer.compose(&config, &assets, None).unwrap();
+        let burnt = composer
+            .compose(&LabelConfig { apply_burn: true, ..config }, &assets, None)
+            .unwrap();
+        let changed = plain.pixels().zip(burnt.pixels()).filter(|(a, b)| a != b).count();
+        assert!(changed > 0);
+    }
+
+    const USER_IMAGE_FILL: Rgba<u8> = Rgba([0, 0, 255, 255]);
+
+    fn missing_image_config(use_alternate_style: bool) -> LabelConfig {
+        LabelConfig {
+            use_alternate_style,
+            image_path: Some(PathBuf::from("does/not/exist.png")),
+            ..LabelConfig::default()
+        }
+    }
+
+    fn user_image() -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(8, 8, USER_IMAGE_FILL))
+    }
+
+    fn assert_user_image_in(label: &RgbaImage, rect: Rectangle) {
+        assert_eq!(label.get_pixel(rect.x + rect.width / 2, rect.y + rect.height / 2), &USER_IMAGE_FILL);
+    }
+
+    #[test]
+    fn compose_never_reads_image_path() {
+        let assets = AssetManager::load_with_pack_dir(None).unwrap();
+        let composer = LabelComposer::new().unwrap();
+        let config = missing_image_config(false);
+
+        let without_image = composer.compose(&config, &assets, None).unwrap();
+        let blank = composer
+            .compose(&LabelConfig { image_path: None, ..config.clone() }, &assets, None)
+            .unwrap();
+        assert_eq!(without_image, blank);
+
+        let with_image = composer.compose(&config, &assets, Some(&user_image())).unwrap();
+        assert_user_image_in(&with_image, NormalLayout::USER_IMAGE);
+    }
+
+    #[test]
+    fn renders_png_from_image_bytes() {
+        let assets = AssetManager::load_with_pack_dir(None).unwrap();
+        let mut source = Vec::new();
+        user_image()
+            .write_to(&mut std::io::Cursor::new(&mut source), image::ImageFormat::Png)
+            .unwrap();
+        let config = missing_image_config(false);
+
+        let png = render_label_to_png(&config, Some(&source), &assets).unwrap();
+        let label = image::load_from_memory(&png).unwrap().to_rgba8();
+        assert_eq!(label.width(), config.output_resolution);
+        assert_user_image_in(&label, NormalLayout::USER_IMAGE);
+        assert!(render_label_to_png(&config, None, &assets).is_ok());
+    }
+
+    #[test]
+    fn alternate_style_places_no_user_image() {
+        let assets = AssetManager::load_with_pack_dir(None).unwrap();
+        let composer = LabelComposer::new().unwrap();
+        let config = missing_image_config(true);
+
+        assert!(load_user_image(&config).unwrap().is_none());
+        let with_image = composer.compose(&config, &assets, Some(&user_image())).unwrap();
+        let without_image = composer
+            .compose(&LabelConfig { image_path: None, ..config.clone() }, &assets, None)
+            .unwrap();
+        assert_eq!(with_image, without_image);
+
+        let opted_in = LabelConfig { alternate_user_image: true, ..config };
+        let with_image = composer.compose(&opted_in, &assets, Some(&user_image())).unwrap();
+        assert_user_image_in(&with_image, AlternateLayout::USER_IMAGE);
+    }
+
+    #[test]
+    fn hazard_opacity_scales_icon_alpha() {
+        let assets = AssetManager::load_with_pack_dir(None).unwrap();
+        let composer = LabelComposer::new().unwrap();
+        let config = LabelConfig {
+            selected_hazard: Some(Hazard::Cognitohazard),
+            ..LabelConfig::default()
+        };
+
+        let (opaque, _) = composer.prepare_hazard_icon(&config, &assets).unwrap().unwrap();
+        let (ghosted, _) = composer
+            .prepare_hazard_icon(&LabelConfig { hazard_opacity: 0.5, ..config }, &assets)
+            .unwrap()
+            .unwrap();
+        for (a, b) in opaque.pixels().zip(ghosted.pixels()) {
+            assert_eq!(b[3], (a[3] as f32 * 0.5).round() as u8);
+        }
+    }
+
+    #[test]
+    fn burn_cache_only_regenerates_for_burn_inputs() {
+        let cache = BurnMaskCache::default();
+        let config = LabelConfig { apply_burn: true, ..LabelConfig::default() };
+
+        let first = cache.get(&config);
+        let recolored = LabelConfig { scp_text_color: Color::WHITE.into(), brightness: 0.5, ..config.clone() };
+        assert!(Arc::ptr_eq(&first, &cache.get(&recolored)));
+
+        let reseeded = LabelConfig { burn_seed: config.burn_seed.wrapping_add(1), ..config.clone() };
+        assert!(!Arc::ptr_eq(&first, &cache.get(&reseeded)));
+
+        let native = LabelConfig { native_resolution: true, output_resolution: 256, ..reseeded };
+        assert_eq!(cache.get(&native).width(), 256);
+    }
+
+    #[test]
+    fn exported_burn_mask_is_the_applied_mask() {
+        // Irregularity adds unseeded jitter, so turn it off to compare masks exactly.
+        let config = LabelConfig { apply_burn: true, burn_seed: 7, burn_irregularity: 0.0, ..LabelConfig::default() };
+        assert_eq!(render_burn_mask(&config), *BurnMaskCache::default().get(&config));
+
+        let supersampled = LabelConfig { supersample: 2, output_resolution: 300, ..config };
+        let applied = BurnMaskCache::default().get(&supersampled);
+        assert_eq!(applied.width(), supersampled.working_resolution());
+        let exported = render_burn_mask(&supersampled);
+        assert_eq!(exported.dimensions(), (300, 300));
+        assert_eq!(exported, imageops::resize(&*applied, 300, 300, imageops::FilterType::Lanczos3));
+    }
+
+    #[test]
+    fn debug_stages_match_the_composed_label() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = LabelConfig {
+            apply_burn: true,
+            supersample: 2,
+            output_resolution: 300,
+            flatten_to: Some(Color::WHITE.into()),
+            ..LabelConfig::default()
+        };
+        save_debug_output(&config, dir.path(), None).unwrap();
+
+        let stage = |name: &str| image::open(dir.path().join(name)).unwrap().to_rgba8();
+        let working = config.working_resolution();
+        assert_eq!(stage("template.png").dimensions(), (working, working));
+        assert_eq!(stage("burn_mask.png").dimensions(), (working, working));
+
+        let label = stage("label.png");
+        assert_eq!(label.dimensions(), (300, 300));
+        assert!(label.pixels().all(|p| p[3] == 255));
+    }
+
+    #[test]
+    fn watermark_is_drawn_in_the_chosen_corner() {
+        let assets = AssetManager::load_with_pack_dir(None).unwrap();
+        let composer = LabelComposer::new().unwrap();
+        let config = LabelConfig { burn_seed: 1, ..LabelConfig::default() };
+        let plain = composer.compose(&config, &assets, None).unwrap();
+
+        let marked = LabelConfig {
+            watermark: Some("made by me".to_string()),
+            watermark_corner: WatermarkCorner::TopLeft,
+            ..config.clone()
+        };
+        let (stamp, x, y) = composer.prepare_watermark(&marked).unwrap().unwrap();
+        assert_eq!((x, y), (8, 8));
+        assert!(stamp.pixels().all(|p| p[3] <= (255.0 * marked.watermark_opacity).round() as u8));
+
+        let label = composer.compose(&marked, &assets, None).unwrap();
+        let differs = |x0: u32, y0: u32| {
+            (y0..y0 + stamp.height()).any(|y| (x0..x0 + stamp.width()).any(|x| label.get_pixel(x, y) != plain.get_pixel(x, y)))
+        };
+        assert!(differs(x, y));
+        assert!(!differs(LABEL_SIZE - stamp.width() - 8, LABEL_SIZE - stamp.height() - 8));
+
+        let blank = LabelConfig { watermark: Some("  ".to_string()), ..config };
+        assert!(composer.prepare_watermark(&blank).unwrap().is_none());
+    }
+
+    #[test]
+    fn multiply_texture_darkens_without_washing_out() {
+        let composer = LabelComposer::new().unwrap();
+        let texture = RgbaImage::from_pixel(1, 1, Rgba([255, 255, 255, 255]));
+        let blended = |mode| {
+            let mut canvas = RgbaImage::from_pixel(1, 1, Rgba([100, 100, 100, 255]));
+            composer.apply_texture(&mut canvas, &texture, 0.5, mode);
+            canvas.get_pixel(0, 0)[0]
+        };
+        assert_eq!(blended(TextureBlendMode::Multiply), 100);
+        assert!(blended(TextureBlendMode::Normal) > 150);
+    }
+
+    #[test]
+    fn small_textures_tile_across_the_canvas() {
+        let composer = LabelComposer::new().unwrap();
+        let texture = RgbaImage::from_pixel(64, 64, Rgba([0, 0, 0, 255]));
+        let mut canvas = RgbaImage::from_pixel(512, 512, Rgba([200, 200, 200, 255]));
+        composer.apply_texture(&mut canvas, &tile_to_label(texture), 0.5, TextureBlendMode::Normal);
+        assert!(canvas.enumerate_pixels().filter(|(x, y, _)| *x >= 256 && *y >= 256).all(|(_, _, p)| p[0] < 200));
+
+        let tiled = tile_to_label(RgbaImage::from_fn(64, 64, |x, _| Rgba([x as u8, 0, 0, 255])));
+        assert_eq!(tiled.dimensions(), (LABEL_SIZE, LABEL_SIZE));
+        assert_eq!(tiled.get_pixel(511, 511)[0], 63);
+    }
+
+    #[test]
+    fn jpeg_export_composites_transparency_over_background() {
+        let config = LabelConfig {
+            output_format: OutputFormat::Jpeg,
+            background_color: Color::from_rgb8(255, 0, 0).into(),
+            ..LabelConfig::default()
+        };
+        let canvas = RgbaImage::from_pixel(16, 16, Rgba([0, 0, 0, 0]));
+        let bytes = jpeg::encode_jpeg(&flatten_for_export(canvas, &config), 90, config.jpeg_subsampling).unwrap();
+
+        let decoded = image::load_from_memory(&bytes).unwrap().to_rgb8();
+        let pixel = decoded.get_pixel(8, 8);
+        assert!(pixel[0] > 200 && pixel[1] < 60 && pixel[2] < 60, "got {:?}", pixel);
+    }
+
+    #[test]
+    fn webp_export_writes_a_decodable_webp_file() {
+        let assets = AssetManager::load_with_pack_dir(None).unwrap();
+        let composer = LabelComposer::new().unwrap();
+        let config = LabelConfig {
+            output_format: OutputFormat::Webp,
+            output_resolution: 128,
+            webp_lossless: true,
+            ..LabelConfig::default()
+        };
+        assert_eq!(config.output_format.extension(), "webp");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("label.webp");
+        save_label(&composer, &assets, &config, &path).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(image::guess_format(&bytes).unwrap(), image::ImageFormat::WebP);
+        let decoded = image::load_from_memory(&bytes).unwrap().to_rgba8();
+        let expected = flatten_for_export(composer.compose(&config, &assets, None).unwrap(), &config);
+        assert_eq!(decoded, expected);
+    }
+
+    #[test]
+    fn crop_marks_stay_in_the_bleed() {
+        let label = RgbaImage::from_pixel(64, 64, Rgba([255, 255, 255, 255]));
+        let mut canvas = add_bleed(&label, 16);
+        draw_crop_marks(&mut canvas, 16);
+
+        assert_eq!(canvas.dimensions(), (96, 96));
+        assert_eq!(canvas.get_pixel(0, 16)[0], 0);
+        assert_eq!(canvas.get_pixel(16, 0)[0], 0);
+        assert_eq!(canvas.get_pixel(95, 79)[0], 0);
+        assert_eq!(canvas.get_pixel(0, 0)[0], 255);
+        assert!((16..80).all(|i| canvas.get_pixel(i, 16)[0] == 255 && canvas.get_pixel(16, i)[0] == 255));
+    }
+
+    #[test]
+    fn full_burn_mask_makes_pixels_transparent() {
+        let composer = LabelComposer::new().unwrap();
+        let mut canvas = RgbaImage::from_pixel(2, 1, Rgba([200, 200, 200, 255]));
+        let burn = RgbaImage::from_fn(2, 1, |x, _| if x == 0 { Rgba([255, 0, 0, 255]) } else { Rgba([128, 0, 0, 255]) });
+        composer.apply_burn_overlay(&mut canvas, &burn);
+        assert_eq!(canvas.get_pixel(0, 0)[3], 0);
+        assert_eq!(canvas.get_pixel(1, 0)[3], 255);
+        assert!(canvas.get_pixel(1, 0)[0] < 200);
+    }
+}
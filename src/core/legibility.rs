@@ -0,0 +1,125 @@
+use super::label_composer::{object_class_region, scp_number_region};
+use super::AssetManager;
+use crate::models::{LabelConfig, TextRegion};
+use crate::utils::LabelError;
+use iced::Color;
+use image::RgbaImage;
+
+/// WCAG AA minimum for large text, which label text always is.
+pub const MIN_CONTRAST_RATIO: f32 = 3.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContrastIssue {
+    pub field: &'static str,
+    pub ratio: f32,
+}
+
+pub fn low_contrast_fields(config: &LabelConfig, assets: &AssetManager) -> Result<Vec<ContrastIssue>, LabelError> {
+    let template = assets
+        .get_template(&config.class_type, config.use_alternate_style)
+        .clone()
+        .try_into_rgba()?;
+    let background: Color = config.canvas_background.into();
+
+    let fields = [
+        (
+            "SCP number",
+            config.display_scp_number(),
+            scp_number_region(config),
+            config.scp_text_color,
+            config.scp_number_font_size,
+            config.scp_text_offset,
+        ),
+        (
+            "object class",
+            config.display_object_class(),
+            object_class_region(config),
+            config.class_text_color,
+            config.object_class_font_size,
+            config.class_text_offset,
+        ),
+    ];
+
+    Ok(fields
+        .into_iter()
+        .filter(|(_, text, ..)| !text.trim().is_empty())
+        .filter_map(|(field, _, region, color, font_size, offset)| {
+            let behind = average_luminance(&template, background, region, font_size, offset)?;
+            let ratio = contrast_ratio(relative_luminance(color.into()), behind);
+            (ratio < MIN_CONTRAST_RATIO).then_some(ContrastIssue { field, ratio })
+        })
+        .collect())
+}
+
+pub fn relative_luminance(color: Color) -> f32 {
+    let linear = |c: f32| {
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(color.r) + 0.7152 * linear(color.g) + 0.0722 * linear(color.b)
+}
+
+pub fn contrast_ratio(a: f32, b: f32) -> f32 {
+    let (lighter, darker) = if a > b { (a, b) } else { (b, a) };
+    (lighter + 0.05) / (darker + 0.05)
+}
+
+// Samples the band the text block is centred on; template pixels are
+// composited over the canvas background the same way `compose` does.
+fn average_luminance(
+    template: &RgbaImage,
+    background: Color,
+    region: TextRegion,
+    font_size: f32,
+    offset: (f32, f32),
+) -> Option<f32> {
+    let half_height = font_size / 2.0;
+    let x0 = (region.x as f32 + offset.0).max(0.0) as u32;
+    let x1 = ((region.x + region.max_width) as f32 + offset.0).max(0.0) as u32;
+    let y0 = (region.y as f32 + offset.1 - half_height).max(0.0) as u32;
+    let y1 = (region.y as f32 + offset.1 + half_height).max(0.0) as u32;
+
+    let (mut total, mut count) = (0.0, 0u32);
+    for y in y0..y1.min(template.height()) {
+        for x in x0..x1.min(template.width()) {
+            let [r, g, b, a] = template.get_pixel(x, y).0;
+            let alpha = a as f32 / 255.0;
+            let blend = |channel: u8, under: f32| channel as f32 / 255.0 * alpha + under * (1.0 - alpha);
+            let pixel = Color::from_rgb(blend(r, background.r), blend(g, background.g), blend(b, background.b));
+            total += relative_luminance(pixel);
+            count += 1;
+        }
+    }
+    (count > 0).then(|| total / count as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contrast_ratio_matches_wcag_extremes() {
+        let black = relative_luminance(Color::BLACK);
+        let white = relative_luminance(Color::WHITE);
+        assert!((contrast_ratio(black, white) - 21.0).abs() < 0.01);
+        assert!((contrast_ratio(white, black) - 21.0).abs() < 0.01);
+        assert!((contrast_ratio(black, black) - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn samples_template_under_region() {
+        let template = RgbaImage::from_pixel(64, 64, image::Rgba([255, 255, 255, 255]));
+        let region = TextRegion {
+            x: 8,
+            y: 32,
+            max_width: 32,
+            alignment: crate::models::Alignment::Left,
+        };
+        let luminance = average_luminance(&template, Color::TRANSPARENT, region, 16.0, (0.0, 0.0)).unwrap();
+        assert!((luminance - 1.0).abs() < 0.001);
+        assert!(average_luminance(&template, Color::TRANSPARENT, region, 16.0, (100.0, 0.0)).is_none());
+    }
+}
@@ -1,10 +1,20 @@
 mod asset_manager;
+pub mod doctor;
+pub mod batch;
+pub mod export_history;
 pub mod label_composer;
+pub mod legibility;
 mod image_processor;
+pub mod jpeg;
+pub mod png_optimizer;
+pub mod project;
 mod text_renderer;
 mod noise_generator;
+mod watermark;
+pub mod svg_export;
+pub mod webp;
 
-pub use asset_manager::AssetManager;
+pub use asset_manager::{AssetManager, LoadProgress};
 pub use label_composer::LabelComposer;
 pub use image_processor::ImageProcessor;
 pub use text_renderer::TextRenderer;
@@ -0,0 +1,22 @@
+use crate::utils::LabelError;
+use image::RgbaImage;
+
+const OPTIMIZATION_PRESET: u8 = 2;
+
+pub fn encode_png(image: &RgbaImage, optimize: bool) -> Result<Vec<u8>, LabelError> {
+    let mut buf = std::io::Cursor::new(Vec::new());
+    image
+        .write_to(&mut buf, image::ImageFormat::Png)
+        .map_err(|e| LabelError::ImageSaving(format!("Failed to save PNG image: {}", e)))?;
+    let bytes = buf.into_inner();
+
+    if !optimize {
+        return Ok(bytes);
+    }
+
+    let options = oxipng::Options::from_preset(OPTIMIZATION_PRESET);
+    let optimized = oxipng::optimize_from_memory(&bytes, &options)
+        .map_err(|e| LabelError::ImageSaving(format!("Failed to optimize PNG image: {}", e)))?;
+    log::info!("PNG optimized from {} to {} bytes.", bytes.len(), optimized.len());
+    Ok(optimized)
+}
@@ -0,0 +1,264 @@
+use crate::models::LabelConfig;
+use crate::utils::LabelError;
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use zip::ZipArchive;
+
+const PROJECT_FORMAT_VERSION: u32 = 1;
+const PROJECT_MANIFEST_ENTRY: &str = "manifest.json";
+const PROJECT_CONFIG_ENTRY: &str = "project.json";
+const PROJECT_IMAGE_STEM: &str = "image";
+const PROJECT_THUMBNAIL_ENTRY: &str = "thumbnail.png";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ProjectManifest {
+    format_version: u32,
+    #[serde(default)]
+    image: Option<String>,
+    #[serde(default)]
+    thumbnail: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProjectData {
+    pub config: LabelConfig,
+    pub image_path: Option<PathBuf>,
+}
+
+impl ProjectData {
+    pub fn into_config(self) -> LabelConfig {
+        LabelConfig {
+            image_path: self.image_path,
+            ..self.config
+        }
+    }
+}
+
+pub fn save_project(
+    config: &LabelConfig,
+    image_path: Option<&Path>,
+    thumbnail: Option<&RgbaImage>,
+    path: &Path,
+) -> Result<(), LabelError> {
+    let file = File::create(path).map_err(|e| LabelError::Io(e.to_string()))?;
+    let mut zip = zip::ZipWriter::new(file);
+    
+    let options = zip::write::FileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    let mut manifest = ProjectManifest { format_version: PROJECT_FORMAT_VERSION, image: None, thumbnail: None };
+
+    if let Some(img_path) = image_path {
+        let img_data = std::fs::read(img_path).map_err(|e| LabelError::Io(e.to_string()))?;
+        let entry_name = format!("{}.{}", PROJECT_IMAGE_STEM, image_extension(&img_data));
+        manifest.image = Some(entry_name.clone());
+        // Re-deflating an already compressed image costs time and saves next to nothing.
+        let image_options = if is_compressed_image(&img_data) {
+            options.compression_method(zip::CompressionMethod::Stored)
+        } else {
+            options
+        };
+        zip.start_file(entry_name, image_options).map_err(|e| LabelError::Io(e.to_string()))?;
+        zip.write_all(&img_data).map_err(|e| LabelError::Io(e.to_string()))?;
+    }
+
+    if let Some(thumbnail) = thumbnail {
+        let mut png = std::io::Cursor::new(Vec::new());
+        thumbnail
+            .write_to(&mut png, image::ImageFormat::Png)
+            .map_err(|e| LabelError::ImageSaving(e.to_string()))?;
+        zip.start_file(PROJECT_THUMBNAIL_ENTRY, options.compression_method(zip::CompressionMethod::Stored))
+            .map_err(|e| LabelError::Io(e.to_string()))?;
+        zip.write_all(png.get_ref()).map_err(|e| LabelError::Io(e.to_string()))?;
+        manifest.thumbnail = Some(PROJECT_THUMBNAIL_ENTRY.to_string());
+    }
+
+    zip.start_file(PROJECT_CONFIG_ENTRY, options).map_err(|e| LabelError::Io(e.to_string()))?;
+    let json = serde_json::to_string_pretty(config).map_err(|e| LabelError::ConfigLoading(e.to_string()))?;
+    zip.write_all(json.as_bytes()).map_err(|e| LabelError::Io(e.to_string()))?;
+
+    zip.start_file(PROJECT_MANIFEST_ENTRY, options).map_err(|e| LabelError::Io(e.to_string()))?;
+    let manifest_json = serde_json::to_string_pretty(&manifest).map_err(|e| LabelError::ConfigLoading(e.to_string()))?;
+    zip.write_all(manifest_json.as_bytes()).map_err(|e| LabelError::Io(e.to_string()))?;
+
+    zip.finish().map_err(|e| LabelError::Io(e.to_string()))?;
+    Ok(())
+}
+
+fn is_compressed_image(data: &[u8]) -> bool {
+    matches!(
+        image::guess_format(data),
+        Ok(image::ImageFormat::Png
+            | image::ImageFormat::Jpeg
+            | image::ImageFormat::Gif
+            | image::ImageFormat::WebP
+            | image::ImageFormat::Avif)
+    )
+}
+
+fn image_extension(data: &[u8]) -> &'static str {
+    image::guess_format(data)
+        .ok()
+        .and_then(|format| format.extensions_str().first().copied())
+        .unwrap_or("bin")
+}
+
+fn read_project_manifest(archive: &mut ZipArchive<File>) -> Result<Option<ProjectManifest>, LabelError> {
+    let mut manifest_file = match archive.by_name(PROJECT_MANIFEST_ENTRY) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(LabelError::Io(e.to_string())),
+    };
+
+    let mut json_str = String::new();
+    manifest_file.read_to_string(&mut json_str).map_err(|e| LabelError::Io(e.to_string()))?;
+    let manifest: ProjectManifest = serde_json::from_str(&json_str)
+        .map_err(|e| LabelError::ConfigLoading(format!("Invalid project manifest: {}", e)))?;
+
+    if manifest.format_version > PROJECT_FORMAT_VERSION {
+        return Err(LabelError::ConfigLoading(format!(
+            "Project format version {} is newer than the supported version {}; please update SCP Label Maker",
+            manifest.format_version, PROJECT_FORMAT_VERSION
+        )));
+    }
+    Ok(Some(manifest))
+}
+
+pub fn load_project(path: &Path) -> Result<ProjectData, LabelError> {
+    let file = File::open(path).map_err(|e| LabelError::Io(e.to_string()))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| LabelError::Io(e.to_string()))?;
+    let manifest = read_project_manifest(&mut archive)?;
+
+    let mut json_str = String::new();
+    {
+        let mut config_file = archive.by_name(PROJECT_CONFIG_ENTRY)
+            .map_err(|_| LabelError::ConfigLoading("Missing project.json".to_string()))?;
+        config_file.read_to_string(&mut json_str).map_err(|e| LabelError::Io(e.to_string()))?;
+    }
+    let config: LabelConfig = serde_json::from_str(&json_str).map_err(|e| LabelError::ConfigLoading(e.to_string()))?;
+
+    // Projects without a manifest predate it and may store the image as a bare "image" entry.
+    let image_name = match manifest {
+        Some(manifest) => manifest.image,
+        None => archive
+            .file_names()
+            .find(|name| {
+                name.strip_prefix(PROJECT_IMAGE_STEM)
+                    .is_some_and(|rest| rest.is_empty() || (rest.starts_with('.') && !rest.contains('/')))
+            })
+            .map(str::to_string),
+    };
+
+    let mut image_path = None;
+    if let Some(name) = image_name {
+        let mut buffer = Vec::new();
+        let mut image_file = archive.by_name(&name).map_err(|e| LabelError::Io(e.to_string()))?;
+        image_file.read_to_end(&mut buffer).map_err(|e| LabelError::Io(e.to_string()))?;
+
+        image::guess_format(&buffer).map_err(|_| LabelError::ImageProcessing("Unknown format".to_string()))?;
+        let ext = image_extension(&buffer);
+
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("temp");
+        let temp_path = std::env::temp_dir().join(format!("scp_proj_{}.{}", stem, ext));
+        std::fs::write(&temp_path, buffer).map_err(|e| LabelError::Io(e.to_string()))?;
+        image_path = Some(temp_path);
+    }
+
+    Ok(ProjectData { config, image_path })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ClassType, Hazard};
+
+    #[test]
+    fn project_round_trip_restores_config_and_image() {
+        let dir = tempfile::tempdir().unwrap();
+        let image_path = dir.path().join("source.png");
+        let source = image::RgbaImage::from_fn(4, 3, |x, y| image::Rgba([x as u8 * 60, y as u8 * 80, 30, 255]));
+        source.save(&image_path).unwrap();
+
+        let config = LabelConfig {
+            scp_number: "049".to_string(),
+            object_class_text: "EUCLID".to_string(),
+            class_type: ClassType::Euclid,
+            selected_hazard: Some(Hazard::BiologicalHazard),
+            output_resolution: 1024,
+            image_path: Some(image_path),
+            ..LabelConfig::default()
+        };
+
+        let project_path = dir.path().join("label.scp");
+        let thumbnail = image::RgbaImage::from_pixel(16, 16, image::Rgba([1, 2, 3, 255]));
+        save_project(&config, config.image_path.as_deref(), Some(&thumbnail), &project_path).unwrap();
+        let loaded = load_project(&project_path).unwrap().into_config();
+
+        assert_eq!(
+            serde_json::to_value(&loaded).unwrap(),
+            serde_json::to_value(&config).unwrap()
+        );
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(dir.path().join("label.scp")).unwrap()).unwrap();
+        assert_eq!(archive.by_name("image.png").unwrap().compression(), zip::CompressionMethod::Stored);
+        assert_eq!(archive.by_name(PROJECT_CONFIG_ENTRY).unwrap().compression(), zip::CompressionMethod::Deflated);
+        let mut thumbnail_png = Vec::new();
+        archive.by_name(PROJECT_THUMBNAIL_ENTRY).unwrap().read_to_end(&mut thumbnail_png).unwrap();
+        assert_eq!(image::load_from_memory(&thumbnail_png).unwrap().to_rgba8(), thumbnail);
+
+        let restored_path = loaded.image_path.expect("embedded image should restore image_path");
+        let restored = image::open(restored_path).unwrap().to_rgba8();
+        assert_eq!(restored, source);
+    }
+
+    #[test]
+    fn legacy_project_image_is_found_among_extra_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_path = dir.path().join("legacy.scp");
+        let source = image::RgbaImage::from_pixel(2, 2, image::Rgba([200, 100, 50, 255]));
+        let mut png = std::io::Cursor::new(Vec::new());
+        source.write_to(&mut png, image::ImageFormat::Png).unwrap();
+
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&project_path).unwrap());
+        let options = zip::write::FileOptions::default();
+        zip.start_file("font.ttf", options).unwrap();
+        zip.write_all(b"not an image").unwrap();
+        zip.start_file("image", options).unwrap();
+        zip.write_all(png.get_ref()).unwrap();
+        zip.start_file(PROJECT_CONFIG_ENTRY, options).unwrap();
+        zip.write_all(serde_json::to_string(&LabelConfig::default()).unwrap().as_bytes()).unwrap();
+        zip.finish().unwrap();
+
+        let loaded = load_project(&project_path).unwrap();
+        let restored = image::open(loaded.image_path.unwrap()).unwrap().to_rgba8();
+        assert_eq!(restored, source);
+    }
+
+    #[test]
+    fn newer_project_version_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_path = dir.path().join("future.scp");
+
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&project_path).unwrap());
+        zip.start_file(PROJECT_MANIFEST_ENTRY, zip::write::FileOptions::default()).unwrap();
+        zip.write_all(format!(r#"{{"format_version": {}}}"#, PROJECT_FORMAT_VERSION + 1).as_bytes()).unwrap();
+        zip.finish().unwrap();
+
+        let err = load_project(&project_path).unwrap_err();
+        assert!(matches!(err, LabelError::ConfigLoading(msg) if msg.contains("newer")));
+    }
+
+    #[test]
+    fn project_without_image_has_no_image_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_path = dir.path().join("empty.scp");
+
+        save_project(&LabelConfig::default(), None, None, &project_path).unwrap();
+        let loaded = load_project(&project_path).unwrap();
+
+        assert!(loaded.image_path.is_none());
+    }
+}
@@ -0,0 +1,203 @@
+use super::label_composer::{object_class_region, scp_number_region, tile_to_label};
+use super::{noise_generator, AssetManager, LabelComposer};
+use crate::models::{LabelConfig, LabelLayer, TextRegion, TextureBlendMode, LABEL_SIZE};
+use crate::utils::{color, LabelError};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use iced::Color;
+use image::{DynamicImage, Rgba, RgbaImage};
+use std::borrow::Cow;
+use std::fmt::Write;
+
+pub fn compose_svg(
+    composer: &LabelComposer,
+    config: &LabelConfig,
+    assets: &AssetManager,
+    user_image: Option<&DynamicImage>,
+) -> Result<String, LabelError> {
+    log::info!("Beginning SVG label composition.");
+    if config.bleed > 0 {
+        log::warn!("SVG export does not support bleed; exporting the trimmed label.");
+    }
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{res}" height="{res}" viewBox="0 0 {size} {size}">"#,
+        res = config.output_resolution,
+        size = LABEL_SIZE,
+    );
+
+    if let Some(flatten_to) = config.flatten_to {
+        let _ = writeln!(
+            svg,
+            r#"  <rect width="{size}" height="{size}" fill="{}"/>"#,
+            color::to_hex(Color { a: 1.0, ..flatten_to.into() }),
+            size = LABEL_SIZE,
+        );
+    }
+
+    let background: Color = config.canvas_background.into();
+    if background.a > 0.0 {
+        let _ = writeln!(
+            svg,
+            r#"  <rect width="{size}" height="{size}" fill="{}" fill-opacity="{:.3}"/>"#,
+            color::to_hex(Color { a: 1.0, ..background }),
+            background.a,
+            size = LABEL_SIZE,
+        );
+    }
+
+    let template = assets
+        .get_template(&config.class_type, config.use_alternate_style)
+        .clone()
+        .try_into_rgba()?;
+    embed_image(&mut svg, &template, 0, 0, None, "")?;
+
+    let scp_number = SvgText {
+        text: config.display_scp_number(),
+        region: scp_number_region(config),
+        fill: config.scp_text_color.into(),
+        font_size: config.scp_number_font_size,
+        offset: config.scp_text_offset,
+        line_spacing: config.class_line_spacing,
+    };
+    let object_class = SvgText {
+        text: config.display_object_class(),
+        region: object_class_region(config),
+        fill: config.class_text_color.into(),
+        font_size: config.object_class_font_size,
+        offset: config.class_text_offset,
+        line_spacing: config.class_line_spacing,
+    };
+
+    for layer in config.composition_order() {
+        match layer {
+            LabelLayer::ScpNumber => write_text(&mut svg, composer, &scp_number),
+            LabelLayer::ObjectClass => write_text(&mut svg, composer, &object_class),
+            LabelLayer::UserImage => {
+                if let Some((user_image, rect)) = composer.prepare_user_image(config, user_image)? {
+                    embed_image(&mut svg, &user_image, rect.x, rect.y, None, "")?;
+                }
+            }
+            LabelLayer::Hazard => {
+                if let Some((icon, rect)) = composer.prepare_hazard_icon(config, assets)? {
+                    embed_image(&mut svg, &icon, rect.x, rect.y, None, "")?;
+                }
+            }
+            LabelLayer::Texture => {
+                for texture_layer in config.texture_stack() {
+                    let mut texture = tile_to_label(assets.get_texture(&texture_layer.name).clone().try_into_rgba()?);
+                    for pixel in texture.pixels_mut() {
+                        pixel[3] = 255;
+                    }
+                    let style = match texture_layer.blend {
+                        TextureBlendMode::Normal => String::new(),
+                        mode => format!(r#" style="mix-blend-mode:{}""#, mode.to_string().to_lowercase()),
+                    };
+                    embed_image(&mut svg, &texture, 0, 0, Some(texture_layer.opacity), &style)?;
+                }
+            }
+        }
+    }
+
+    if config.apply_burn {
+        let mask = noise_generator::generate_burn_mask(config, LABEL_SIZE, LABEL_SIZE);
+        let burn = RgbaImage::from_fn(LABEL_SIZE, LABEL_SIZE, |x, y| Rgba([0, 0, 0, mask.get_pixel(x, y)[0]]));
+        embed_image(&mut svg, &burn, 0, 0, None, "")?;
+    }
+
+    if let Some((stamp, x, y)) = composer.prepare_watermark(config)? {
+        embed_image(&mut svg, &stamp, x, y, None, "")?;
+    }
+
+    svg.push_str("</svg>\n");
+    log::info!("SVG label composition finished.");
+    Ok(svg)
+}
+
+struct SvgText<'a> {
+    text: Cow<'a, str>,
+    region: TextRegion,
+    fill: Color,
+    font_size: f32,
+    offset: (f32, f32),
+    line_spacing: f32,
+}
+
+fn write_text(svg: &mut String, composer: &LabelComposer, layer: &SvgText) {
+    let path = composer
+        .text_renderer()
+        .outline_path(&layer.text, layer.region, layer.font_size, layer.offset, layer.line_spacing);
+    if path.is_empty() {
+        return;
+    }
+    let opacity = if layer.fill.a < 1.0 {
+        format!(r#" fill-opacity="{:.3}""#, layer.fill.a)
+    } else {
+        String::new()
+    };
+    let _ = writeln!(
+        svg,
+        r#"  <path d="{}" fill="{}"{}/>"#,
+        path,
+        color::to_hex(Color { a: 1.0, ..layer.fill }),
+        opacity
+    );
+}
+
+fn embed_image(
+    svg: &mut String,
+    image: &RgbaImage,
+    x: u32,
+    y: u32,
+    opacity: Option<f32>,
+    extra_attributes: &str,
+) -> Result<(), LabelError> {
+    let mut png = std::io::Cursor::new(Vec::new());
+    image
+        .write_to(&mut png, image::ImageFormat::Png)
+        .map_err(|e| LabelError::ImageSaving(format!("Failed to encode embedded PNG: {}", e)))?;
+
+    let opacity = opacity
+        .map(|o| format!(r#" opacity="{:.3}""#, o.clamp(0.0, 1.0)))
+        .unwrap_or_default();
+    let _ = writeln!(
+        svg,
+        r#"  <image x="{}" y="{}" width="{}" height="{}"{}{} href="data:image/png;base64,{}"/>"#,
+        x,
+        y,
+        image.width(),
+        image.height(),
+        opacity,
+        extra_attributes,
+        STANDARD.encode(png.into_inner()),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Hazard;
+
+    #[test]
+    fn svg_has_vector_text_and_embedded_layers() {
+        let assets = AssetManager::load_with_pack_dir(None).unwrap();
+        let composer = LabelComposer::new().unwrap();
+        let config = LabelConfig {
+            selected_hazard: Some(Hazard::Cognitohazard),
+            class_text_color: Color { a: 0.5, ..Color::BLACK }.into(),
+            ..LabelConfig::default()
+        };
+        let user_image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(8, 8, Rgba([255, 0, 0, 255])));
+
+        let svg = compose_svg(&composer, &config, &assets, Some(&user_image)).unwrap();
+
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="512" height="512""#));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<path ").count(), 2);
+        assert_eq!(svg.matches(r#" fill-opacity="0.500""#).count(), 1);
+        // Template, user image and hazard icon.
+        assert_eq!(svg.matches("<image ").count(), 3);
+    }
+}
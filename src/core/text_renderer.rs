@@ -1,14 +1,18 @@
 use crate::models::{Alignment, TextRegion};
-use crate::utils::LabelError;
-use rusttype::{Font, Scale};
+use crate::utils::{asset_path, LabelError};
+use rusttype::{point, Font, GlyphId, OutlineBuilder, PositionedGlyph, Scale};
+use std::fmt::Write;
+use std::path::Path;
 use image::{Rgba, RgbaImage};
-use imageproc::drawing::{draw_text_mut, text_size};
 use serde::{Serialize, Deserialize};
 use serde_bytes;
+use unicode_bidi::BidiInfo;
+
+const FALLBACK_FONT_DIR: &str = "resources/fonts";
 
 #[derive(Clone)]
 pub struct TextRenderer {
-    font: Font<'static>,
+    fonts: Vec<Font<'static>>,
 }
 
 impl TextRenderer {
@@ -17,7 +21,83 @@ impl TextRenderer {
         let font = Font::try_from_bytes(font_bytes)
             .ok_or_else(|| LabelError::TextRendering("Failed to load font".to_string()))?;
 
-        Ok(Self { font })
+        Ok(Self::with_fallbacks(font))
+    }
+
+    fn with_fallbacks(primary: Font<'static>) -> Self {
+        let mut fonts = vec![primary];
+        fonts.extend(Self::load_fallback_fonts(&asset_path(FALLBACK_FONT_DIR)));
+        Self { fonts }
+    }
+
+    fn load_fallback_fonts(dir: &Path) -> Vec<Font<'static>> {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Vec::new();
+        };
+
+        let mut paths: Vec<_> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                matches!(
+                    path.extension().and_then(|s| s.to_str()).map(str::to_lowercase).as_deref(),
+                    Some("ttf" | "otf")
+                )
+            })
+            .collect();
+        paths.sort();
+
+        paths
+            .into_iter()
+            .filter_map(|path| {
+                let font = std::fs::read(&path).ok().and_then(Font::try_from_vec);
+                match &font {
+                    Some(_) => log::info!("Loaded fallback font {}", path.display()),
+                    None => log::warn!("Failed to load fallback font {}", path.display()),
+                }
+                font
+            })
+            .collect()
+    }
+
+    fn font_index_for(&self, c: char) -> usize {
+        if c.is_whitespace() || c.is_control() {
+            return 0;
+        }
+        self.fonts
+            .iter()
+            .position(|font| font.glyph(c).id() != GlyphId(0))
+            .unwrap_or(0)
+    }
+
+    fn layout_glyphs(&self, line: &str, scale: Scale) -> Vec<PositionedGlyph<'static>> {
+        let ascent = self.fonts[0].v_metrics(scale).ascent;
+        let mut caret = 0.0;
+        let mut last: Option<(usize, GlyphId)> = None;
+        let mut glyphs = Vec::new();
+
+        for c in line.chars() {
+            let font_index = self.font_index_for(c);
+            let font = &self.fonts[font_index];
+            let glyph = font.glyph(c).scaled(scale);
+            if let Some((last_index, last_id)) = last {
+                if last_index == font_index {
+                    caret += font.pair_kerning(scale, last_id, glyph.id());
+                }
+            }
+            let advance = glyph.h_metrics().advance_width;
+            last = Some((font_index, glyph.id()));
+            glyphs.push(glyph.positioned(point(caret, ascent)));
+            caret += advance;
+        }
+        glyphs
+    }
+
+    fn line_size(&self, line: &str, scale: Scale) -> (i32, i32) {
+        self.layout_glyphs(line, scale)
+            .iter()
+            .filter_map(|glyph| glyph.pixel_bounding_box())
+            .fold((0, 0), |(w, h), bb| (w.max(bb.max.x), h.max(bb.max.y)))
     }
 
 pub fn render_text(
@@ -30,33 +110,117 @@ pub fn render_text(
         offset: (f32, f32),
         line_spacing_multiplier: f32,
     ) {
+        let scale = Scale::uniform(font_size);
+
+        for (line, x, y) in self.layout_lines(text, region, font_size, offset, line_spacing_multiplier) {
+            self.draw_line_blended(canvas, color, x, y, scale, &line);
+        }
+    }
+
+    fn draw_line_blended(&self, canvas: &mut RgbaImage, color: Rgba<u8>, x: i32, y: i32, scale: Scale, line: &str) {
+        let opacity = color[3] as f32 / 255.0;
+        let (width, height) = (canvas.width() as i32, canvas.height() as i32);
+
+        for glyph in self.layout_glyphs(line, scale) {
+            let Some(bb) = glyph.pixel_bounding_box() else {
+                continue;
+            };
+
+            glyph.draw(|gx, gy, gv| {
+                let px = gx as i32 + bb.min.x + x;
+                let py = gy as i32 + bb.min.y + y;
+                if !(0..width).contains(&px) || !(0..height).contains(&py) {
+                    return;
+                }
+
+                let coverage = gv * opacity;
+                let pixel = canvas.get_pixel_mut(px as u32, py as u32);
+                for i in 0..3 {
+                    pixel[i] = (pixel[i] as f32 * (1.0 - coverage) + color[i] as f32 * coverage).round() as u8;
+                }
+                pixel[3] = (pixel[3] as f32 * (1.0 - coverage) + 255.0 * coverage).round() as u8;
+            });
+        }
+    }
+
+    pub fn outline_path(
+        &self,
+        text: &str,
+        region: TextRegion,
+        font_size: f32,
+        offset: (f32, f32),
+        line_spacing_multiplier: f32,
+    ) -> String {
+        let scale = Scale::uniform(font_size);
+        let mut builder = SvgPathBuilder::default();
+
+        for (line, x, y) in self.layout_lines(text, region, font_size, offset, line_spacing_multiplier) {
+            for glyph in self.layout_glyphs(&line, scale) {
+                let position = glyph.position();
+                builder.origin = (x as f32 + position.x, y as f32 + position.y);
+                glyph.unpositioned().build_outline(&mut builder);
+            }
+        }
+
+        builder.path.trim_end().to_string()
+    }
+
+    pub fn measure(&self, text: &str, font_size: f32, line_spacing_multiplier: f32) -> (u32, u32, usize) {
         if text.is_empty() {
-            return;
+            return (0, 0, 0);
         }
 
         let scale = Scale::uniform(font_size);
-        
-        let processed_text = text.replace("\\n", "\n");
-        let lines: Vec<&str> = processed_text.split('\n').collect();
-        
-        let (_, glyph_height) = text_size(scale, &self.font, "Hg"); 
+        let lines = split_lines(text);
+        let (_, block_height) = self.block_metrics(scale, lines.len(), line_spacing_multiplier);
+
+        let width = lines
+            .iter()
+            .map(|line| self.line_size(line, scale).0.max(0))
+            .max()
+            .unwrap_or(0);
+
+        (width as u32, block_height.max(0) as u32, lines.len())
+    }
+
+    fn block_metrics(&self, scale: Scale, line_count: usize, line_spacing_multiplier: f32) -> (i32, i32) {
+        let (_, glyph_height) = self.line_size("Hg", scale); 
         let line_spacing = (glyph_height as f32 * line_spacing_multiplier) as i32;
         
-        let total_block_height = if lines.len() > 1 {
-            (lines.len() as i32 - 1) * line_spacing + glyph_height as i32
+        let total_block_height = if line_count > 1 {
+            (line_count as i32 - 1) * line_spacing + glyph_height
         } else {
-            glyph_height as i32
+            glyph_height
         };
+        (line_spacing, total_block_height)
+    }
+
+    fn layout_lines(
+        &self,
+        text: &str,
+        region: TextRegion,
+        font_size: f32,
+        offset: (f32, f32),
+        line_spacing_multiplier: f32,
+    ) -> Vec<(String, i32, i32)> {
+        if text.is_empty() {
+            return Vec::new();
+        }
+
+        let scale = Scale::uniform(font_size);
+        let lines = split_lines(text);
+        let (line_spacing, total_block_height) = self.block_metrics(scale, lines.len(), line_spacing_multiplier);
 
+        let mut positioned = Vec::new();
         for (i, line) in lines.iter().enumerate() {
             if line.trim().is_empty() && lines.len() > 1 { continue; }
 
-            let (text_w, _) = text_size(scale, &self.font, line);
+            let (text_w, _) = self.line_size(line, scale);
 
             let x = match region.alignment {
                 Alignment::Left => region.x as i32,
-                Alignment::Center => (region.x + region.max_width / 2) as i32 - (text_w / 2) as i32,
-                Alignment::Right => (region.x + region.max_width) as i32 - text_w as i32,
+                Alignment::Center => (region.x + region.max_width / 2) as i32 - (text_w / 2),
+                Alignment::Right => (region.x + region.max_width) as i32 - text_w,
                 Alignment::CenterLeft => region.x as i32,
             } + offset.0 as i32;
 
@@ -64,8 +228,9 @@ pub fn render_text(
                     + (i as i32 * line_spacing) 
                     + offset.1 as i32;
 
-            draw_text_mut(canvas, color, x, y, scale, &self.font, line);
+            positioned.push((line.clone(), x, y));
         }
+        positioned
     }
     pub fn render_text_with_stroke(
         &self,
@@ -82,41 +247,103 @@ pub fn render_text(
         }
 
         let scale = Scale::uniform(font_size);
-        let (text_w, text_h) = text_size(scale, &self.font, text);
+        let (text_w, text_h) = self.line_size(text, scale);
 
         let x = match region.alignment {
             Alignment::Left => region.x as i32,
-            Alignment::Center => (region.x + region.max_width / 2) as i32 - (text_w / 2) as i32,
-            Alignment::Right => (region.x + region.max_width) as i32 - text_w as i32,
+            Alignment::Center => (region.x + region.max_width / 2) as i32 - (text_w / 2),
+            Alignment::Right => (region.x + region.max_width) as i32 - text_w,
             Alignment::CenterLeft => region.x as i32,
         } + offset.0 as i32;
 
-        let y = (region.y as i32 - text_h as i32 / 2) + offset.1 as i32;
+        let y = (region.y as i32 - text_h / 2) + offset.1 as i32;
 
         for dx in -2..=2 {
             for dy in -2..=2 {
                 if dx != 0 || dy != 0 {
-                    draw_text_mut(
-                        canvas,
-                        stroke_color,
-                        x + dx,
-                        y + dy,
-                        scale,
-                        &self.font,
-                        text,
-                    );
+                    self.draw_line_blended(canvas, stroke_color, x + dx, y + dy, scale, text);
                 }
             }
         }
 
-        draw_text_mut(canvas, color, x, y, scale, &self.font, text);
+        self.draw_line_blended(canvas, color, x, y, scale, text);
+    }
+
+    pub fn render_sample(&self, text: &str, font_size: f32, color: Rgba<u8>) -> RgbaImage {
+        const PADDING: u32 = 4;
+        let (width, height, _) = self.measure(text, font_size, 1.0);
+        let mut canvas = RgbaImage::new(width + PADDING * 2, height + PADDING * 2);
+        let region = TextRegion {
+            x: PADDING,
+            y: PADDING + height / 2,
+            max_width: width,
+            alignment: Alignment::Left,
+        };
+        self.render_text(&mut canvas, text, region, color, font_size, (0.0, 0.0), 1.0);
+        canvas
     }
 
     pub fn from_font_data(font_data: Vec<u8>) -> Result<Self, LabelError> {
-        let leaked_font_data: &'static [u8] = Box::leak(font_data.into_boxed_slice());
-        let font = Font::try_from_bytes(leaked_font_data)
+        let font = Font::try_from_vec(font_data)
             .ok_or_else(|| LabelError::TextRendering("Failed to load font".to_string()))?;
-        Ok(Self { font })
+        Ok(Self::with_fallbacks(font))
+    }
+}
+
+fn split_lines(text: &str) -> Vec<String> {
+    text.replace("\\n", "\n").split('\n').map(visual_order).collect()
+}
+
+fn visual_order(line: &str) -> String {
+    let bidi = BidiInfo::new(line, None);
+    if !bidi.has_rtl() {
+        return line.to_string();
+    }
+
+    bidi.paragraphs
+        .iter()
+        .map(|paragraph| bidi.reorder_line(paragraph, paragraph.range.clone()))
+        .collect()
+}
+
+#[derive(Default)]
+struct SvgPathBuilder {
+    path: String,
+    origin: (f32, f32),
+}
+
+impl SvgPathBuilder {
+    fn point(&self, x: f32, y: f32) -> (f32, f32) {
+        (x + self.origin.0, y + self.origin.1)
+    }
+}
+
+impl OutlineBuilder for SvgPathBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.point(x, y);
+        let _ = write!(self.path, "M{:.2} {:.2} ", x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.point(x, y);
+        let _ = write!(self.path, "L{:.2} {:.2} ", x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (x1, y1) = self.point(x1, y1);
+        let (x, y) = self.point(x, y);
+        let _ = write!(self.path, "Q{:.2} {:.2} {:.2} {:.2} ", x1, y1, x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x1, y1) = self.point(x1, y1);
+        let (x2, y2) = self.point(x2, y2);
+        let (x, y) = self.point(x, y);
+        let _ = write!(self.path, "C{:.2} {:.2} {:.2} {:.2} {:.2} {:.2} ", x1, y1, x2, y2, x, y);
+    }
+
+    fn close(&mut self) {
+        self.path.push_str("Z ");
     }
 }
 
@@ -140,4 +367,94 @@ impl SerializableTextRenderer {
     pub fn to_text_renderer(&self) -> Result<TextRenderer, LabelError> {
         TextRenderer::from_font_data(self.font_bytes.clone())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn visual_order_keeps_ltr_and_reorders_rtl() {
+        assert_eq!(visual_order("SCP-173"), "SCP-173");
+        assert_eq!(visual_order("אבג"), "גבא");
+        assert_eq!(visual_order("SCP-אבג"), "SCP-גבא");
+    }
+
+    #[test]
+    fn measure_empty_text() {
+        let renderer = TextRenderer::new().unwrap();
+        assert_eq!(renderer.measure("", 60.0, 1.2), (0, 0, 0));
+    }
+
+    #[test]
+    fn measure_counts_lines_and_grows_with_content() {
+        let renderer = TextRenderer::new().unwrap();
+        let (short_w, single_h, single_lines) = renderer.measure("SCP", 60.0, 1.2);
+        let (long_w, _, _) = renderer.measure("SCP-173", 60.0, 1.2);
+        let (multi_w, multi_h, multi_lines) = renderer.measure("SCP\\nSCP-173", 60.0, 1.2);
+
+        assert_eq!(single_lines, 1);
+        assert_eq!(multi_lines, 2);
+        assert!(long_w > short_w);
+        assert_eq!(multi_w, long_w);
+        assert!(multi_h > single_h);
+    }
+
+    #[test]
+    fn sample_fits_rendered_text() {
+        let renderer = TextRenderer::new().unwrap();
+        let sample = renderer.render_sample("SCP-173 SAFE", 32.0, Rgba([255, 255, 255, 255]));
+        let (width, height, _) = renderer.measure("SCP-173 SAFE", 32.0, 1.0);
+
+        assert!(sample.width() > width && sample.height() > height);
+        assert!(sample.pixels().any(|p| p[3] == 255));
+        assert!(TextRenderer::from_font_data(b"not a font".to_vec()).is_err());
+    }
+
+    #[test]
+    fn opaque_text_fully_covers_glyph_interiors() {
+        let renderer = TextRenderer::new().unwrap();
+        let scale = Scale::uniform(80.0);
+        let mut canvas = RgbaImage::new(200, 100);
+        renderer.draw_line_blended(&mut canvas, Rgba([10, 20, 30, 255]), 0, 0, scale, "SCP");
+
+        let mut covered = 0;
+        for glyph in renderer.layout_glyphs("SCP", scale) {
+            let Some(bb) = glyph.pixel_bounding_box() else {
+                continue;
+            };
+            glyph.draw(|gx, gy, gv| {
+                if gv >= 0.999 {
+                    covered += 1;
+                    let pixel = canvas.get_pixel((gx as i32 + bb.min.x) as u32, (gy as i32 + bb.min.y) as u32);
+                    assert_eq!(pixel, &Rgba([10, 20, 30, 255]));
+                }
+            });
+        }
+        assert!(covered > 0);
+    }
+
+    #[test]
+    fn missing_glyphs_render_through_bundled_fallback() {
+        let renderer = TextRenderer::new().unwrap();
+        assert_eq!(renderer.fonts[0].glyph('☢').id(), GlyphId(0));
+        assert!(renderer.font_index_for('☢') > 0);
+
+        let mut canvas = RgbaImage::new(100, 100);
+        renderer.draw_line_blended(&mut canvas, Rgba([10, 20, 30, 255]), 0, 0, Scale::uniform(80.0), "☢");
+        assert!(canvas.pixels().any(|p| p[3] > 0));
+    }
+
+    #[test]
+    fn semi_transparent_text_blends_with_canvas() {
+        let renderer = TextRenderer::new().unwrap();
+        let mut canvas = RgbaImage::from_pixel(200, 100, Rgba([255, 255, 255, 255]));
+        let region = TextRegion { x: 0, y: 0, max_width: 200, alignment: Alignment::Left };
+        renderer.render_text(&mut canvas, "SCP", region, Rgba([0, 0, 0, 128]), 80.0, (0.0, 0.0), 1.0);
+
+        let darkest = canvas.pixels().map(|p| p[0]).min().unwrap();
+        assert!(darkest < 255, "text was not drawn");
+        assert!(darkest > 100, "half-transparent text drew opaque pixels: {}", darkest);
+        assert!(canvas.pixels().all(|p| p[3] == 255));
+    }
+}
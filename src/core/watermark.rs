@@ -0,0 +1,88 @@
+use super::{ImageProcessor, TextRenderer};
+use crate::models::{LabelConfig, WatermarkCorner};
+use crate::utils::{load_image_robustly, LabelError};
+use image::{imageops, Rgba, RgbaImage};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+const FONT_SIZE: f32 = 14.0;
+const MAX_IMAGE_SIZE: u32 = 48;
+const MARGIN: u32 = 8;
+const GAP: u32 = 4;
+
+/// Keeps the decoded watermark image so only a change of path reads the disk.
+#[derive(Clone, Default)]
+pub(crate) struct WatermarkImageCache(Arc<Mutex<Option<CachedImage>>>);
+
+struct CachedImage {
+    path: PathBuf,
+    image: Arc<RgbaImage>,
+}
+
+impl WatermarkImageCache {
+    fn get(&self, path: &Path) -> Result<Arc<RgbaImage>, LabelError> {
+        let mut cached = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        match cached.as_ref() {
+            Some(cached) if cached.path == path => Ok(cached.image.clone()),
+            _ => {
+                let image = Arc::new(load_image_robustly(path)?.to_rgba8());
+                *cached = Some(CachedImage { path: path.to_path_buf(), image: image.clone() });
+                Ok(image)
+            }
+        }
+    }
+}
+
+/// Builds the watermark (image, then text) for a canvas of `canvas_size`
+/// pixels at `scale`, returning it with its top-left corner.
+pub(crate) fn prepare_watermark(
+    text_renderer: &TextRenderer,
+    images: &WatermarkImageCache,
+    config: &LabelConfig,
+    canvas_size: u32,
+    scale: f32,
+) -> Result<Option<(RgbaImage, u32, u32)>, LabelError> {
+    let text = config.watermark.as_deref().map(str::trim).filter(|text| !text.is_empty());
+    let mut parts = Vec::new();
+
+    if let Some(path) = &config.watermark_image {
+        let image = images.get(path)?;
+        let max_size = ((MAX_IMAGE_SIZE as f32 * scale).round() as u32).max(1);
+        let ratio = max_size as f32 / image.width().max(image.height()) as f32;
+        let width = ((image.width() as f32 * ratio).round() as u32).max(1);
+        let height = ((image.height() as f32 * ratio).round() as u32).max(1);
+        parts.push(ImageProcessor::resize_premultiplied(&image, width, height, imageops::FilterType::Lanczos3));
+    }
+    if let Some(text) = text {
+        parts.push(text_renderer.render_sample(text, FONT_SIZE * scale, Rgba([0, 0, 0, 255])));
+    }
+    if parts.is_empty() {
+        return Ok(None);
+    }
+
+    let gap = (GAP as f32 * scale).round() as u32;
+    let width = parts.iter().map(RgbaImage::width).sum::<u32>() + gap * (parts.len() as u32 - 1);
+    let height = parts.iter().map(RgbaImage::height).max().unwrap_or(0);
+    let mut stamp = RgbaImage::new(width, height);
+    let mut x = 0;
+    for part in &parts {
+        imageops::overlay(&mut stamp, part, x as i64, ((height - part.height()) / 2) as i64);
+        x += part.width() + gap;
+    }
+
+    let opacity = config.watermark_opacity.clamp(0.0, 1.0);
+    for pixel in stamp.pixels_mut() {
+        pixel[3] = (pixel[3] as f32 * opacity).round() as u8;
+    }
+
+    let margin = (MARGIN as f32 * scale).round() as u32;
+    let far_x = canvas_size.saturating_sub(width + margin);
+    let far_y = canvas_size.saturating_sub(height + margin);
+    let (x, y) = match config.watermark_corner {
+        WatermarkCorner::TopLeft => (margin, margin),
+        WatermarkCorner::TopRight => (far_x, margin),
+        WatermarkCorner::BottomLeft => (margin, far_y),
+        WatermarkCorner::BottomRight => (far_x, far_y),
+    };
+    Ok(Some((stamp, x, y)))
+}
@@ -0,0 +1,126 @@
+use crate::utils::LabelError;
+use image::RgbaImage;
+use libwebp_sys::{
+    WebPAnimEncoder, WebPAnimEncoderAdd, WebPAnimEncoderAssemble, WebPAnimEncoderDelete, WebPAnimEncoderGetError,
+    WebPAnimEncoderNewInternal, WebPAnimEncoderOptions, WebPAnimEncoderOptionsInitInternal, WebPConfig, WebPData,
+    WebPDataClear, WebPGetMuxABIVersion, WebPPicture, WebPPictureFree, WebPPictureImportRGBA,
+};
+
+pub fn encode_webp(image: &RgbaImage, quality: u8, lossless: bool) -> Result<Vec<u8>, LabelError> {
+    let encoder = ::webp::Encoder::from_rgba(image.as_raw(), image.width(), image.height());
+    let encoded = if lossless {
+        encoder.encode_lossless()
+    } else {
+        encoder.encode(quality as f32)
+    };
+
+    if encoded.is_empty() {
+        return Err(LabelError::ImageSaving("Failed to encode WebP image".to_string()));
+    }
+    Ok(encoded.to_vec())
+}
+
+/// Encodes an animation where frame `i` is shown for `delays_ms[i]`.
+///
+/// This talks to libwebp directly because `webp::AnimEncoder` closes the
+/// stream at timestamp 0, which leaves libwebp to guess the last frame's
+/// duration from the average of the others.
+pub fn encode_webp_animation(
+    frames: &[RgbaImage],
+    delays_ms: &[u32],
+    quality: u8,
+    lossless: bool,
+) -> Result<Vec<u8>, LabelError> {
+    let (width, height) = frames
+        .first()
+        .map(|frame| frame.dimensions())
+        .ok_or_else(|| LabelError::ImageProcessing("No frames to export".to_string()))?;
+    if frames.iter().any(|frame| frame.dimensions() != (width, height)) {
+        return Err(LabelError::ImageProcessing("Animation frames differ in size".to_string()));
+    }
+
+    let mut config =
+        WebPConfig::new().map_err(|_| LabelError::ImageSaving("Failed to initialize WebP encoder".to_string()))?;
+    config.lossless = lossless as i32;
+    config.quality = quality as f32;
+
+    unsafe {
+        let mut options = std::mem::MaybeUninit::<WebPAnimEncoderOptions>::uninit();
+        if WebPAnimEncoderOptionsInitInternal(options.as_mut_ptr(), WebPGetMuxABIVersion()) == 0 {
+            return Err(LabelError::ImageSaving("Failed to initialize WebP animation options".to_string()));
+        }
+        let mut options = options.assume_init();
+        options.anim_params.loop_count = 0;
+        options.anim_params.bgcolor = 0;
+
+        let encoder = WebPAnimEncoderNewInternal(width as i32, height as i32, &options, WebPGetMuxABIVersion());
+        if encoder.is_null() {
+            return Err(LabelError::ImageSaving("Failed to create WebP animation encoder".to_string()));
+        }
+        let result = encode_frames(encoder, frames, delays_ms, &config);
+        WebPAnimEncoderDelete(encoder);
+        result
+    }
+}
+
+unsafe fn encode_frames(
+    encoder: *mut WebPAnimEncoder,
+    frames: &[RgbaImage],
+    delays_ms: &[u32],
+    config: &WebPConfig,
+) -> Result<Vec<u8>, LabelError> {
+    let mut timestamp_ms = 0i32;
+    for (i, frame) in frames.iter().enumerate() {
+        let mut picture =
+            WebPPicture::new().map_err(|_| LabelError::ImageSaving("Failed to allocate WebP frame".to_string()))?;
+        picture.use_argb = 1;
+        picture.width = frame.width() as i32;
+        picture.height = frame.height() as i32;
+        let added = WebPPictureImportRGBA(&mut picture, frame.as_raw().as_ptr(), frame.width() as i32 * 4) != 0
+            && WebPAnimEncoderAdd(encoder, &mut picture, timestamp_ms, config) != 0;
+        WebPPictureFree(&mut picture);
+        if !added {
+            return Err(encoder_error(encoder, &format!("Failed to encode WebP frame {}", i)));
+        }
+        timestamp_ms += delays_ms.get(i).copied().unwrap_or(100).max(1) as i32;
+    }
+
+    // A null frame marks when the last frame ends.
+    if WebPAnimEncoderAdd(encoder, std::ptr::null_mut(), timestamp_ms, std::ptr::null()) == 0 {
+        return Err(encoder_error(encoder, "Failed to finish WebP animation"));
+    }
+    let mut data = WebPData::default();
+    if WebPAnimEncoderAssemble(encoder, &mut data) == 0 {
+        return Err(encoder_error(encoder, "Failed to assemble WebP animation"));
+    }
+    let bytes = std::slice::from_raw_parts(data.bytes, data.size).to_vec();
+    WebPDataClear(&mut data);
+    Ok(bytes)
+}
+
+unsafe fn encoder_error(encoder: *mut WebPAnimEncoder, context: &str) -> LabelError {
+    let message = WebPAnimEncoderGetError(encoder);
+    if message.is_null() {
+        LabelError::ImageSaving(context.to_string())
+    } else {
+        LabelError::ImageSaving(format!("{}: {}", context, std::ffi::CStr::from_ptr(message).to_string_lossy()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn animation_keeps_every_frame_delay() {
+        let frames: Vec<_> = (0..3u8)
+            .map(|i| RgbaImage::from_pixel(16, 16, image::Rgba([i * 100, 0, 0, 255])))
+            .collect();
+
+        let data = encode_webp_animation(&frames, &[100, 200, 300], 90, true).unwrap();
+
+        let animation = ::webp::AnimDecoder::new(&data).decode().unwrap();
+        let ends: Vec<_> = (0..animation.len()).map(|i| animation.get_frame(i).unwrap().get_time_ms()).collect();
+        assert_eq!(ends, [100, 300, 600]);
+    }
+}
@@ -1,12 +1,13 @@
 use clap::{Parser, Subcommand, Args};
-use iced::{Application, Settings, Color};
+use iced::{Application, Settings};
 use anyhow::{Context};
 use crate::app::App;
-use crate::models::{LabelConfig, ClassType, Hazard, ResizeMethod, OutputFormat, BurnType};
-use crate::core::label_composer::generate_and_save_label;
-use std::path::PathBuf;
+use crate::models::{LabelConfig, BackgroundFill, BackgroundFillKind, ClassType, Hazard, ResizeMethod, OutputFormat, BurnType, BurnPreset, JpegSubsampling, LabelLayer, TextureBlendMode, WatermarkCorner, MAX_BLEED, MAX_OUTPUT_RESOLUTION};
+use crate::core::label_composer::{generate_and_save_label, save_burn_mask, save_debug_output, save_label};
+use crate::core::{batch, doctor, project, AssetManager, LabelComposer};
+use std::path::{Path, PathBuf};
 use colored::Colorize;
-use crate::utils::CliExitCode;
+use crate::utils::{color, CliExitCode};
 
 mod app;
 mod core;
@@ -14,19 +15,6 @@ mod models;
 mod ui;
 mod utils;
 
-fn parse_hex_color(hex: &str) -> anyhow::Result<Color> {
-    let hex = hex.trim_start_matches('#');
-    if hex.len() != 6 {
-        return Err(anyhow::anyhow!("Invalid hex color string length: {}", hex.len()));
-    }
-    
-    let r = u8::from_str_radix(&hex[0..2], 16).context("Invalid R component")?;
-    let g = u8::from_str_radix(&hex[2..4], 16).context("Invalid G component")?;
-    let b = u8::from_str_radix(&hex[4..6], 16).context("Invalid B component")?;
-    
-    Ok(Color::from_rgb(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0))
-}
-
 fn parse_float_range(s: &str, min: f32, max: f32) -> anyhow::Result<f32> {
     let value: f32 = s.parse()?;
     if value >= min && value <= max {
@@ -45,6 +33,13 @@ fn parse_u8_range(s: &str, min: u8, max: u8) -> anyhow::Result<u8> {
     }
 }
 
+fn parse_supersample(s: &str) -> anyhow::Result<u8> {
+    match s.parse()? {
+        factor @ (1 | 2 | 4) => Ok(factor),
+        _ => Err(anyhow::anyhow!("supersampling factor must be 1, 2 or 4")),
+    }
+}
+
 fn parse_non_empty_string(s: &str) -> anyhow::Result<String> {
     if s.is_empty() {
         Err(anyhow::anyhow!("value cannot be empty"))
@@ -59,13 +54,118 @@ struct Cli {
     #[arg(long)]
     cli: bool,
 
+    /// Directory to load resources/ and texturepacks/ from (overrides $ASSET_ROOT)
+    #[arg(long, global = true)]
+    asset_root: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
 
 #[derive(Subcommand, Debug)]
 enum Commands {
-    Generate(GenerateArgs),
+    Generate(Box<GenerateArgs>),
+    /// Generate one label per row of a CSV or JSON manifest
+    Batch(BatchArgs),
+    /// Report how an image will fit the label without rendering anything
+    Check(CheckArgs),
+    /// Render a label from a layer file exported by an image editor
+    Import(ImportArgs),
+    /// Check assets, fonts and folder permissions and report what is broken
+    Doctor(DoctorArgs),
+    /// List the values accepted by options such as --burn-preset
+    List(ListArgs),
+    #[command(subcommand)]
+    Project(ProjectCommands),
+}
+
+#[derive(Subcommand, Debug)]
+enum ProjectCommands {
+    Render(ProjectRenderArgs),
+    ExtractConfig(ProjectExtractConfigArgs),
+}
+
+#[derive(Args, Debug)]
+struct ProjectRenderArgs {
+    file: PathBuf,
+
+    #[arg(short, long)]
+    output: PathBuf,
+
+    #[arg(long, value_name = "DIR")]
+    texturepacks_dir: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct BatchArgs {
+    manifest: PathBuf,
+
+    #[arg(short, long, value_name = "DIR")]
+    output_dir: PathBuf,
+
+    #[arg(long, value_name = "DIR")]
+    texturepacks_dir: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct CheckArgs {
+    image_path: PathBuf,
+
+    #[arg(long, value_enum, default_value_t = LabelConfig::default().resize_method)]
+    resize_method: ResizeMethod,
+
+    /// Print the result as JSON
+    #[arg(long)]
+    json: bool,
+
+    #[arg(long, value_name = "DIR")]
+    texturepacks_dir: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct ImportArgs {
+    file: PathBuf,
+
+    #[arg(short, long)]
+    output: PathBuf,
+
+    #[arg(long, value_name = "DIR")]
+    texturepacks_dir: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct ListArgs {
+    #[arg(value_enum)]
+    what: ListTarget,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum ListTarget {
+    BurnPresets,
+    Classes,
+    Hazards,
+}
+
+#[derive(Args, Debug)]
+struct DoctorArgs {
+    /// Folder labels will be exported to
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    output_dir: PathBuf,
+
+    /// Print the report as JSON
+    #[arg(long)]
+    json: bool,
+
+    #[arg(long, value_name = "DIR")]
+    texturepacks_dir: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct ProjectExtractConfigArgs {
+    file: PathBuf,
+
+    #[arg(short, long)]
+    output: PathBuf,
 }
 
 #[derive(Args, Debug)]
@@ -75,36 +175,109 @@ struct GenerateArgs {
     #[arg(short = 'c', long, default_value_t = LabelConfig::default().object_class_text.clone(), value_parser = parse_non_empty_string)]
     object_class_text: String,
 
+    /// Draw the SCP number in uppercase
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    uppercase_number: bool,
+
+    /// Draw the object class text in uppercase
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    uppercase_class: bool,
+
     #[arg(short = 't', long, value_enum, default_value_t = LabelConfig::default().class_type)]
     class_type: ClassType,
 
     #[arg(long, action = clap::ArgAction::SetTrue)]
     use_alternate_style: bool,
 
+    /// Place the image in the alternate template's center slot (under any hazard icon)
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    alternate_user_image: bool,
+
     #[arg(short, long)]
     image_path: Option<PathBuf>,
 
+    #[arg(long, value_name = "PATH")]
+    font: Option<PathBuf>,
+
     #[arg(long, value_enum, default_value_t = LabelConfig::default().resize_method)]
     resize_method: ResizeMethod,
 
+    /// Fill color for letterbox bars; use an 8-digit hex with 00 alpha for transparent bars
+    #[arg(long, value_name = "HEX", default_value_t = color::to_hex(LabelConfig::default().letterbox_color.into()))]
+    letterbox_color: String,
+
+    /// Fill the image slot with a color or gradient when no image is given
+    #[arg(long, value_enum, default_value_t = BackgroundFillKind::None)]
+    background_fill: BackgroundFillKind,
+
+    /// Solid fill color, or the color a gradient starts from
+    #[arg(long, value_name = "HEX", default_value_t = color::to_hex(BackgroundFill::None.parts().0.into()))]
+    fill_color: String,
+
+    /// Color a gradient ends at
+    #[arg(long, value_name = "HEX", default_value_t = color::to_hex(BackgroundFill::None.parts().1.into()))]
+    fill_end_color: String,
+
+    /// Linear gradient direction in degrees clockwise from left-to-right
+    #[arg(long, value_name = "DEGREES", default_value_t = BackgroundFill::None.parts().2, allow_hyphen_values = true)]
+    fill_angle: f32,
+
     #[arg(short = 'z', long, value_enum)]
     hazard: Option<Hazard>,
 
+    #[arg(long, default_value_t = LabelConfig::default().hazard_opacity, value_parser = |s: &str| parse_float_range(s, 0.0, 1.0))]
+    hazard_opacity: f32,
+
     #[arg(long, action = clap::ArgAction::SetTrue)]
     apply_texture: bool,
 
+    #[arg(long, default_value_t = LabelConfig::default().texture_name)]
+    texture: String,
+
     #[arg(long, default_value_t = LabelConfig::default().texture_opacity, value_parser = |s: &str| parse_float_range(s, 0.0, 1.0))]
     texture_opacity: f32,
 
-    #[arg(short = 'r', long, default_value_t = LabelConfig::default().output_resolution)]
+    #[arg(long, value_enum, default_value_t = LabelConfig::default().texture_blend)]
+    texture_blend: TextureBlendMode,
+
+    /// Comma-separated drawing order, bottom first; unlisted layers keep their default order after these
+    #[arg(long, value_enum, value_delimiter = ',')]
+    layer_order: Vec<LabelLayer>,
+
+    #[arg(short = 'r', long, default_value_t = LabelConfig::default().output_resolution, value_parser = clap::value_parser!(u32).range(1..=MAX_OUTPUT_RESOLUTION as i64))]
     resolution: u32,
 
+    /// Render at 2x or 4x the output resolution and downsample for smoother edges (slower)
+    #[arg(long, default_value_t = LabelConfig::default().supersample, value_parser = parse_supersample)]
+    supersample: u8,
+
+    /// Lay out and rasterize at the output resolution instead of resampling a 512px label
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    native_resolution: bool,
+
+    /// Extend the label by this many pixels (at 512px) on every side for print trimming
+    #[arg(long, default_value_t = LabelConfig::default().bleed, value_parser = clap::value_parser!(u32).range(0..=MAX_BLEED as i64))]
+    bleed: u32,
+
+    /// Draw crop marks in the bleed area; requires --bleed
+    #[arg(long, action = clap::ArgAction::SetTrue, requires = "bleed")]
+    print_marks: bool,
+
     #[arg(short = 'f', long, value_enum, default_value_t = LabelConfig::default().output_format)]
     output_format: OutputFormat,
 
     #[arg(short = 'q', long, default_value_t = LabelConfig::default().output_quality, value_parser = |s: &str| parse_u8_range(s, 0, 100))]
     output_quality: u8,
 
+    #[arg(long, value_enum, default_value_t = LabelConfig::default().jpeg_subsampling)]
+    jpeg_subsampling: JpegSubsampling,
+
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    optimize_png: bool,
+
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    webp_lossless: bool,
+
     #[arg(long, default_value_t = LabelConfig::default().brightness, value_parser = |s: &str| parse_float_range(s, -1.0, 1.0))]
     brightness: f32,
 
@@ -132,12 +305,22 @@ struct GenerateArgs {
     #[arg(long, default_value_t = LabelConfig::default().class_text_offset.1)]
     class_offset_y: f32,
 
-    #[arg(long, default_value_t = format!("#{:02x}{:02x}{:02x}", (Color::from(LabelConfig::default().scp_text_color).r * 255.0) as u8, (Color::from(LabelConfig::default().scp_text_color).g * 255.0) as u8, (Color::from(LabelConfig::default().scp_text_color).b * 255.0) as u8))]
+    #[arg(long, default_value_t = color::to_hex(LabelConfig::default().scp_text_color.into()))]
     scp_color: String,
 
-    #[arg(long, default_value_t = format!("#{:02x}{:02x}{:02x}", (Color::from(LabelConfig::default().class_text_color).r * 255.0) as u8, (Color::from(LabelConfig::default().class_text_color).g * 255.0) as u8, (Color::from(LabelConfig::default().class_text_color).b * 255.0) as u8))]
+    #[arg(long, default_value_t = color::to_hex(LabelConfig::default().class_text_color.into()))]
     class_color: String,
 
+    #[arg(long)]
+    canvas_background: Option<String>,
+
+    #[arg(long, value_name = "HEX")]
+    flatten_to: Option<String>,
+
+    /// Color transparent areas are flattened onto for formats without transparency
+    #[arg(long, value_name = "HEX", default_value_t = color::to_hex(LabelConfig::default().background_color.into()))]
+    background_color: String,
+
     #[arg(long, default_value_t = LabelConfig::default().scp_line_spacing, value_parser = |s: &str| parse_float_range(s, 0.5, 3.0))]
     scp_line_spacing: f32,
 
@@ -147,50 +330,74 @@ struct GenerateArgs {
     #[arg(long, action = clap::ArgAction::SetTrue)]
     apply_burn: bool,
 
-    #[arg(long, value_enum, default_value_t = LabelConfig::default().burn_type)]
-    burn_type: BurnType,
+    /// Start from a named group of burn settings (see `list burn-presets`); implies --apply-burn
+    #[arg(long, value_enum)]
+    burn_preset: Option<BurnPreset>,
 
-    #[arg(long, default_value_t = LabelConfig::default().burn_amount,
-        value_parser = |s: &str| parse_float_range(s, 0.0, 1.0))]
-    burn_amount: f32,
+    #[arg(long, value_enum)]
+    burn_type: Option<BurnType>,
 
-    #[arg(long, default_value_t = LabelConfig::default().burn_scale,
-        value_parser = |s: &str| parse_float_range(s, 0.1, 5.0))]
-    burn_scale: f32,
+    #[arg(long, value_parser = |s: &str| parse_float_range(s, 0.0, 1.0))]
+    burn_amount: Option<f32>,
 
-    #[arg(long, default_value_t = LabelConfig::default().burn_detail,
-        value_parser = |s: &str| parse_float_range(s, 0.0, 1.0))]
-    burn_detail: f32,
+    #[arg(long, value_parser = |s: &str| parse_float_range(s, 0.1, 5.0))]
+    burn_scale: Option<f32>,
 
-    #[arg(long, default_value_t = LabelConfig::default().burn_edge_softness,
-        value_parser = |s: &str| parse_float_range(s, 0.0, 1.0))]
-    burn_edge_softness: f32,
+    #[arg(long, value_parser = |s: &str| parse_float_range(s, 0.0, 1.0))]
+    burn_detail: Option<f32>,
 
-    #[arg(long, default_value_t = LabelConfig::default().burn_irregularity,
-        value_parser = |s: &str| parse_float_range(s, 0.0, 1.0))]
-    burn_irregularity: f32,
+    #[arg(long, value_parser = |s: &str| parse_float_range(s, 0.0, 1.0))]
+    burn_edge_softness: Option<f32>,
 
-    #[arg(long, default_value_t = LabelConfig::default().burn_char,
-        value_parser = |s: &str| parse_float_range(s, 0.0, 1.0))]
-    burn_char: f32,
+    #[arg(long, value_parser = |s: &str| parse_float_range(s, 0.0, 1.0))]
+    burn_irregularity: Option<f32>,
+
+    #[arg(long, value_parser = |s: &str| parse_float_range(s, 0.0, 1.0))]
+    burn_char: Option<f32>,
 
     #[arg(long, default_value_t = LabelConfig::default().burn_seed)]
     burn_seed: u32,
 
-    #[arg(long, default_value_t = LabelConfig::default().burn_scale_multiplier)]
-    burn_scale_multiplier: f32,
+    #[arg(long)]
+    burn_scale_multiplier: Option<f32>,
 
-    #[arg(long, default_value_t = LabelConfig::default().burn_detail_blend, value_parser = |s: &str| parse_float_range(s, 0.0, 1.0))]
-    burn_detail_blend: f32,
+    #[arg(long, value_parser = |s: &str| parse_float_range(s, 0.0, 1.0))]
+    burn_detail_blend: Option<f32>,
 
-    #[arg(long, default_value_t = LabelConfig::default().burn_turbulence_freq)]
-    burn_turbulence_freq: f32,
+    #[arg(long)]
+    burn_turbulence_freq: Option<f32>,
 
-    #[arg(long, default_value_t = LabelConfig::default().burn_turbulence_strength, value_parser = |s: &str| parse_float_range(s, 0.0, 1.0))]
-    burn_turbulence_strength: f32,
+    #[arg(long, value_parser = |s: &str| parse_float_range(s, 0.0, 1.0))]
+    burn_turbulence_strength: Option<f32>,
 
-    #[arg(short, long)]
-    output: PathBuf,
+    /// Small attribution text drawn in a corner of the label
+    #[arg(long, value_name = "TEXT")]
+    watermark: Option<String>,
+
+    /// Small image drawn next to the watermark text
+    #[arg(long, value_name = "PATH")]
+    watermark_image: Option<PathBuf>,
+
+    #[arg(long, value_enum, default_value_t = LabelConfig::default().watermark_corner)]
+    watermark_corner: WatermarkCorner,
+
+    #[arg(long, default_value_t = LabelConfig::default().watermark_opacity, value_parser = |s: &str| parse_float_range(s, 0.0, 1.0))]
+    watermark_opacity: f32,
+
+    #[arg(short, long, required_unless_present = "output_dir")]
+    output: Option<PathBuf>,
+
+    #[arg(long, value_name = "DIR")]
+    output_dir: Option<PathBuf>,
+
+    #[arg(long, value_name = "PATH")]
+    dump_burn_mask: Option<PathBuf>,
+
+    #[arg(long, value_name = "DIR")]
+    debug_output: Option<PathBuf>,
+
+    #[arg(long, value_name = "DIR")]
+    texturepacks_dir: Option<PathBuf>,
 }
 
 fn main() {
@@ -203,12 +410,25 @@ fn main() {
 
     let cli = Cli::parse();
 
-    let result = if has_cli_args || cli.cli {
+    if let Some(root) = cli.asset_root {
+        utils::set_asset_root(root);
+    }
+
+    let result = if cli.cli || cli.command.is_some() {
         match cli.command {
             Some(Commands::Generate(args)) => {
                 println!("{}", "Running in CLI mode to generate label.".green());
-                run_cli(args).context("Failed to generate label via CLI")
+                run_cli(*args).context("Failed to generate label via CLI")
+            }
+            Some(Commands::Batch(args)) => run_batch(args).context("Batch generation failed"),
+            Some(Commands::Check(args)) => run_check(args).context("Check failed"),
+            Some(Commands::Import(args)) => run_import(args).context("Import failed"),
+            Some(Commands::Doctor(args)) => run_doctor(args),
+            Some(Commands::List(args)) => {
+                run_list(args);
+                Ok(())
             }
+            Some(Commands::Project(command)) => run_project(command).context("Project command failed"),
             None => {
                 Err(anyhow::anyhow!("CLI mode specified but no command given. Use `scp-label-maker --help` for more information."))
             }
@@ -229,27 +449,231 @@ fn main() {
     }
 }
 
+fn run_batch(args: BatchArgs) -> anyhow::Result<()> {
+    let configs = batch::load_batch(&args.manifest, &LabelConfig::default())
+        .context(format!("Failed to load manifest {}", args.manifest.display()))?;
+    let assets = AssetManager::load_with_pack_dir(args.texturepacks_dir.as_deref())?;
+    let default_composer = LabelComposer::new()?;
+
+    let mut failed = 0;
+    for (index, config) in configs.iter().enumerate() {
+        let stem = utils::sanitize_filename(&format!("SCP-{}", config.scp_number));
+        let output = args.output_dir.join(format!("{}.{}", stem, config.output_format.extension()));
+        let result = match config.font_path {
+            Some(_) => LabelComposer::for_config(config).and_then(|composer| save_label(&composer, &assets, config, &output)),
+            None => save_label(&default_composer, &assets, config, &output),
+        };
+        match result {
+            Ok(()) => println!("{}", format!("Row {}: wrote {}", index + 1, output.display()).green()),
+            Err(e) => {
+                failed += 1;
+                eprintln!("{}", format!("Row {}: {}", index + 1, e).red());
+            }
+        }
+    }
+
+    println!("{}", format!("{} of {} labels generated.", configs.len() - failed, configs.len()).bold());
+    if failed > 0 {
+        anyhow::bail!("{} of {} rows failed", failed, configs.len());
+    }
+    Ok(())
+}
+
+fn run_check(args: CheckArgs) -> anyhow::Result<()> {
+    if args.json {
+        // The logger writes to stdout, which has to stay parseable.
+        log::set_max_level(log::LevelFilter::Warn);
+    }
+    AssetManager::load_with_pack_dir(args.texturepacks_dir.as_deref())?;
+
+    let image = match utils::load_image_robustly(&args.image_path) {
+        Ok(image) => image,
+        Err(e) => {
+            eprintln!("{}: {}: {}", "Error".red().bold(), args.image_path.display(), e);
+            std::process::exit(CliExitCode::InvalidInput as i32);
+        }
+    };
+    let validation = utils::validate_user_image(&image, args.resize_method);
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&validation)?);
+    } else {
+        let (source_w, source_h) = validation.source_dimensions;
+        let (target_w, target_h) = validation.target_dimensions;
+        println!("Status: {:?}", validation.status);
+        println!("Source: {}×{}", source_w, source_h);
+        println!("Target: {}×{} ({})", target_w, target_h, args.resize_method);
+        println!("{}", validation.message);
+    }
+    Ok(())
+}
+
+fn run_list(args: ListArgs) {
+    let rows: Vec<(String, String)> = match args.what {
+        ListTarget::BurnPresets => BurnPreset::all()
+            .into_iter()
+            .map(|preset| (value_name(preset), preset.description().to_string()))
+            .collect(),
+        ListTarget::Classes => ClassType::all()
+            .into_iter()
+            .map(|class| (value_name(class), class.as_str().to_string()))
+            .collect(),
+        ListTarget::Hazards => Hazard::all()
+            .into_iter()
+            .map(|hazard| (value_name(hazard), hazard.display_name().to_string()))
+            .collect(),
+    };
+    for (name, description) in rows {
+        println!("{:<28} {}", name.bold(), description);
+    }
+}
+
+fn value_name(value: impl clap::ValueEnum) -> String {
+    value.to_possible_value().map_or_else(String::new, |value| value.get_name().to_string())
+}
+
+fn run_doctor(args: DoctorArgs) -> anyhow::Result<()> {
+    if args.json {
+        log::set_max_level(log::LevelFilter::Off);
+    }
+    let checks = doctor::run_checks(args.texturepacks_dir.as_deref(), &args.output_dir);
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&checks)?);
+    } else {
+        for check in &checks {
+            let status = if check.passed { "PASS".green().bold() } else { "FAIL".red().bold() };
+            println!("{} {}: {}", status, check.name, check.detail);
+        }
+    }
+
+    let failed = checks.iter().filter(|check| !check.passed).count();
+    if failed > 0 {
+        if !args.json {
+            eprintln!("{}", format!("{} of {} checks failed.", failed, checks.len()).red().bold());
+        }
+        std::process::exit(CliExitCode::GenericError as i32);
+    }
+    if !args.json {
+        println!("{}", "All checks passed.".green().bold());
+    }
+    Ok(())
+}
+
+fn set_format_from_extension(config: &mut LabelConfig, output: &Path) {
+    let extension = output.extension().and_then(|s| s.to_str()).map(str::to_lowercase);
+    if let Some(format) = OutputFormat::all().into_iter().find(|f| Some(f.extension()) == extension.as_deref()) {
+        config.output_format = format;
+    } else if extension.as_deref() == Some("jpeg") {
+        config.output_format = OutputFormat::Jpeg;
+    }
+}
+
+fn run_import(args: ImportArgs) -> anyhow::Result<()> {
+    let mut config = LabelConfig::import_layers(&args.file)
+        .context(format!("Failed to import layers from {}", args.file.display()))?;
+    set_format_from_extension(&mut config, &args.output);
+
+    generate_and_save_label(&config, &args.output, args.texturepacks_dir.as_deref())
+        .context(format!("Failed to render layers to {}", args.output.display()))?;
+    println!("{}", format!("Rendered {} to {}", args.file.display(), args.output.display()).green().bold());
+    Ok(())
+}
+
+fn run_project(command: ProjectCommands) -> anyhow::Result<()> {
+    match command {
+        ProjectCommands::Render(args) => {
+            let mut config = project::load_project(&args.file)
+                .context(format!("Failed to load project {}", args.file.display()))?
+                .into_config();
+            set_format_from_extension(&mut config, &args.output);
+            generate_and_save_label(&config, &args.output, args.texturepacks_dir.as_deref())
+                .context(format!("Failed to render project to {}", args.output.display()))?;
+            println!("{}", format!("Rendered {} to {}", args.file.display(), args.output.display()).green().bold());
+        }
+        ProjectCommands::ExtractConfig(args) => {
+            let project = project::load_project(&args.file)
+                .context(format!("Failed to load project {}", args.file.display()))?;
+            project
+                .config
+                .save(&args.output)
+                .context(format!("Failed to write config to {}", args.output.display()))?;
+            println!("{}", format!("Extracted config to {}", args.output.display()).green().bold());
+        }
+    }
+    Ok(())
+}
+
 fn run_cli(args: GenerateArgs) -> anyhow::Result<()> {
-    let scp_text_color = parse_hex_color(&args.scp_color)
+    let scp_text_color = color::parse_hex(&args.scp_color)
         .context(format!("Invalid SCP number color '{}'", args.scp_color))?
         .into();
-    let class_text_color = parse_hex_color(&args.class_color)
+    let class_text_color = color::parse_hex(&args.class_color)
         .context(format!("Invalid object class color '{}'", args.class_color))?
         .into();
+    let letterbox_color = color::parse_hex(&args.letterbox_color)
+        .context(format!("Invalid letterbox color '{}'", args.letterbox_color))?
+        .into();
+    let fill_color = color::parse_hex(&args.fill_color)
+        .context(format!("Invalid fill color '{}'", args.fill_color))?
+        .into();
+    let fill_end_color = color::parse_hex(&args.fill_end_color)
+        .context(format!("Invalid fill end color '{}'", args.fill_end_color))?
+        .into();
+    let background_fill = BackgroundFill::from_parts(args.background_fill, fill_color, fill_end_color, args.fill_angle);
+    let mut burn = LabelConfig::default();
+    if let Some(preset) = args.burn_preset {
+        preset.apply_to(&mut burn);
+    }
+    let background_color = color::parse_hex(&args.background_color)
+        .context(format!("Invalid background color '{}'", args.background_color))?
+        .into();
+    let canvas_background = match &args.canvas_background {
+        Some(hex) => color::parse_hex(hex)
+            .context(format!("Invalid canvas background color '{}'", hex))?
+            .into(),
+        None => LabelConfig::default().canvas_background,
+    };
+    let flatten_to = match &args.flatten_to {
+        Some(hex) => Some(
+            color::parse_hex(hex)
+                .context(format!("Invalid flatten color '{}'", hex))?
+                .into(),
+        ),
+        None => LabelConfig::default().flatten_to,
+    };
 
     let config = LabelConfig {
         scp_number: args.scp_number,
         object_class_text: args.object_class_text,
+        uppercase_number: args.uppercase_number,
+        uppercase_class: args.uppercase_class,
         class_type: args.class_type,
         use_alternate_style: args.use_alternate_style,
+        alternate_user_image: args.alternate_user_image,
         image_path: args.image_path,
+        font_path: args.font,
         resize_method: args.resize_method,
+        letterbox_color,
+        background_fill,
         selected_hazard: args.hazard,
+        hazard_opacity: args.hazard_opacity,
         apply_texture: args.apply_texture,
+        texture_name: args.texture,
         texture_opacity: args.texture_opacity,
+        texture_blend: args.texture_blend,
+        texture_layers: Vec::new(),
+        layer_order: args.layer_order,
         output_resolution: args.resolution,
+        supersample: args.supersample,
+        native_resolution: args.native_resolution,
+        bleed: args.bleed,
+        print_marks: args.print_marks,
         output_format: args.output_format,
         output_quality: args.output_quality,
+        jpeg_subsampling: args.jpeg_subsampling,
+        webp_lossless: args.webp_lossless,
+        optimize_png: args.optimize_png,
         brightness: args.brightness,
         contrast: args.contrast,
         grayscale: args.grayscale,
@@ -259,27 +683,52 @@ fn run_cli(args: GenerateArgs) -> anyhow::Result<()> {
         class_text_offset: (args.class_offset_x, args.class_offset_y),
         scp_text_color,
         class_text_color,
+        canvas_background,
+        flatten_to,
+        background_color,
         scp_line_spacing: args.scp_line_spacing,
         class_line_spacing: args.class_line_spacing,
-        apply_burn: args.apply_burn,
-        burn_type: args.burn_type,
-        burn_amount: args.burn_amount,
-        burn_scale: args.burn_scale,
-        burn_detail: args.burn_detail,
-        burn_edge_softness: args.burn_edge_softness,
-        burn_irregularity: args.burn_irregularity,
-        burn_char: args.burn_char,
+        apply_burn: args.apply_burn || burn.apply_burn,
+        burn_type: args.burn_type.unwrap_or(burn.burn_type),
+        burn_amount: args.burn_amount.unwrap_or(burn.burn_amount),
+        burn_scale: args.burn_scale.unwrap_or(burn.burn_scale),
+        burn_detail: args.burn_detail.unwrap_or(burn.burn_detail),
+        burn_edge_softness: args.burn_edge_softness.unwrap_or(burn.burn_edge_softness),
+        burn_irregularity: args.burn_irregularity.unwrap_or(burn.burn_irregularity),
+        burn_char: args.burn_char.unwrap_or(burn.burn_char),
         burn_seed: args.burn_seed,
-        burn_scale_multiplier: args.burn_scale_multiplier,
-        burn_detail_blend: args.burn_detail_blend,
-        burn_turbulence_freq: args.burn_turbulence_freq,
-        burn_turbulence_strength: args.burn_turbulence_strength,
+        burn_scale_multiplier: args.burn_scale_multiplier.unwrap_or(burn.burn_scale_multiplier),
+        burn_detail_blend: args.burn_detail_blend.unwrap_or(burn.burn_detail_blend),
+        burn_turbulence_freq: args.burn_turbulence_freq.unwrap_or(burn.burn_turbulence_freq),
+        burn_turbulence_strength: args.burn_turbulence_strength.unwrap_or(burn.burn_turbulence_strength),
+        watermark: args.watermark,
+        watermark_image: args.watermark_image,
+        watermark_corner: args.watermark_corner,
+        watermark_opacity: args.watermark_opacity,
+    };
+
+    let output = match (args.output, args.output_dir) {
+        (Some(path), _) => path,
+        (None, Some(dir)) => dir.join(config.derived_file_name()),
+        (None, None) => anyhow::bail!("Either --output or --output-dir must be given"),
     };
 
     println!("{}", format!("Generating label for SCP-{}...", config.scp_number).cyan());
-            generate_and_save_label(&config, &args.output)
-        .context(format!("Failed to generate and save label to {}", args.output.display()))?;
+            generate_and_save_label(&config, &output, args.texturepacks_dir.as_deref())
+        .context(format!("Failed to generate and save label to {}", output.display()))?;
+
+    println!("{}", format!("Successfully generated label to {}", output.display()).green().bold());
 
-    println!("{}", format!("Successfully generated label to {}", args.output.display()).green().bold());
+    if let Some(mask_path) = &args.dump_burn_mask {
+        save_burn_mask(&config, mask_path)
+            .context(format!("Failed to save burn mask to {}", mask_path.display()))?;
+        println!("{}", format!("Saved burn mask to {}", mask_path.display()).green());
+    }
+
+    if let Some(debug_dir) = &args.debug_output {
+        save_debug_output(&config, debug_dir, args.texturepacks_dir.as_deref())
+            .context(format!("Failed to write debug output to {}", debug_dir.display()))?;
+        println!("{}", format!("Wrote debug stages to {}", debug_dir.display()).green());
+    }
     Ok(())
 }
\ No newline at end of file
@@ -1,9 +1,16 @@
-use super::{ClassType, Hazard};
+use super::{ClassType, Hazard, LABEL_SIZE, MAX_BLEED, MAX_OUTPUT_RESOLUTION};
+use std::borrow::Cow;
 use std::path::PathBuf;
 use serde::{Deserialize, Serialize};
 use rand::{thread_rng, Rng};
 use iced::Color;
 
+pub const DEFAULT_TEXTURE: &str = "dirty_overlay";
+
+fn default_texture_name() -> String {
+    DEFAULT_TEXTURE.to_string()
+}
+
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, clap::ValueEnum)]
 pub enum BurnType {
@@ -51,20 +58,40 @@ impl From<SerializableColor> for iced::Color {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct LabelConfig {
     pub scp_number: String,
     pub object_class_text: String,
+    pub uppercase_number: bool,
+    pub uppercase_class: bool,
     pub class_type: ClassType,
     pub use_alternate_style: bool,
+    pub alternate_user_image: bool,
     #[serde(skip)]
     pub image_path: Option<PathBuf>,
+    #[serde(skip)]
+    pub font_path: Option<PathBuf>,
     pub resize_method: ResizeMethod,
+    pub letterbox_color: SerializableColor,
+    pub background_fill: BackgroundFill,
     pub selected_hazard: Option<Hazard>,
+    pub hazard_opacity: f32,
     pub apply_texture: bool,
+    pub texture_name: String,
     pub texture_opacity: f32,
+    pub texture_blend: TextureBlendMode,
+    pub texture_layers: Vec<TextureLayer>,
+    pub layer_order: Vec<LabelLayer>,
     pub output_resolution: u32,
+    pub supersample: u8,
+    pub native_resolution: bool,
+    pub bleed: u32,
+    pub print_marks: bool,
     pub output_format: OutputFormat,
     pub output_quality: u8,
+    pub jpeg_subsampling: JpegSubsampling,
+    pub webp_lossless: bool,
+    pub optimize_png: bool,
     pub brightness: f32,
     pub contrast: f32,
     pub grayscale: bool,
@@ -74,6 +101,9 @@ pub struct LabelConfig {
     pub class_text_offset: (f32, f32),
     pub scp_text_color: SerializableColor,
     pub class_text_color: SerializableColor,
+    pub canvas_background: SerializableColor,
+    pub flatten_to: Option<SerializableColor>,
+    pub background_color: SerializableColor,
     pub scp_line_spacing: f32,   
     pub class_line_spacing: f32,
     pub apply_burn: bool,
@@ -89,25 +119,46 @@ pub struct LabelConfig {
     pub burn_detail_blend: f32,
     pub burn_turbulence_freq: f32,
     pub burn_turbulence_strength: f32,
+    pub watermark: Option<String>,
+    #[serde(skip)]
+    pub watermark_image: Option<PathBuf>,
+    pub watermark_corner: WatermarkCorner,
+    pub watermark_opacity: f32,
 }
 
 impl Default for LabelConfig {
     fn default() -> Self {
-        let mut rng = thread_rng();
-        let random_scp_number = rng.gen_range(1..=1000);
         Self {
-            scp_number: format!("{:03}", random_scp_number),
+            scp_number: String::from("000"),
             object_class_text: String::from("SAFE"),
+            uppercase_number: false,
+            uppercase_class: false,
             class_type: ClassType::Safe,
             use_alternate_style: false,
+            alternate_user_image: false,
             image_path: None,
+            font_path: None,
             resize_method: ResizeMethod::CropToFit,
+            letterbox_color: Color::WHITE.into(),
+            background_fill: BackgroundFill::None,
             selected_hazard: None,
+            hazard_opacity: 1.0,
             apply_texture: false,
+            texture_name: default_texture_name(),
             texture_opacity: 0.3,
+            texture_blend: TextureBlendMode::Normal,
+            texture_layers: Vec::new(),
+            layer_order: LabelLayer::all(),
             output_resolution: 512,
+            supersample: 1,
+            native_resolution: false,
+            bleed: 0,
+            print_marks: false,
             output_format: OutputFormat::Png,
             output_quality: 95,
+            jpeg_subsampling: JpegSubsampling::Full,
+            webp_lossless: false,
+            optimize_png: false,
             brightness: 0.0,
             contrast: 1.0,
             grayscale: false,
@@ -117,6 +168,9 @@ impl Default for LabelConfig {
             class_text_offset: (2.0, -7.0),
             scp_text_color: Color::BLACK.into(),
             class_text_color: Color::BLACK.into(),
+            canvas_background: Color::TRANSPARENT.into(),
+            flatten_to: None,
+            background_color: Color::WHITE.into(),
             scp_line_spacing: 1.2,
             class_line_spacing: 1.2,
             apply_burn: false,
@@ -127,16 +181,96 @@ impl Default for LabelConfig {
             burn_edge_softness: 0.4,
             burn_irregularity: 0.3,
             burn_char: 0.7,
-            burn_seed: rng.gen(),
+            burn_seed: 0,
             burn_scale_multiplier: 5.0,
             burn_detail_blend: 0.5,
             burn_turbulence_freq: 2.0,
             burn_turbulence_strength: 0.1,
+            watermark: None,
+            watermark_image: None,
+            watermark_corner: WatermarkCorner::BottomRight,
+            watermark_opacity: 0.6,
         }
     }
 }
 
 impl LabelConfig {
+    /// The default config with a random SCP number and burn seed, used for a
+    /// fresh start in the GUI.
+    pub fn random() -> Self {
+        let mut rng = thread_rng();
+        Self {
+            scp_number: format!("{:03}", rng.gen_range(1..=1000)),
+            burn_seed: rng.gen(),
+            ..Self::default()
+        }
+    }
+
+    pub fn texture_stack(&self) -> Vec<TextureLayer> {
+        if !self.apply_texture {
+            return Vec::new();
+        }
+
+        let primary = TextureLayer {
+            name: self.texture_name.clone(),
+            opacity: self.texture_opacity,
+            blend: self.texture_blend,
+        };
+        std::iter::once(primary)
+            .chain(self.texture_layers.iter().cloned())
+            .collect()
+    }
+
+    /// The order layers are drawn in, bottom first. Duplicates are dropped and
+    /// layers missing from `layer_order` keep their default position at the end.
+    pub fn composition_order(&self) -> Vec<LabelLayer> {
+        let mut order = Vec::new();
+        for layer in self.layer_order.iter().copied().chain(LabelLayer::all()) {
+            if !order.contains(&layer) {
+                order.push(layer);
+            }
+        }
+        order
+    }
+
+    /// Side length of the canvas `compose` works on before the final resize.
+    /// Unless rendering natively or supersampling, this is the template's size.
+    /// Supersampling never drops below the template's size, since that would
+    /// lose detail rather than add it.
+    pub fn working_resolution(&self) -> u32 {
+        match self.supersample {
+            factor @ (2 | 4) => (self.output_resolution * factor as u32).clamp(LABEL_SIZE, MAX_OUTPUT_RESOLUTION),
+            _ if self.native_resolution => self.output_resolution,
+            _ => LABEL_SIZE,
+        }
+    }
+
+    /// The SCP number as drawn on the label.
+    pub fn display_scp_number(&self) -> Cow<'_, str> {
+        uppercased_if(&self.scp_number, self.uppercase_number)
+    }
+
+    /// The object class text as drawn on the label.
+    pub fn display_object_class(&self) -> Cow<'_, str> {
+        uppercased_if(&self.object_class_text, self.uppercase_class)
+    }
+
+    /// Bleed added to each side of the output, in output pixels.
+    pub fn bleed_pixels(&self) -> u32 {
+        (self.bleed.min(MAX_BLEED) as f32 * self.output_resolution as f32 / LABEL_SIZE as f32).round() as u32
+    }
+
+    /// Side length of the exported image, including bleed.
+    pub fn export_size(&self) -> u32 {
+        self.output_resolution + 2 * self.bleed_pixels()
+    }
+
+    pub fn derived_file_name(&self) -> String {
+        let stem = crate::utils::sanitize_filename(&format!("SCP-{}_{}", self.scp_number, self.object_class_text));
+        format!("{}.{}", stem, self.output_format.extension())
+    }
+
+
     pub fn save(&self, path: &PathBuf) -> Result<(), std::io::Error> {
         let json = serde_json::to_string_pretty(self)?;
         std::fs::write(path, json)?;
@@ -152,11 +286,378 @@ impl LabelConfig {
     }
 }
 
+fn uppercased_if(text: &str, uppercase: bool) -> Cow<'_, str> {
+    if uppercase {
+        Cow::Owned(text.to_uppercase())
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// A named group of burn settings. Applying one turns the burn on and leaves
+/// the seed alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BurnPreset {
+    Light,
+    Heavy,
+    Patches,
+}
+
+impl BurnPreset {
+    pub fn all() -> Vec<Self> {
+        vec![Self::Light, Self::Heavy, Self::Patches]
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Light => "Faint scorching with soft edges",
+            Self::Heavy => "Deep charring that burns through in places",
+            Self::Patches => "Scattered cell-shaped burn patches",
+        }
+    }
+
+    pub fn apply_to(&self, config: &mut LabelConfig) {
+        let defaults = LabelConfig::default();
+        config.apply_burn = true;
+        config.burn_scale_multiplier = defaults.burn_scale_multiplier;
+        config.burn_detail_blend = defaults.burn_detail_blend;
+        config.burn_turbulence_freq = defaults.burn_turbulence_freq;
+
+        let (burn_type, amount, scale, detail, edge_softness, irregularity, char, turbulence) = match self {
+            Self::Light => (BurnType::Perlin, 0.2, 1.0, 0.4, 0.6, 0.15, 0.5, 0.1),
+            Self::Heavy => (BurnType::Perlin, 0.75, 0.8, 0.6, 0.3, 0.4, 0.9, 0.1),
+            Self::Patches => (BurnType::Patches, 0.5, 3.0, 0.5, 0.5, 0.2, 0.8, 0.3),
+        };
+        config.burn_type = burn_type;
+        config.burn_amount = amount;
+        config.burn_scale = scale;
+        config.burn_detail = detail;
+        config.burn_edge_softness = edge_softness;
+        config.burn_irregularity = irregularity;
+        config.burn_char = char;
+        config.burn_turbulence_strength = turbulence;
+    }
+}
+
+impl std::fmt::Display for BurnPreset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BurnPreset::Light => write!(f, "Light"),
+            BurnPreset::Heavy => write!(f, "Heavy"),
+            BurnPreset::Patches => write!(f, "Patches"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffectSettings {
+    pub apply_texture: bool,
+    #[serde(default = "default_texture_name")]
+    pub texture_name: String,
+    pub texture_opacity: f32,
+    #[serde(default)]
+    pub texture_blend: TextureBlendMode,
+    #[serde(default)]
+    pub texture_layers: Vec<TextureLayer>,
+    pub brightness: f32,
+    pub contrast: f32,
+    pub grayscale: bool,
+    pub apply_burn: bool,
+    pub burn_type: BurnType,
+    pub burn_amount: f32,
+    pub burn_scale: f32,
+    pub burn_detail: f32,
+    pub burn_edge_softness: f32,
+    pub burn_irregularity: f32,
+    pub burn_char: f32,
+    pub burn_seed: u32,
+    pub burn_scale_multiplier: f32,
+    pub burn_detail_blend: f32,
+    pub burn_turbulence_freq: f32,
+    pub burn_turbulence_strength: f32,
+}
+
+impl EffectSettings {
+    pub fn from_config(config: &LabelConfig) -> Self {
+        Self {
+            apply_texture: config.apply_texture,
+            texture_name: config.texture_name.clone(),
+            texture_opacity: config.texture_opacity,
+            texture_blend: config.texture_blend,
+            texture_layers: config.texture_layers.clone(),
+            brightness: config.brightness,
+            contrast: config.contrast,
+            grayscale: config.grayscale,
+            apply_burn: config.apply_burn,
+            burn_type: config.burn_type,
+            burn_amount: config.burn_amount,
+            burn_scale: config.burn_scale,
+            burn_detail: config.burn_detail,
+            burn_edge_softness: config.burn_edge_softness,
+            burn_irregularity: config.burn_irregularity,
+            burn_char: config.burn_char,
+            burn_seed: config.burn_seed,
+            burn_scale_multiplier: config.burn_scale_multiplier,
+            burn_detail_blend: config.burn_detail_blend,
+            burn_turbulence_freq: config.burn_turbulence_freq,
+            burn_turbulence_strength: config.burn_turbulence_strength,
+        }
+    }
+
+    pub fn apply_to(&self, config: &mut LabelConfig) {
+        config.apply_texture = self.apply_texture;
+        config.texture_name = self.texture_name.clone();
+        config.texture_opacity = self.texture_opacity;
+        config.texture_blend = self.texture_blend;
+        config.texture_layers = self.texture_layers.clone();
+        config.brightness = self.brightness;
+        config.contrast = self.contrast;
+        config.grayscale = self.grayscale;
+        config.apply_burn = self.apply_burn;
+        config.burn_type = self.burn_type;
+        config.burn_amount = self.burn_amount;
+        config.burn_scale = self.burn_scale;
+        config.burn_detail = self.burn_detail;
+        config.burn_edge_softness = self.burn_edge_softness;
+        config.burn_irregularity = self.burn_irregularity;
+        config.burn_char = self.burn_char;
+        config.burn_seed = self.burn_seed;
+        config.burn_scale_multiplier = self.burn_scale_multiplier;
+        config.burn_detail_blend = self.burn_detail_blend;
+        config.burn_turbulence_freq = self.burn_turbulence_freq;
+        config.burn_turbulence_strength = self.burn_turbulence_strength;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, clap::ValueEnum)]
+pub enum TextureBlendMode {
+    #[default]
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+}
+
+impl TextureBlendMode {
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::Normal,
+            Self::Multiply,
+            Self::Screen,
+            Self::Overlay,
+            Self::Darken,
+            Self::Lighten,
+        ]
+    }
+
+    pub fn blend(&self, base: u8, texture: u8) -> u8 {
+        let (b, t) = (base as f32 / 255.0, texture as f32 / 255.0);
+        let result = match self {
+            Self::Normal => return texture,
+            Self::Multiply => b * t,
+            Self::Screen => 1.0 - (1.0 - b) * (1.0 - t),
+            Self::Overlay => {
+                if b < 0.5 {
+                    2.0 * b * t
+                } else {
+                    1.0 - 2.0 * (1.0 - b) * (1.0 - t)
+                }
+            }
+            Self::Darken => b.min(t),
+            Self::Lighten => b.max(t),
+        };
+        (result.clamp(0.0, 1.0) * 255.0).round() as u8
+    }
+}
+
+impl std::fmt::Display for TextureBlendMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TextureBlendMode::Normal => write!(f, "Normal"),
+            TextureBlendMode::Multiply => write!(f, "Multiply"),
+            TextureBlendMode::Screen => write!(f, "Screen"),
+            TextureBlendMode::Overlay => write!(f, "Overlay"),
+            TextureBlendMode::Darken => write!(f, "Darken"),
+            TextureBlendMode::Lighten => write!(f, "Lighten"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+pub enum LabelLayer {
+    ScpNumber,
+    ObjectClass,
+    UserImage,
+    Hazard,
+    Texture,
+}
+
+impl LabelLayer {
+    pub fn all() -> Vec<Self> {
+        vec![Self::ScpNumber, Self::ObjectClass, Self::UserImage, Self::Hazard, Self::Texture]
+    }
+}
+
+impl std::fmt::Display for LabelLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LabelLayer::ScpNumber => write!(f, "SCP Number"),
+            LabelLayer::ObjectClass => write!(f, "Object Class"),
+            LabelLayer::UserImage => write!(f, "Image"),
+            LabelLayer::Hazard => write!(f, "Hazard Icon"),
+            LabelLayer::Texture => write!(f, "Texture"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, clap::ValueEnum)]
+pub enum WatermarkCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+impl WatermarkCorner {
+    pub fn all() -> Vec<Self> {
+        vec![Self::TopLeft, Self::TopRight, Self::BottomLeft, Self::BottomRight]
+    }
+}
+
+impl std::fmt::Display for WatermarkCorner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WatermarkCorner::TopLeft => write!(f, "Top Left"),
+            WatermarkCorner::TopRight => write!(f, "Top Right"),
+            WatermarkCorner::BottomLeft => write!(f, "Bottom Left"),
+            WatermarkCorner::BottomRight => write!(f, "Bottom Right"),
+        }
+    }
+}
+
+/// What fills the image slot when no image is set. Gradient angles are in
+/// degrees clockwise from left-to-right, so 90 runs top to bottom.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum BackgroundFill {
+    #[default]
+    None,
+    Solid(SerializableColor),
+    LinearGradient {
+        from: SerializableColor,
+        to: SerializableColor,
+        angle: f32,
+    },
+    RadialGradient {
+        inner: SerializableColor,
+        outer: SerializableColor,
+    },
+}
+
+impl BackgroundFill {
+    pub fn kind(&self) -> BackgroundFillKind {
+        match self {
+            Self::None => BackgroundFillKind::None,
+            Self::Solid(_) => BackgroundFillKind::Solid,
+            Self::LinearGradient { .. } => BackgroundFillKind::LinearGradient,
+            Self::RadialGradient { .. } => BackgroundFillKind::RadialGradient,
+        }
+    }
+
+    /// The start color, end color and angle, with defaults for the ones this
+    /// kind of fill doesn't use.
+    pub fn parts(&self) -> (SerializableColor, SerializableColor, f32) {
+        let (start, end, angle) = (Color::BLACK.into(), Color::WHITE.into(), 90.0);
+        match *self {
+            Self::None => (start, end, angle),
+            Self::Solid(color) => (color, end, angle),
+            Self::LinearGradient { from, to, angle } => (from, to, angle),
+            Self::RadialGradient { inner, outer } => (inner, outer, angle),
+        }
+    }
+
+    pub fn from_parts(kind: BackgroundFillKind, start: SerializableColor, end: SerializableColor, angle: f32) -> Self {
+        match kind {
+            BackgroundFillKind::None => Self::None,
+            BackgroundFillKind::Solid => Self::Solid(start),
+            BackgroundFillKind::LinearGradient => Self::LinearGradient { from: start, to: end, angle },
+            BackgroundFillKind::RadialGradient => Self::RadialGradient { inner: start, outer: end },
+        }
+    }
+
+    /// The same colors and angle as a different kind of fill.
+    pub fn with_kind(&self, kind: BackgroundFillKind) -> Self {
+        let (start, end, angle) = self.parts();
+        Self::from_parts(kind, start, end, angle)
+    }
+
+    pub fn with_start(&self, start: SerializableColor) -> Self {
+        let (_, end, angle) = self.parts();
+        Self::from_parts(self.kind(), start, end, angle)
+    }
+
+    pub fn with_end(&self, end: SerializableColor) -> Self {
+        let (start, _, angle) = self.parts();
+        Self::from_parts(self.kind(), start, end, angle)
+    }
+
+    pub fn with_angle(&self, angle: f32) -> Self {
+        let (start, end, _) = self.parts();
+        Self::from_parts(self.kind(), start, end, angle)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BackgroundFillKind {
+    None,
+    Solid,
+    LinearGradient,
+    RadialGradient,
+}
+
+impl BackgroundFillKind {
+    pub fn all() -> Vec<Self> {
+        vec![Self::None, Self::Solid, Self::LinearGradient, Self::RadialGradient]
+    }
+}
+
+impl std::fmt::Display for BackgroundFillKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BackgroundFillKind::None => write!(f, "None"),
+            BackgroundFillKind::Solid => write!(f, "Solid Color"),
+            BackgroundFillKind::LinearGradient => write!(f, "Linear Gradient"),
+            BackgroundFillKind::RadialGradient => write!(f, "Radial Gradient"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextureLayer {
+    pub name: String,
+    pub opacity: f32,
+    #[serde(default)]
+    pub blend: TextureBlendMode,
+}
+
+impl Default for TextureLayer {
+    fn default() -> Self {
+        Self {
+            name: default_texture_name(),
+            opacity: 0.3,
+            blend: TextureBlendMode::Normal,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, clap::ValueEnum)]
 pub enum ResizeMethod {
     CropToFit,
     Stretch,
     Letterbox,
+    BlurFill,
 }
 
 impl std::fmt::Display for ResizeMethod {
@@ -165,6 +666,37 @@ impl std::fmt::Display for ResizeMethod {
             ResizeMethod::CropToFit => write!(f, "CropToFit"),
             ResizeMethod::Stretch => write!(f, "Stretch"),
             ResizeMethod::Letterbox => write!(f, "Letterbox"),
+            ResizeMethod::BlurFill => write!(f, "BlurFill"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, clap::ValueEnum)]
+pub enum JpegSubsampling {
+    #[default]
+    #[serde(rename = "4:4:4")]
+    #[value(name = "444")]
+    Full,
+    #[serde(rename = "4:2:2")]
+    #[value(name = "422")]
+    Half,
+    #[serde(rename = "4:2:0")]
+    #[value(name = "420")]
+    Quarter,
+}
+
+impl JpegSubsampling {
+    pub fn all() -> Vec<Self> {
+        vec![Self::Full, Self::Half, Self::Quarter]
+    }
+}
+
+impl std::fmt::Display for JpegSubsampling {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JpegSubsampling::Full => write!(f, "4:4:4"),
+            JpegSubsampling::Half => write!(f, "4:2:2"),
+            JpegSubsampling::Quarter => write!(f, "4:2:0"),
         }
     }
 }
@@ -173,6 +705,23 @@ impl std::fmt::Display for ResizeMethod {
 pub enum OutputFormat {
     Png,
     Jpeg,
+    Webp,
+    Svg,
+}
+
+impl OutputFormat {
+    pub fn all() -> Vec<Self> {
+        vec![Self::Png, Self::Jpeg, Self::Webp, Self::Svg]
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Png => "png",
+            OutputFormat::Jpeg => "jpg",
+            OutputFormat::Webp => "webp",
+            OutputFormat::Svg => "svg",
+        }
+    }
 }
 
 impl std::fmt::Display for OutputFormat {
@@ -180,6 +729,8 @@ impl std::fmt::Display for OutputFormat {
         match self {
             OutputFormat::Png => write!(f, "Png"),
             OutputFormat::Jpeg => write!(f, "Jpeg"),
+            OutputFormat::Webp => write!(f, "Webp"),
+            OutputFormat::Svg => write!(f, "Svg"),
         }
     }
 }
@@ -197,5 +748,89 @@ pub enum ValidationStatus {
     PerfectFit,
     WillCrop,
     WillStretch,
+    WillLetterbox,
     NoImage,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_is_deterministic() {
+        let a = serde_json::to_string(&LabelConfig::default()).unwrap();
+        let b = serde_json::to_string(&LabelConfig::default()).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(LabelConfig::default().scp_number, "000");
+    }
+
+    #[test]
+    fn burn_presets_enable_burn_and_keep_the_seed() {
+        for preset in BurnPreset::all() {
+            let mut config = LabelConfig { burn_seed: 42, ..LabelConfig::default() };
+            preset.apply_to(&mut config);
+            assert!(config.apply_burn);
+            assert_eq!(config.burn_seed, 42);
+        }
+    }
+
+    #[test]
+    fn supersampling_renders_at_least_at_template_size() {
+        let at = |output_resolution, supersample, native_resolution| {
+            LabelConfig { output_resolution, supersample, native_resolution, ..LabelConfig::default() }
+                .working_resolution()
+        };
+        assert_eq!(at(128, 1, false), LABEL_SIZE);
+        assert_eq!(at(128, 1, true), 128);
+        assert_eq!(at(128, 2, false), LABEL_SIZE);
+        assert_eq!(at(512, 2, false), 1024);
+        assert_eq!(at(1000, 4, true), 4000);
+        assert_eq!(at(4096, 4, false), MAX_OUTPUT_RESOLUTION);
+    }
+
+    #[test]
+    fn export_size_ignores_supersampling() {
+        let config = LabelConfig { output_resolution: 300, supersample: 4, ..LabelConfig::default() };
+        assert_eq!(config.export_size(), 300);
+
+        let config = LabelConfig { bleed: 16, ..config };
+        assert_eq!(config.export_size(), 318);
+    }
+
+    #[test]
+    fn uppercase_flags_apply_to_each_field_separately() {
+        let config = LabelConfig {
+            scp_number: "173-j".to_string(),
+            object_class_text: "euclid".to_string(),
+            uppercase_class: true,
+            ..LabelConfig::default()
+        };
+        assert_eq!(config.display_scp_number(), "173-j");
+        assert_eq!(config.display_object_class(), "EUCLID");
+
+        let config = LabelConfig { uppercase_number: true, ..config };
+        assert_eq!(config.display_scp_number(), "173-J");
+    }
+
+    #[test]
+    fn composition_order_fills_in_missing_layers() {
+        let config = LabelConfig {
+            layer_order: vec![LabelLayer::Texture, LabelLayer::Hazard, LabelLayer::Texture],
+            ..LabelConfig::default()
+        };
+        assert_eq!(
+            config.composition_order(),
+            vec![
+                LabelLayer::Texture,
+                LabelLayer::Hazard,
+                LabelLayer::ScpNumber,
+                LabelLayer::ObjectClass,
+                LabelLayer::UserImage,
+            ]
+        );
+
+        let json = serde_json::to_string(&config).unwrap();
+        let loaded: LabelConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.layer_order, config.layer_order);
+    }
+}
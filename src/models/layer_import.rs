@@ -0,0 +1,241 @@
+//! Imports a flat layer description exported from an image editor.
+//!
+//! ```json
+//! {
+//!   "width": 1024,
+//!   "height": 1024,
+//!   "class": "KETER",
+//!   "layers": [
+//!     { "kind": "scp_number", "text": "173", "color": "#202020", "offset": [0, 8], "font_size": 140 },
+//!     { "kind": "object_class", "text": "KETER", "color": "#202020cc", "offset": [-12, 0] },
+//!     { "kind": "image", "path": "statue.png" }
+//!   ]
+//! }
+//! ```
+//!
+//! `width` and `height` give the document size (default 512); offsets and font
+//! sizes are in document pixels and scaled onto the label. Offsets move text
+//! from where the template normally places it. Only the `scp_number` layer is
+//! required, each kind may appear once, and `class` is one of the object class
+//! names shown in the app.
+
+use super::{ClassType, LabelConfig, SerializableColor, LABEL_SIZE};
+use crate::utils::{color, LabelError};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LayerDocument {
+    #[serde(default = "default_document_size")]
+    width: f32,
+    #[serde(default = "default_document_size")]
+    height: f32,
+    class: Option<String>,
+    layers: Vec<Layer>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+enum Layer {
+    ScpNumber(TextLayer),
+    ObjectClass(TextLayer),
+    Image { path: PathBuf },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TextLayer {
+    text: String,
+    color: Option<String>,
+    #[serde(default)]
+    offset: (f32, f32),
+    font_size: Option<f32>,
+}
+
+struct ImportedText {
+    text: String,
+    color: Option<SerializableColor>,
+    offset: (f32, f32),
+    font_size: Option<f32>,
+}
+
+fn default_document_size() -> f32 {
+    LABEL_SIZE as f32
+}
+
+fn invalid(message: String) -> LabelError {
+    LabelError::ConfigLoading(format!("Invalid layer file: {}", message))
+}
+
+impl LayerDocument {
+    fn apply_text(&self, layer: &TextLayer, name: &str) -> Result<ImportedText, LabelError> {
+        let text = layer.text.trim();
+        if text.is_empty() {
+            return Err(invalid(format!("the {} layer has no text", name)));
+        }
+
+        let color = layer
+            .color
+            .as_deref()
+            .map(|hex| color::parse_hex(hex).map(SerializableColor::from))
+            .transpose()
+            .map_err(|e| invalid(format!("the {} layer color: {}", name, e)))?;
+
+        let (dx, dy) = layer.offset;
+        if !dx.is_finite() || !dy.is_finite() {
+            return Err(invalid(format!("the {} layer offset must be a pair of numbers", name)));
+        }
+        let (scale_x, scale_y) = (LABEL_SIZE as f32 / self.width, LABEL_SIZE as f32 / self.height);
+
+        let font_size = match layer.font_size {
+            Some(size) if size.is_finite() && size > 0.0 => Some(size * scale_x),
+            Some(size) => return Err(invalid(format!("the {} layer font size {} must be positive", name, size))),
+            None => None,
+        };
+
+        Ok(ImportedText {
+            text: text.to_string(),
+            color,
+            offset: (dx * scale_x, dy * scale_y),
+            font_size,
+        })
+    }
+}
+
+impl LabelConfig {
+    /// Converts a layer description (see the `layer_import` module docs) into a
+    /// config, leaving everything the layers don't mention at its default.
+    pub fn from_layer_json(json: &str) -> Result<Self, LabelError> {
+        let document: LayerDocument = serde_json::from_str(json).map_err(|e| invalid(e.to_string()))?;
+        if !(document.width > 0.0 && document.height > 0.0) {
+            return Err(invalid(format!("document size {}×{} must be positive", document.width, document.height)));
+        }
+
+        let mut config = LabelConfig::default();
+        let (mut scp_number, mut object_class, mut image) = (false, false, false);
+
+        for layer in &document.layers {
+            match layer {
+                Layer::ScpNumber(layer) => {
+                    if std::mem::replace(&mut scp_number, true) {
+                        return Err(invalid("more than one scp_number layer".to_string()));
+                    }
+                    let imported = document.apply_text(layer, "scp_number")?;
+                    config.scp_number = imported.text;
+                    config.scp_text_offset = imported.offset;
+                    if let Some(color) = imported.color {
+                        config.scp_text_color = color;
+                    }
+                    if let Some(font_size) = imported.font_size {
+                        config.scp_number_font_size = font_size;
+                    }
+                }
+                Layer::ObjectClass(layer) => {
+                    if std::mem::replace(&mut object_class, true) {
+                        return Err(invalid("more than one object_class layer".to_string()));
+                    }
+                    let imported = document.apply_text(layer, "object_class")?;
+                    config.object_class_text = imported.text;
+                    config.class_text_offset = imported.offset;
+                    if let Some(color) = imported.color {
+                        config.class_text_color = color;
+                    }
+                    if let Some(font_size) = imported.font_size {
+                        config.object_class_font_size = font_size;
+                    }
+                }
+                Layer::Image { path } => {
+                    if std::mem::replace(&mut image, true) {
+                        return Err(invalid("more than one image layer".to_string()));
+                    }
+                    if path.as_os_str().is_empty() {
+                        return Err(invalid("the image layer has no path".to_string()));
+                    }
+                    config.image_path = Some(path.clone());
+                }
+            }
+        }
+
+        if !scp_number {
+            return Err(invalid("missing the scp_number layer".to_string()));
+        }
+
+        if let Some(class) = &document.class {
+            config.class_type = ClassType::all()
+                .into_iter()
+                .find(|c| c.as_str().eq_ignore_ascii_case(class.trim()))
+                .ok_or_else(|| invalid(format!("unknown object class '{}'", class)))?;
+            if !object_class {
+                config.object_class_text = config.class_type.as_str().to_string();
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Reads a layer file, resolving its image path against the file's folder
+    /// and checking that the image exists.
+    pub fn import_layers(path: &Path) -> Result<Self, LabelError> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| LabelError::ConfigLoading(format!("Failed to read layer file: {}", e)))?;
+        let mut config = Self::from_layer_json(&json)?;
+
+        if let Some(image_path) = &mut config.image_path {
+            if image_path.is_relative() {
+                *image_path = path.parent().unwrap_or(Path::new(".")).join(&*image_path);
+            }
+            if !image_path.is_file() {
+                return Err(invalid(format!("image {} does not exist", image_path.display())));
+            }
+        }
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_layers_onto_the_label() {
+        let config = LabelConfig::from_layer_json(
+            r##"{
+                "width": 1024, "height": 2048, "class": "keter",
+                "layers": [
+                    { "kind": "scp_number", "text": " 173 ", "color": "#ff0000", "offset": [10, 20], "font_size": 100 },
+                    { "kind": "image", "path": "statue.png" }
+                ]
+            }"##,
+        )
+        .unwrap();
+
+        assert_eq!(config.scp_number, "173");
+        assert_eq!(config.scp_text_offset, (5.0, 5.0));
+        assert_eq!(config.scp_number_font_size, 50.0);
+        assert_eq!(iced::Color::from(config.scp_text_color), iced::Color::from_rgb8(255, 0, 0));
+        assert_eq!(config.class_type, ClassType::Keter);
+        assert_eq!(config.object_class_text, "KETER");
+        assert_eq!(config.image_path, Some(PathBuf::from("statue.png")));
+    }
+
+    #[test]
+    fn rejects_invalid_layers() {
+        let cases = [
+            r#"{ "layers": [] }"#,
+            r#"{ "layers": [{ "kind": "scp_number", "text": "1" }, { "kind": "scp_number", "text": "2" }] }"#,
+            r#"{ "layers": [{ "kind": "scp_number", "text": "1", "color": "red" }] }"#,
+            r#"{ "layers": [{ "kind": "scp_number", "text": "1", "font_size": 0 }] }"#,
+            r##"{ "layers": [{ "kind": "scp_number", "text": "1", "colour": "#fff" }] }"##,
+            r#"{ "class": "Pending", "layers": [{ "kind": "scp_number", "text": "1" }] }"#,
+            r#"{ "width": 0, "layers": [{ "kind": "scp_number", "text": "1" }] }"#,
+        ];
+        for json in cases {
+            assert!(
+                matches!(LabelConfig::from_layer_json(json), Err(LabelError::ConfigLoading(_))),
+                "accepted {}",
+                json
+            );
+        }
+    }
+}
@@ -14,6 +14,30 @@ pub struct TextRegion {
     pub alignment: Alignment,
 }
 
+impl Rectangle {
+    pub fn scaled(self, scale: f32) -> Self {
+        let scale_value = |v: u32| (v as f32 * scale).round() as u32;
+        Self {
+            x: scale_value(self.x),
+            y: scale_value(self.y),
+            width: scale_value(self.width),
+            height: scale_value(self.height),
+        }
+    }
+}
+
+impl TextRegion {
+    pub fn scaled(self, scale: f32) -> Self {
+        let scale_value = |v: u32| (v as f32 * scale).round() as u32;
+        Self {
+            x: scale_value(self.x),
+            y: scale_value(self.y),
+            max_width: scale_value(self.max_width),
+            ..self
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum Alignment {
     Left,
@@ -23,6 +47,8 @@ pub enum Alignment {
 }
 
 pub const LABEL_SIZE: u32 = 512;
+pub const MAX_OUTPUT_RESOLUTION: u32 = 8192;
+pub const MAX_BLEED: u32 = 64;
 
 pub struct CommonLayout;
 impl CommonLayout {
@@ -82,6 +108,9 @@ impl AlternateLayout {
         height: 240,
     };
 
+    /// Alternate templates have a single slot, shared with the hazard icon.
+    pub const USER_IMAGE: Rectangle = Self::HAZARD_ICON;
+
     pub const SCP_NUMBER: TextRegion = TextRegion {
         x: 268,
         y: 167,
@@ -1,13 +1,16 @@
 mod class_type;
 mod hazard;
 pub mod label_config;
+mod layer_import;
 mod layout;
 
 pub use class_type::ClassType;
 pub use hazard::Hazard;
 pub use label_config::{
-    ImageValidation, LabelConfig, OutputFormat, ResizeMethod, ValidationStatus, BurnType,
+    ImageValidation, JpegSubsampling, LabelConfig, OutputFormat, ResizeMethod, ValidationStatus, BackgroundFill, BackgroundFillKind, BurnType, BurnPreset,
+    EffectSettings, LabelLayer, SerializableColor, TextureBlendMode, TextureLayer, WatermarkCorner, DEFAULT_TEXTURE,
 };
 pub use layout::{
-    Alignment, AlternateLayout, CommonLayout, NormalLayout, Rectangle, TextRegion, LABEL_SIZE,
+    Alignment, AlternateLayout, CommonLayout, NormalLayout, Rectangle, TextRegion, LABEL_SIZE, MAX_BLEED,
+    MAX_OUTPUT_RESOLUTION,
 };
\ No newline at end of file
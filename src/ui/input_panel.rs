@@ -1,52 +1,357 @@
-use crate::app::Message;
-use crate::models::{ClassType, Hazard, ImageValidation, LabelConfig, ResizeMethod, ValidationStatus, BurnType};
+use crate::app::{ColorTarget, FontPreview, Message};
+use crate::models::{BackgroundFillKind, ClassType, Hazard, ImageValidation, JpegSubsampling, LabelConfig, OutputFormat, ResizeMethod, ValidationStatus, BurnType, LabelLayer, TextureBlendMode, TextureLayer, WatermarkCorner, LABEL_SIZE, MAX_BLEED, MAX_OUTPUT_RESOLUTION};
 use iced::widget::{button, checkbox, column, container, pick_list, row, slider, text, text_input, Space, radio};
 use iced::{Element, Length, Color};
 use crate::ui::theme;
+use crate::utils::color;
+use std::path::{Path, PathBuf};
 
-fn parse_hex_color(hex: &str) -> Result<Color, ()> {
-    let hex = hex.trim_start_matches('#');
-    if hex.len() != 6 {
-        return Err(());
+fn color_input(
+    label: &str,
+    current: Color,
+    draft: Option<&str>,
+    on_input: fn(String) -> Message,
+    on_submit: Message,
+    recent: &[Color],
+    target: ColorTarget,
+) -> iced::widget::Column<'static, Message> {
+    let value = draft.map(str::to_string).unwrap_or_else(|| color::to_hex(current));
+    let is_invalid = color::parse_hex(&value).is_err();
+
+    let input = text_input("#000000", &value)
+        .on_input(on_input)
+        .on_submit(on_submit)
+        .padding(10)
+        .width(120);
+
+    let swatches = recent.iter().map(|&swatch| {
+        button(container(Space::new(16, 16)).style(theme::swatch(swatch)))
+            .on_press(Message::RecentColorPicked(target, swatch))
+            .padding(0)
+            .style(iced::theme::Button::Text)
+            .into()
+    });
+
+    column![
+        label_text(label),
+        container(input).style(if is_invalid {
+            theme::invalid_input_container()
+        } else {
+            container::Appearance::default()
+        }),
+        row(swatches.collect::<Vec<_>>()).spacing(4),
+    ]
+    .spacing(8)
+}
+
+fn resolution_controls((width, height): (String, String), aspect_locked: bool) -> iced::widget::Column<'static, Message> {
+    let parse = |value: &str| {
+        value
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|v| (1..=MAX_OUTPUT_RESOLUTION).contains(v))
+    };
+    let dimension_input = |value: &str, on_input: fn(String) -> Message| {
+        container(
+            text_input("512", value)
+                .on_input(on_input)
+                .padding(8)
+                .width(80),
+        )
+        .style(if parse(value).is_some() {
+            container::Appearance::default()
+        } else {
+            theme::invalid_input_container()
+        })
+    };
+
+    let mut controls = column![
+        row![
+            dimension_input(&width, Message::ResolutionWidthChanged),
+            text("×").size(14),
+            dimension_input(&height, Message::ResolutionHeightChanged),
+            checkbox("Lock aspect ratio", aspect_locked)
+                .on_toggle(Message::AspectLockToggled)
+                .text_size(13),
+        ]
+        .spacing(8)
+        .align_items(iced::Alignment::Center),
+    ]
+    .spacing(5);
+
+    match (parse(&width), parse(&height)) {
+        (Some(w), Some(h)) if w != h => {
+            controls = controls.push(
+                text(format!("Labels are square; exporting at {}×{}.", w.max(h), w.max(h)))
+                    .size(12)
+                    .style(iced::theme::Text::Color(theme::palette().warning)),
+            );
+        }
+        (None, _) | (_, None) => {
+            controls = controls.push(
+                text(format!("Enter a size between 1 and {} px.", MAX_OUTPUT_RESOLUTION))
+                    .size(12)
+                    .style(iced::theme::Text::Color(theme::palette().error)),
+            );
+        }
+        _ => {}
     }
-    
-    let r = u8::from_str_radix(&hex[0..2], 16).map_err(|_| ())?;
-    let g = u8::from_str_radix(&hex[2..4], 16).map_err(|_| ())?;
-    let b = u8::from_str_radix(&hex[4..6], 16).map_err(|_| ())?;
-    
-    Ok(Color::from_rgb(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0))
+    controls
 }
 
 fn section_header(title: &str) -> iced::widget::Text<'static, iced::Theme> {
     text(title)
         .size(16)
-        .style(iced::theme::Text::Color(theme::ACCENT))
+        .style(iced::theme::Text::Color(theme::palette().accent))
+}
+
+fn effect_controls(
+    controls: iced::widget::Column<'static, Message>,
+    enabled: bool,
+    disabled_hint: &str,
+) -> Element<'static, Message> {
+    if enabled {
+        return controls.into();
+    }
+
+    container(
+        column![
+            text(disabled_hint)
+                .size(12)
+                .style(iced::theme::Text::Color(theme::palette().text_disabled)),
+            controls,
+        ]
+        .spacing(4),
+    )
+    .padding(10)
+    .width(Length::Fill)
+    .style(theme::disabled_section())
+    .into()
+}
+
+fn texture_layer_list(layers: &[TextureLayer], texture_names: &[String]) -> iced::widget::Column<'static, Message> {
+    let mut list = column![label_text("Additional Layers")].spacing(8);
+
+    for (index, layer) in layers.iter().enumerate() {
+        list = list.push(
+            column![
+                row![
+                    pick_list(
+                        texture_names.to_vec(),
+                        Some(layer.name.clone()),
+                        move |name| Message::TextureLayerNameChanged(index, name),
+                    )
+                    .padding(8),
+                    pick_list(
+                        TextureBlendMode::all(),
+                        Some(layer.blend),
+                        move |blend| Message::TextureLayerBlendChanged(index, blend),
+                    )
+                    .padding(8),
+                    button("Remove")
+                        .on_press(Message::RemoveTextureLayer(index))
+                        .padding(8)
+                        .style(iced::theme::Button::Secondary),
+                ]
+                .spacing(8)
+                .align_items(iced::Alignment::Center),
+                row![
+                    slider(0.0..=1.0, layer.opacity, move |v| Message::TextureLayerOpacityChanged(index, v))
+                        .step(0.05)
+                        .width(180),
+                    text(format!("{:.0}%", layer.opacity * 100.0)).size(13),
+                ]
+                .spacing(10)
+                .align_items(iced::Alignment::Center),
+            ]
+            .spacing(6),
+        );
+    }
+
+    list.push(
+        button("Add Texture Layer")
+            .on_press(Message::AddTextureLayer)
+            .padding(8)
+            .style(iced::theme::Button::Secondary),
+    )
+}
+
+fn layer_order_list(order: &[LabelLayer]) -> iced::widget::Column<'static, Message> {
+    let mut list = column![label_text("Layer Order (bottom to top)")].spacing(6);
+
+    for (index, layer) in order.iter().enumerate() {
+        list = list.push(
+            row![
+                text(layer.to_string()).size(13).width(120),
+                button("Up")
+                    .on_press_maybe((index + 1 < order.len()).then_some(Message::MoveLayerUp(index)))
+                    .padding(6)
+                    .style(iced::theme::Button::Secondary),
+                button("Down")
+                    .on_press_maybe((index > 0).then_some(Message::MoveLayerDown(index)))
+                    .padding(6)
+                    .style(iced::theme::Button::Secondary),
+            ]
+            .spacing(8)
+            .align_items(iced::Alignment::Center),
+        );
+    }
+    list
+}
+
+fn recent_files_section(images: &[PathBuf], projects: &[PathBuf]) -> Element<'static, Message> {
+    if images.is_empty() && projects.is_empty() {
+        return Space::with_height(0).into();
+    }
+
+    let file_button = |path: &PathBuf, message: Message| {
+        let name = path.file_name().map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().into_owned());
+        button(text(name).size(13))
+            .on_press(message)
+            .padding(6)
+            .width(Length::Fill)
+            .style(iced::theme::Button::Text)
+    };
+
+    let mut list = column![section_header("Recent"), Space::with_height(10)].spacing(4);
+    if !projects.is_empty() {
+        list = list.push(label_text("Projects"));
+        for path in projects {
+            list = list.push(file_button(path, Message::OpenProject(path.clone())));
+        }
+    }
+    if !images.is_empty() {
+        list = list.push(label_text("Images"));
+        for path in images {
+            list = list.push(file_button(path, Message::ImageSelected(Ok(path.clone()))));
+        }
+    }
+
+    container(list.padding(20)).style(theme::card()).into()
 }
 
 fn label_text(title: &str) -> iced::widget::Text<'static, iced::Theme> {
     text(title)
         .size(13)
-        .style(iced::theme::Text::Color(theme::TEXT_SECONDARY))
+        .style(iced::theme::Text::Color(theme::palette().text_secondary))
 }
 
-pub fn view(config: &LabelConfig, validation: &Option<ImageValidation>, advanced_burn_settings_visible: bool) -> Element<'static, Message> {
+/// Controls for what fills the image slot when no image is selected.
+fn background_fill_controls(
+    config: &LabelConfig,
+    start_draft: Option<&str>,
+    end_draft: Option<&str>,
+    recent_colors: &[Color],
+) -> Element<'static, Message> {
+    let kind = config.background_fill.kind();
+    let (start, end, angle) = config.background_fill.parts();
+    let mut controls = column![
+        label_text("Fill When No Image"),
+        pick_list(BackgroundFillKind::all(), Some(kind), Message::BackgroundFillChanged)
+            .padding(10)
+            .width(200),
+    ]
+    .spacing(8);
+
+    if kind != BackgroundFillKind::None {
+        let start_label = if kind == BackgroundFillKind::Solid { "Fill Color" } else { "Start Color" };
+        controls = controls.push(color_input(
+            start_label,
+            start.into(),
+            start_draft,
+            Message::FillColorChanged,
+            Message::FillColorSubmitted,
+            recent_colors,
+            ColorTarget::FillStart,
+        ));
+    }
+    if matches!(kind, BackgroundFillKind::LinearGradient | BackgroundFillKind::RadialGradient) {
+        controls = controls.push(color_input(
+            "End Color",
+            end.into(),
+            end_draft,
+            Message::FillEndColorChanged,
+            Message::FillEndColorSubmitted,
+            recent_colors,
+            ColorTarget::FillEnd,
+        ));
+    }
+    if kind == BackgroundFillKind::LinearGradient {
+        controls = controls.push(
+            column![
+                label_text(&format!("Angle: {:.0}°", angle)),
+                slider(0.0..=360.0, angle, Message::FillAngleChanged)
+                    .step(5.0)
+                    .width(250),
+            ]
+            .spacing(8),
+        );
+    }
+    controls.into()
+}
+
+pub struct ViewState<'a> {
+    pub validation: &'a Option<ImageValidation>,
+    pub advanced_burn_settings_visible: bool,
+    pub scp_color_draft: Option<&'a str>,
+    pub class_color_draft: Option<&'a str>,
+    pub flatten_color_draft: Option<&'a str>,
+    pub letterbox_color_draft: Option<&'a str>,
+    pub background_color_draft: Option<&'a str>,
+    pub fill_color_draft: Option<&'a str>,
+    pub fill_end_color_draft: Option<&'a str>,
+    pub resolution_inputs: (String, String),
+    pub aspect_locked: bool,
+    pub preview_as_export: bool,
+    pub texture_pack_dir: Option<&'a Path>,
+    pub texture_names: Vec<String>,
+    pub recent_colors: &'a [Color],
+    pub recent_images: &'a [PathBuf],
+    pub recent_projects: &'a [PathBuf],
+    pub watch_image: bool,
+    pub class_series_use_names: bool,
+    pub font_preview: Option<FontPreview>,
+}
+
+pub fn view(config: &LabelConfig, state: ViewState) -> Element<'static, Message> {
+    let ViewState {
+        validation,
+        advanced_burn_settings_visible,
+        scp_color_draft,
+        class_color_draft,
+        flatten_color_draft,
+        letterbox_color_draft,
+        background_color_draft,
+        fill_color_draft,
+        fill_end_color_draft,
+        resolution_inputs,
+        aspect_locked,
+        preview_as_export,
+        texture_pack_dir,
+        texture_names,
+        recent_colors,
+        recent_images,
+        recent_projects,
+        watch_image,
+        class_series_use_names,
+        font_preview,
+    } = state;
+
     let title = text("SCP Label Maker")
         .size(28)
-        .style(iced::theme::Text::Color(Color::WHITE));
+        .style(iced::theme::Text::Color(theme::palette().text_primary));
     
     let subtitle = text("Create custom SCP Foundation labels")
         .size(14)
-        .style(iced::theme::Text::Color(theme::TEXT_SECONDARY));
+        .style(iced::theme::Text::Color(theme::palette().text_secondary));
 
-    let scp_text_color = config.scp_text_color;
-    let class_text_color = config.class_text_color;
-    
     let scp_input = column![
         label_text("SCP Number"),
         row![
             text("SCP-")
                 .size(20)
-                .style(iced::theme::Text::Color(theme::ACCENT)),
+                .style(iced::theme::Text::Color(theme::palette().accent)),
             text_input("001", &config.scp_number)
                 .on_input(Message::ScpNumberChanged)
                 .on_submit(Message::ScpNumberSubmitted(config.scp_number.clone()))
@@ -55,6 +360,9 @@ pub fn view(config: &LabelConfig, validation: &Option<ImageValidation>, advanced
         ]
         .spacing(8)
         .align_items(iced::Alignment::Center),
+        checkbox("Uppercase", config.uppercase_number)
+            .on_toggle(Message::UppercaseNumberToggled)
+            .text_size(13),
     ]
     .spacing(8);
 
@@ -64,7 +372,10 @@ pub fn view(config: &LabelConfig, validation: &Option<ImageValidation>, advanced
             .on_input(Message::ObjectClassChanged)
             .on_submit(Message::ObjectClassSubmitted(config.object_class_text.clone()))
             .padding(10)
-            .width(200)
+            .width(200),
+        checkbox("Uppercase", config.uppercase_class)
+            .on_toggle(Message::UppercaseClassToggled)
+            .text_size(13),
     ]
     .spacing(8);
 
@@ -179,6 +490,7 @@ pub fn view(config: &LabelConfig, validation: &Option<ImageValidation>, advanced
                     .width(180),
                 text_input("1.2", &format!("{:.2}", config.scp_line_spacing))
                     .on_input(Message::ScpLineSpacingTextChanged)
+                    .on_submit(Message::LineSpacingSubmitted)
                     .padding(8)
                     .width(70),
             ]
@@ -195,6 +507,7 @@ pub fn view(config: &LabelConfig, validation: &Option<ImageValidation>, advanced
                     .width(180),
                 text_input("1.2", &format!("{:.2}", config.class_line_spacing))
                     .on_input(Message::ClassLineSpacingTextChanged)
+                    .on_submit(Message::LineSpacingSubmitted)
                     .padding(8)
                     .width(70),
             ]
@@ -205,54 +518,78 @@ pub fn view(config: &LabelConfig, validation: &Option<ImageValidation>, advanced
     ]
     .spacing(15);
 
-    let color_controls = row![
-        column![
-            label_text("SCP Number Color"),
-            text_input(
-                "#000000",
-                &format!(
-                    "#{:02x}{:02x}{:02x}",
-                    (Color::from(config.scp_text_color).r * 255.0) as u8,
-                    (Color::from(config.scp_text_color).g * 255.0) as u8,
-                    (Color::from(config.scp_text_color).b * 255.0) as u8
-                )
-            )
-            .on_input(move |s| {
-                if let Ok(color) = parse_hex_color(&s) {
-                    Message::ScpTextColorChanged(color)
-                } else {
-                    Message::ScpTextColorChanged(scp_text_color.into())
-                }
-            })
-            .on_submit(Message::ScpTextColorSubmitted(config.scp_text_color.into()))
-            .padding(10)
-            .width(120),
+    let font_sample: Element<'static, Message> = match font_preview {
+        Some(preview) => column![
+            text(preview.name)
+                .size(12)
+                .style(iced::theme::Text::Color(theme::palette().text_secondary)),
+            container(iced::widget::image(preview.sample))
+                .padding(8)
+                .style(theme::input_container()),
+            button("Use This Font")
+                .on_press(Message::FontSelected(Some(preview.path)))
+                .padding(8)
+                .style(iced::theme::Button::Secondary),
         ]
-        .spacing(8),
-        Space::with_width(20),
-        column![
-            label_text("Object Class Color"),
-            text_input(
-                "#000000",
-                &format!(
-                    "#{:02x}{:02x}{:02x}",
-                    (Color::from(config.class_text_color).r * 255.0) as u8,
-                    (Color::from(config.class_text_color).g * 255.0) as u8,
-                    (Color::from(config.class_text_color).b * 255.0) as u8
-                )
-            )
-            .on_input(move |s| {
-                if let Ok(color) = parse_hex_color(&s) {
-                    Message::ClassTextColorChanged(color)
-                } else {
-                    Message::ClassTextColorChanged(class_text_color.into())
-                }
-            })
-            .on_submit(Message::ClassTextColorSubmitted(config.class_text_color.into()))
-            .padding(10)
-            .width(120),
+        .spacing(6)
+        .into(),
+        None => text("Pick a .ttf or .otf file to see a sample")
+            .size(12)
+            .style(iced::theme::Text::Color(theme::palette().text_disabled))
+            .into(),
+    };
+
+    let current_font = config
+        .font_path
+        .as_deref()
+        .and_then(Path::file_name)
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "Impact (built-in)".to_string());
+
+    let font_controls = column![
+        label_text("Font"),
+        row![
+            button("Select Font")
+                .on_press(Message::SelectFontPressed)
+                .padding(8)
+                .style(iced::theme::Button::Secondary),
+            button("Preview Font...")
+                .on_press(Message::PreviewFontPressed)
+                .padding(8)
+                .style(iced::theme::Button::Secondary),
+            button("Use Default")
+                .on_press_maybe(config.font_path.is_some().then_some(Message::ResetFontPressed))
+                .padding(8)
+                .style(iced::theme::Button::Secondary),
         ]
         .spacing(8),
+        text(format!("Current: {}", current_font))
+            .size(12)
+            .style(iced::theme::Text::Color(theme::palette().text_secondary)),
+        font_sample,
+    ]
+    .spacing(8);
+
+    let color_controls = row![
+        color_input(
+            "SCP Number Color",
+            config.scp_text_color.into(),
+            scp_color_draft,
+            Message::ScpTextColorChanged,
+            Message::ScpTextColorSubmitted,
+            recent_colors,
+            ColorTarget::ScpText,
+        ),
+        Space::with_width(20),
+        color_input(
+            "Object Class Color",
+            config.class_text_color.into(),
+            class_color_draft,
+            Message::ClassTextColorChanged,
+            Message::ClassTextColorSubmitted,
+            recent_colors,
+            ColorTarget::ClassText,
+        ),
     ]
     .spacing(15);
 
@@ -307,6 +644,8 @@ pub fn view(config: &LabelConfig, validation: &Option<ImageValidation>, advanced
             Space::with_height(15),
             line_spacing_controls,
             Space::with_height(15),
+            font_controls,
+            Space::with_height(15),
             color_controls,
             Space::with_height(15),
             offset_controls,
@@ -323,10 +662,11 @@ pub fn view(config: &LabelConfig, validation: &Option<ImageValidation>, advanced
 
     let validation_display = if let Some(val) = validation {
         let (icon, color) = match val.status {
-            ValidationStatus::PerfectFit => ("✓", theme::SUCCESS),
-            ValidationStatus::WillCrop => ("⚠", theme::WARNING),
-            ValidationStatus::WillStretch => ("⚠", Color::from_rgb(0.9, 0.3, 0.3)),
-            ValidationStatus::NoImage => ("ℹ", theme::TEXT_SECONDARY),
+            ValidationStatus::PerfectFit => ("✓", theme::palette().success),
+            ValidationStatus::WillCrop => ("⚠", theme::palette().warning),
+            ValidationStatus::WillStretch => ("⚠", theme::palette().error),
+            ValidationStatus::WillLetterbox => ("ℹ", theme::palette().warning),
+            ValidationStatus::NoImage => ("ℹ", theme::palette().text_secondary),
         };
         
         row![
@@ -353,22 +693,44 @@ pub fn view(config: &LabelConfig, validation: &Option<ImageValidation>, advanced
                 Into::<Element<'static, Message>>::into(
                     text(format!("{}", path.file_name().unwrap().to_string_lossy()))
                         .size(12)
-                        .style(iced::theme::Text::Color(theme::TEXT_SECONDARY))
+                        .style(iced::theme::Text::Color(theme::palette().text_secondary))
                 )
             } else {
                 Into::<Element<'static, Message>>::into(
                     text("No image selected")
                         .size(12)
-                        .style(iced::theme::Text::Color(theme::TEXT_SECONDARY))
+                        .style(iced::theme::Text::Color(theme::palette().text_secondary))
+                )
+            },
+            if config.use_alternate_style {
+                Into::<Element<'static, Message>>::into(
+                    column![
+                        checkbox("Place image in the alternate slot", config.alternate_user_image)
+                            .on_toggle(Message::AlternateUserImageToggled)
+                            .text_size(13),
+                        text(if config.alternate_user_image {
+                            "The alternate slot is shared with the hazard icon, which is drawn on top."
+                        } else {
+                            "Alternate style shares its only slot with the hazard icon; the image is kept for the normal style."
+                        })
+                        .size(12)
+                        .style(iced::theme::Text::Color(theme::palette().warning)),
+                    ]
+                    .spacing(8)
                 )
+            } else {
+                Into::<Element<'static, Message>>::into(column![])
             },
+            checkbox("Reload when the file changes on disk", watch_image)
+                .on_toggle(Message::WatchImageToggled)
+                .text_size(13),
             Space::with_height(10),
             validation_display,
             Space::with_height(15),
             column![
                 label_text("Resize Method"),
                 pick_list(
-                    vec![ResizeMethod::CropToFit, ResizeMethod::Stretch, ResizeMethod::Letterbox],
+                    vec![ResizeMethod::CropToFit, ResizeMethod::Stretch, ResizeMethod::Letterbox, ResizeMethod::BlurFill],
                     Some(config.resize_method),
                     Message::ResizeMethodChanged
                 )
@@ -376,13 +738,28 @@ pub fn view(config: &LabelConfig, validation: &Option<ImageValidation>, advanced
                 .width(200),
             ]
             .spacing(8),
+            if config.resize_method == ResizeMethod::Letterbox {
+                Element::from(color_input(
+                    "Letterbox Fill (alpha 00 for transparent)",
+                    config.letterbox_color.into(),
+                    letterbox_color_draft,
+                    Message::LetterboxColorChanged,
+                    Message::LetterboxColorSubmitted,
+                    recent_colors,
+                    ColorTarget::Letterbox,
+                ))
+            } else {
+                Space::with_height(0).into()
+            },
+            background_fill_controls(config, fill_color_draft, fill_end_color_draft, recent_colors),
         ]
         .spacing(12)
         .padding(20)
     )
     .style(theme::card());
 
-    let image_adjustments = if !config.use_alternate_style {
+    let places_image = !config.use_alternate_style || config.alternate_user_image;
+    let image_adjustments = if places_image {
         container(
             column![
                 section_header("Image Adjustments"),
@@ -449,6 +826,17 @@ pub fn view(config: &LabelConfig, validation: &Option<ImageValidation>, advanced
                 .style(iced::theme::Button::Secondary),
         ]
         .spacing(10),
+        if config.selected_hazard.is_some() {
+            Into::<Element<'static, Message>>::into(column![
+                label_text(&format!("Hazard opacity: {:.0}%", config.hazard_opacity * 100.0)),
+                slider(0.0..=1.0, config.hazard_opacity, Message::HazardOpacityChanged)
+                    .step(0.05)
+                    .width(250),
+            ]
+            .spacing(8))
+        } else {
+            Into::<Element<'static, Message>>::into(column![])
+        },
     ]
     .spacing(8);
 
@@ -456,10 +844,21 @@ pub fn view(config: &LabelConfig, validation: &Option<ImageValidation>, advanced
         checkbox("Apply texture overlay", config.apply_texture)
             .on_toggle(Message::TextureToggled)
             .text_size(13),
-        if config.apply_texture {
-            Into::<Element<'static, Message>>::into(
-                column![
+        effect_controls(
+            column![
                     Space::with_height(8),
+                    label_text("Texture"),
+                    row![
+                        pick_list(
+                            texture_names.clone(),
+                            Some(config.texture_name.clone()),
+                            Message::TextureSelected,
+                        )
+                        .padding(10),
+                        pick_list(TextureBlendMode::all(), Some(config.texture_blend), Message::TextureBlendChanged)
+                            .padding(10),
+                    ]
+                    .spacing(8),
                     label_text(&format!("Opacity: {:.0}%", config.texture_opacity * 100.0)),
                     row![
                         slider(0.0..=1.0, config.texture_opacity, |v| Message::OpacityTextChanged(v.to_string()))
@@ -473,12 +872,28 @@ pub fn view(config: &LabelConfig, validation: &Option<ImageValidation>, advanced
                     ]
                     .spacing(10)
                     .align_items(iced::Alignment::Center),
+                    texture_layer_list(&config.texture_layers, &texture_names),
                 ]
-                .spacing(8)
-            )
-        } else {
-            Into::<Element<'static, Message>>::into(column![])
-        }
+                .spacing(8),
+            config.apply_texture,
+            "Texture overlay is off. Adjust settings, then enable it to apply.",
+        ),
+        Space::with_height(4),
+        label_text(&format!(
+            "Texture packs: {}",
+            texture_pack_dir.map_or("default locations".to_string(), |dir| dir.display().to_string())
+        )),
+        row![
+            button("Choose Folder")
+                .on_press(Message::SelectTexturePackDir)
+                .padding(8)
+                .style(iced::theme::Button::Secondary),
+            button("Use Default")
+                .on_press_maybe(texture_pack_dir.map(|_| Message::TexturePackDirSelected(None)))
+                .padding(8)
+                .style(iced::theme::Button::Secondary),
+        ]
+        .spacing(10),
     ]
     .spacing(8);
 
@@ -486,7 +901,7 @@ pub fn view(config: &LabelConfig, validation: &Option<ImageValidation>, advanced
         checkbox("Apply burn overlay", config.apply_burn)
             .on_toggle(Message::BurnToggled)
             .text_size(13),
-        if config.apply_burn {
+        {
             let advanced_burn_controls = if advanced_burn_settings_visible {
                 column![
                     Space::with_height(10),
@@ -503,7 +918,7 @@ pub fn view(config: &LabelConfig, validation: &Option<ImageValidation>, advanced
                 column![]
             };
 
-            Into::<Element<'static, Message>>::into(
+            effect_controls(
                 column![
                     Space::with_height(8),
                     label_text("Burn Style"),
@@ -516,11 +931,12 @@ pub fn view(config: &LabelConfig, validation: &Option<ImageValidation>, advanced
                     Space::with_height(10),
                     label_text(&format!("Burn Amount: {:.0}%", config.burn_amount * 100.0)),
                     row![
-                        slider(0.0..=1.0, config.burn_amount, |v| Message::BurnAmountChanged(v.to_string()))
+                        slider(0.0..=1.0, config.burn_amount, Message::BurnAmountChanged)
                             .step(0.01)
                             .width(180),
                         text_input("0.35", &format!("{:.2}", config.burn_amount))
-                            .on_input(Message::BurnAmountChanged)
+                            .on_input(Message::BurnAmountTextChanged)
+                            .on_submit(Message::BurnAmountSubmitted)
                             .padding(8)
                             .width(70),
                     ]
@@ -569,12 +985,24 @@ pub fn view(config: &LabelConfig, validation: &Option<ImageValidation>, advanced
                     checkbox("Advanced Burn Settings", advanced_burn_settings_visible)
                         .on_toggle(Message::ToggleAdvancedBurnSettings),
                     advanced_burn_controls,
+                    Space::with_height(10),
+                    row![
+                        button("Reset Burn")
+                            .on_press(Message::ResetBurn)
+                            .padding(10)
+                            .style(iced::theme::Button::Secondary),
+                        button("Export Burn Mask")
+                            .on_press(Message::ExportBurnMask)
+                            .padding(10)
+                            .style(iced::theme::Button::Secondary),
+                    ]
+                    .spacing(8),
                 ]
-                .spacing(8)
+                .spacing(8),
+                config.apply_burn,
+                "Burn overlay is off. Adjust settings, then enable it to apply.",
             )
-        } else {
-            Into::<Element<'static, Message>>::into(column![])
-        }
+        },
     ]
     .spacing(8);
 
@@ -587,6 +1015,65 @@ pub fn view(config: &LabelConfig, validation: &Option<ImageValidation>, advanced
             texture_section,
             Space::with_height(15),
             burn_section,
+            Space::with_height(15),
+            layer_order_list(&config.composition_order()),
+            Space::with_height(15),
+            row![
+                button("Copy Effects")
+                    .on_press(Message::CopyEffects)
+                    .padding(10)
+                    .style(iced::theme::Button::Secondary),
+                button("Paste Effects")
+                    .on_press(Message::PasteEffects)
+                    .padding(10)
+                    .style(iced::theme::Button::Secondary),
+            ]
+            .spacing(8),
+        ]
+        .spacing(12)
+        .padding(20)
+    )
+    .style(theme::card());
+
+    let watermark_section = container(
+        column![
+            section_header("Watermark"),
+            Space::with_height(10),
+            label_text("Credit text"),
+            text_input("e.g. Art by ...", config.watermark.as_deref().unwrap_or(""))
+                .on_input(Message::WatermarkTextChanged)
+                .on_submit(Message::WatermarkSubmitted)
+                .padding(10)
+                .width(300),
+            row![
+                button("Select Image")
+                    .on_press(Message::SelectWatermarkImagePressed)
+                    .padding(10)
+                    .style(iced::theme::Button::Secondary),
+                button("Clear")
+                    .on_press_maybe(config.watermark_image.as_ref().map(|_| Message::ClearWatermarkImage))
+                    .padding(10)
+                    .style(iced::theme::Button::Secondary),
+                text(match &config.watermark_image {
+                    Some(path) => path.file_name().map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().into_owned()),
+                    None => "No image".to_string(),
+                })
+                .size(12)
+                .style(iced::theme::Text::Color(theme::palette().text_secondary)),
+            ]
+            .spacing(10)
+            .align_items(iced::Alignment::Center),
+            row![
+                label_text("Corner"),
+                pick_list(WatermarkCorner::all(), Some(config.watermark_corner), Message::WatermarkCornerSelected)
+                    .padding(8),
+            ]
+            .spacing(10)
+            .align_items(iced::Alignment::Center),
+            label_text(&format!("Opacity: {:.0}%", config.watermark_opacity * 100.0)),
+            slider(0.0..=1.0, config.watermark_opacity, Message::WatermarkOpacityChanged)
+                .step(0.05)
+                .width(250),
         ]
         .spacing(12)
         .padding(20)
@@ -609,10 +1096,100 @@ pub fn view(config: &LabelConfig, validation: &Option<ImageValidation>, advanced
                     .into()
                 }).collect::<Vec<_>>()
             ).spacing(10),
+            resolution_controls(resolution_inputs, aspect_locked),
+            label_text(&format!(
+                "Scale: {:.1}× of {}px",
+                config.output_resolution as f32 / LABEL_SIZE as f32,
+                LABEL_SIZE
+            )),
+            slider(
+                0.5..=8.0,
+                (config.output_resolution as f32 / LABEL_SIZE as f32).clamp(0.5, 8.0),
+                |scale| Message::ResolutionChanged((LABEL_SIZE as f32 * scale).round() as u32),
+            )
+            .step(0.5)
+            .width(250),
+            label_text("Supersampling:"),
+            row(
+                [1u8, 2, 4].iter().map(|&factor| {
+                    radio(
+                        format!("{}x", factor),
+                        factor,
+                        Some(config.supersample),
+                        Message::SupersampleChanged,
+                    )
+                    .into()
+                }).collect::<Vec<_>>()
+            ).spacing(10),
+            text("Higher factors give smoother text and edges but render up to 16x more pixels.")
+                .size(12)
+                .style(iced::theme::Text::Color(theme::palette().text_secondary)),
+            checkbox("Render natively at output resolution", config.native_resolution)
+                .on_toggle(Message::NativeResolutionToggled)
+                .text_size(13),
             Space::with_height(5),
-            text("Note: Increasing resolution interpolates the image, it does not add new detail.")
+            text(if config.native_resolution {
+                "Text and layout are drawn at the output size; template artwork is still upscaled."
+            } else {
+                "Note: Increasing resolution interpolates the image, it does not add new detail."
+            })
                 .size(12)
-                .style(iced::theme::Text::Color(theme::TEXT_SECONDARY)),
+                .style(iced::theme::Text::Color(theme::palette().text_secondary)),
+            label_text(&format!("Print Bleed: {}px", config.bleed)),
+            slider(0..=MAX_BLEED, config.bleed, Message::BleedChanged).width(220),
+            checkbox("Crop marks in the bleed area", config.print_marks && config.bleed > 0)
+                .on_toggle_maybe((config.bleed > 0).then_some(Message::PrintMarksToggled))
+                .text_size(13),
+            row![
+                label_text("Export Format"),
+                pick_list(OutputFormat::all(), Some(config.output_format), Message::FormatChanged).padding(8),
+            ]
+            .spacing(10)
+            .align_items(iced::Alignment::Center),
+            row![
+                label_text("JPEG Chroma Subsampling"),
+                pick_list(
+                    JpegSubsampling::all(),
+                    Some(config.jpeg_subsampling),
+                    Message::JpegSubsamplingChanged,
+                )
+                .padding(8),
+            ]
+            .spacing(10)
+            .align_items(iced::Alignment::Center),
+            checkbox("Preview with export format compression", preview_as_export)
+                .on_toggle(Message::PreviewAsExportToggled)
+                .text_size(13),
+            checkbox("Flatten transparency on export", config.flatten_to.is_some())
+                .on_toggle(Message::FlattenToggled)
+                .text_size(13),
+            match config.flatten_to {
+                Some(flatten_to) => Element::from(color_input(
+                    "Flatten Color",
+                    flatten_to.into(),
+                    flatten_color_draft,
+                    Message::FlattenColorChanged,
+                    Message::FlattenColorSubmitted,
+                    recent_colors,
+                    ColorTarget::Flatten,
+                )),
+                None if config.output_format == OutputFormat::Jpeg => Element::from(color_input(
+                    "JPEG Background",
+                    config.background_color.into(),
+                    background_color_draft,
+                    Message::BackgroundColorChanged,
+                    Message::BackgroundColorSubmitted,
+                    recent_colors,
+                    ColorTarget::Background,
+                )),
+                None => Space::with_height(0).into(),
+            },
+            checkbox("Lossless WebP", config.webp_lossless)
+                .on_toggle(Message::WebpLosslessToggled)
+                .text_size(13),
+            checkbox("Optimize PNG size (slower export)", config.optimize_png)
+                .on_toggle(Message::OptimizePngToggled)
+                .text_size(13),
             Space::with_height(15),
             row![
                 button("Save Config")
@@ -632,13 +1209,40 @@ pub fn view(config: &LabelConfig, validation: &Option<ImageValidation>, advanced
                     .on_press(Message::LoadProject)
                     .padding(10)
                     .style(iced::theme::Button::Secondary),
+                button("Import Layers")
+                    .on_press(Message::ImportLayers)
+                    .padding(10)
+                    .style(iced::theme::Button::Secondary),
+                Space::with_width(10),
+                button("Preview Batch")
+                    .on_press(Message::LoadBatch)
+                    .padding(10)
+                    .style(iced::theme::Button::Secondary),
             ]
             .spacing(8),
             Space::with_height(15),
-            button("Export Label")
-                .on_press(Message::ExportPressed)
-                .padding(15)
-                .style(iced::theme::Button::Primary),
+            row![
+                button("Export Label")
+                    .on_press(Message::ExportPressed)
+                    .padding(15)
+                    .style(iced::theme::Button::Primary),
+                button("Export History")
+                    .on_press(Message::ShowExportHistory)
+                    .padding(15)
+                    .style(iced::theme::Button::Secondary),
+            ]
+            .spacing(8),
+            row![
+                button("Export All Classes")
+                    .on_press(Message::ExportClassSeriesPressed)
+                    .padding(10)
+                    .style(iced::theme::Button::Secondary),
+                checkbox("Use class names as object text", class_series_use_names)
+                    .on_toggle(Message::ClassSeriesNamesToggled)
+                    .text_size(13),
+            ]
+            .spacing(12)
+            .align_items(iced::Alignment::Center),
         ]
         .spacing(12)
         .padding(20)
@@ -646,11 +1250,17 @@ pub fn view(config: &LabelConfig, validation: &Option<ImageValidation>, advanced
     .style(theme::card());
 
     let content = column![
-        column![
-            title,
-            subtitle,
+        row![
+            column![
+                title,
+                subtitle,
+            ]
+            .spacing(5)
+            .width(Length::Fill),
+            pick_list(theme::UiTheme::all(), Some(theme::ui_theme()), Message::UiThemeSelected)
+                .padding(8),
         ]
-        .spacing(5)
+        .align_items(iced::Alignment::Center)
         .padding(5),
         Space::with_height(20),
         basic_settings,
@@ -660,7 +1270,7 @@ pub fn view(config: &LabelConfig, validation: &Option<ImageValidation>, advanced
         row![
             image_section,
             Space::with_width(15),
-            if !config.use_alternate_style {
+            if places_image {
                 Into::<Element<'static, Message>>::into(image_adjustments)
             } else {
                 Into::<Element<'static, Message>>::into(container(column![]))
@@ -670,7 +1280,11 @@ pub fn view(config: &LabelConfig, validation: &Option<ImageValidation>, advanced
         Space::with_height(15),
         effects_section,
         Space::with_height(15),
+        watermark_section,
+        Space::with_height(15),
         export_section,
+        Space::with_height(15),
+        recent_files_section(recent_images, recent_projects),
         Space::with_height(20),
     ]
     .spacing(0)
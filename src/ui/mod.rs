@@ -1,3 +1,5 @@
 pub mod input_panel;
+pub mod panned_image;
 pub mod preview_panel;
-pub mod theme;
\ No newline at end of file
+pub mod theme;
+pub mod ui_state;
\ No newline at end of file
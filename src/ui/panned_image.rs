@@ -0,0 +1,204 @@
+use iced::advanced::image::{self as advanced_image, FilterMethod};
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::renderer;
+use iced::advanced::widget::{tree, Tree, Widget};
+use iced::advanced::{Clipboard, Shell};
+use iced::event::{self, Event};
+use iced::widget::image::Handle;
+use iced::{mouse, Element, Length, Point, Rectangle, Size, Vector};
+
+const PIXELS_PER_LINE: f32 = 40.0;
+
+/// An image drawn at a fixed size, clipped to its frame and translated by an
+/// offset that the user can change by dragging.
+pub struct PannedImage<'a, Message> {
+    handle: Handle,
+    size: Size,
+    offset: Vector,
+    filter_method: FilterMethod,
+    on_drag: Option<Box<dyn Fn(Vector) -> Message + 'a>>,
+    on_hover: Option<Box<dyn Fn(Option<Point>) -> Message + 'a>>,
+    on_scroll: Option<Box<dyn Fn(f32, Vector) -> Message + 'a>>,
+}
+
+#[derive(Default)]
+struct State {
+    drag_origin: Option<Point>,
+    hovered: bool,
+}
+
+impl<'a, Message> PannedImage<'a, Message> {
+    pub fn new(handle: Handle, size: Size, offset: Vector) -> Self {
+        Self {
+            handle,
+            size,
+            offset,
+            filter_method: FilterMethod::Linear,
+            on_drag: None,
+            on_hover: None,
+            on_scroll: None,
+        }
+    }
+
+    pub fn filter_method(mut self, filter_method: FilterMethod) -> Self {
+        self.filter_method = filter_method;
+        self
+    }
+
+    /// Publishes the cursor movement since the last event while the left button is held.
+    pub fn on_drag(mut self, f: impl Fn(Vector) -> Message + 'a) -> Self {
+        self.on_drag = Some(Box::new(f));
+        self
+    }
+
+    /// Publishes the cursor position relative to the image's top-left corner,
+    /// or `None` once the cursor leaves the frame.
+    pub fn on_hover(mut self, f: impl Fn(Option<Point>) -> Message + 'a) -> Self {
+        self.on_hover = Some(Box::new(f));
+        self
+    }
+
+    /// Publishes the wheel movement in lines, along with the cursor position
+    /// relative to the frame's center.
+    pub fn on_scroll(mut self, f: impl Fn(f32, Vector) -> Message + 'a) -> Self {
+        self.on_scroll = Some(Box::new(f));
+        self
+    }
+
+    fn image_bounds(&self, frame: Rectangle) -> Rectangle {
+        let center = frame.center() + self.offset;
+        Rectangle {
+            x: center.x - self.size.width / 2.0,
+            y: center.y - self.size.height / 2.0,
+            width: self.size.width,
+            height: self.size.height,
+        }
+    }
+}
+
+impl<'a, Message, Theme, Renderer> Widget<Message, Theme, Renderer> for PannedImage<'a, Message>
+where
+    Renderer: advanced_image::Renderer<Handle = Handle>,
+{
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn size(&self) -> Size<Length> {
+        Size::new(Length::Shrink, Length::Shrink)
+    }
+
+    fn layout(&self, _tree: &mut Tree, _renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        layout::Node::new(limits.resolve(Length::Shrink, Length::Shrink, self.size))
+    }
+
+    fn draw(
+        &self,
+        _tree: &Tree,
+        renderer: &mut Renderer,
+        _theme: &Theme,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        _cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+    ) {
+        let frame = layout.bounds();
+        let image_bounds = self.image_bounds(frame);
+        renderer.with_layer(frame, |renderer| {
+            renderer.draw(self.handle.clone(), self.filter_method, image_bounds);
+        });
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        _viewport: &Rectangle,
+    ) -> event::Status {
+        let state = tree.state.downcast_mut::<State>();
+        let frame = layout.bounds();
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+                if self.on_drag.is_some() && cursor.is_over(frame) =>
+            {
+                state.drag_origin = cursor.position();
+                return event::Status::Captured;
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) if state.drag_origin.is_some() => {
+                state.drag_origin = None;
+                return event::Status::Captured;
+            }
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                if let (Some(position), Some(on_scroll)) = (cursor.position_over(frame), &self.on_scroll) {
+                    let lines = match delta {
+                        mouse::ScrollDelta::Lines { y, .. } => y,
+                        mouse::ScrollDelta::Pixels { y, .. } => y / PIXELS_PER_LINE,
+                    };
+                    shell.publish(on_scroll(lines, position - frame.center()));
+                    return event::Status::Captured;
+                }
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                if let (Some(origin), Some(on_drag)) = (state.drag_origin, &self.on_drag) {
+                    state.drag_origin = Some(position);
+                    shell.publish(on_drag(position - origin));
+                }
+
+                if let Some(on_hover) = &self.on_hover {
+                    if frame.contains(position) {
+                        let image_bounds = self.image_bounds(frame);
+                        state.hovered = true;
+                        shell.publish(on_hover(Some(Point::new(
+                            position.x - image_bounds.x,
+                            position.y - image_bounds.y,
+                        ))));
+                    } else if state.hovered {
+                        state.hovered = false;
+                        shell.publish(on_hover(None));
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        event::Status::Ignored
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+        _renderer: &Renderer,
+    ) -> mouse::Interaction {
+        let state = tree.state.downcast_ref::<State>();
+        if state.drag_origin.is_some() {
+            mouse::Interaction::Grabbing
+        } else if self.on_drag.is_some() && cursor.is_over(layout.bounds()) {
+            mouse::Interaction::Grab
+        } else {
+            mouse::Interaction::Idle
+        }
+    }
+}
+
+impl<'a, Message, Theme, Renderer> From<PannedImage<'a, Message>> for Element<'a, Message, Theme, Renderer>
+where
+    Message: 'a,
+    Renderer: advanced_image::Renderer<Handle = Handle> + 'a,
+{
+    fn from(image: PannedImage<'a, Message>) -> Self {
+        Element::new(image)
+    }
+}
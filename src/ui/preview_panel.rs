@@ -1,17 +1,111 @@
 use crate::app::Message;
-use iced::widget::{Row, Space, button, column, container, image, row, text};
-use iced::{Element, Length, alignment};
+use crate::models::{ClassType, LABEL_SIZE};
+use iced::widget::{Row, Space, button, checkbox, column, container, pick_list, row, text};
+use iced::advanced::image::FilterMethod;
+use iced::{Element, Length, Size, Vector, alignment};
 use iced::theme::Text as TextStyle;
-use crate::ui::theme;
-
-pub fn view(
-    preview: &Option<iced::widget::image::Handle>,
-    zoom_factor: f32,
-    is_gif: bool,
-    is_playing: bool,
-    current_frame: usize,
-    total_frames: usize,
-) -> Element<'static, Message> {
+use crate::ui::panned_image::PannedImage;
+use crate::ui::theme::{self, PreviewBackdrop};
+
+const PREVIEW_SIZE: f32 = 512.0;
+const FRAME_PADDING: f32 = 20.0;
+const PREVIEW_SPACING: f32 = 20.0;
+const CAPTION_HEIGHT: f32 = 30.0;
+pub const MIN_ZOOM: f32 = 0.5;
+pub const MAX_ZOOM: f32 = 4.0;
+const SCROLL_ZOOM_STEP: f32 = 0.1;
+
+pub fn area_id() -> container::Id {
+    container::Id::new("preview-area")
+}
+
+/// Zoom factor at which the framed preview (or both previews when a class is
+/// pinned) fills an area of the given size.
+pub fn fit_zoom(area: iced::Size, pinned: bool) -> f32 {
+    let (columns, caption) = if pinned { (2.0, CAPTION_HEIGHT) } else { (1.0, 0.0) };
+    let available_width = (area.width - PREVIEW_SPACING * (columns - 1.0)) / columns - FRAME_PADDING * 2.0;
+    let available_height = area.height - caption - FRAME_PADDING * 2.0;
+    (available_width.min(available_height) / PREVIEW_SIZE).clamp(MIN_ZOOM, MAX_ZOOM)
+}
+
+/// Keeps a dragged preview at least half visible at the given zoom.
+pub fn clamp_offset(offset: (f32, f32), zoom_factor: f32) -> (f32, f32) {
+    let limit = PREVIEW_SIZE * zoom_factor / 2.0;
+    (offset.0.clamp(-limit, limit), offset.1.clamp(-limit, limit))
+}
+
+/// Applies a wheel movement to the zoom, keeping the label point under the
+/// cursor in place. Returns the new zoom factor and preview offset.
+pub fn scroll_zoom(zoom_factor: f32, offset: (f32, f32), lines: f32, cursor: (f32, f32)) -> (f32, (f32, f32)) {
+    let zoom = (zoom_factor + lines * SCROLL_ZOOM_STEP).clamp(MIN_ZOOM, MAX_ZOOM);
+    let ratio = zoom / zoom_factor;
+    let offset = (
+        cursor.0 - (cursor.0 - offset.0) * ratio,
+        cursor.1 - (cursor.1 - offset.1) * ratio,
+    );
+    (zoom, clamp_offset(offset, zoom))
+}
+
+/// Maps a cursor position relative to the preview image's top-left corner to
+/// label-space pixels, undoing the clockwise display rotation and the print
+/// bleed around the exported image.
+pub fn to_label_space(position: iced::Point, zoom_factor: f32, rotation: u16, bleed: u32, export_size: u32) -> (u32, u32) {
+    let side = export_size as f32;
+    let to_image = |value: f32| value / zoom_factor * side / PREVIEW_SIZE;
+    let (x, y) = (to_image(position.x), to_image(position.y));
+    let (x, y) = match rotation {
+        90 => (y, side - x),
+        180 => (side - x, side - y),
+        270 => (side - y, x),
+        _ => (x, y),
+    };
+    let trimmed = export_size.saturating_sub(2 * bleed).max(1) as f32;
+    let max = (LABEL_SIZE - 1) as f32;
+    let convert = |value: f32| ((value - bleed as f32) * LABEL_SIZE as f32 / trimmed).clamp(0.0, max) as u32;
+    (convert(x), convert(y))
+}
+
+pub struct ViewState<'a> {
+    pub preview: &'a Option<iced::widget::image::Handle>,
+    pub pinned: Option<(ClassType, &'a Option<iced::widget::image::Handle>)>,
+    pub zoom_factor: f32,
+    pub preview_offset: (f32, f32),
+    pub hover_position: Option<(u32, u32)>,
+    pub rotation: u16,
+    /// Bleed and full side length of the exported image, in output pixels.
+    pub bleed_pixels: u32,
+    pub export_size: u32,
+    /// Nearest-neighbour display filtering, for inspecting edges pixel by pixel.
+    pub pixelated: bool,
+    pub is_regenerating: bool,
+    pub backdrop: PreviewBackdrop,
+    /// `Some(false)` when the outline is on but the current style has no image region.
+    pub image_region: Option<bool>,
+    pub is_gif: bool,
+    pub is_playing: bool,
+    pub current_frame: usize,
+    pub total_frames: usize,
+}
+
+pub fn view(state: ViewState) -> Element<'static, Message> {
+    let ViewState {
+        preview,
+        pinned,
+        zoom_factor,
+        preview_offset,
+        hover_position,
+        rotation,
+        bleed_pixels,
+        export_size,
+        pixelated,
+        is_regenerating,
+        backdrop,
+        image_region,
+        is_gif,
+        is_playing,
+        current_frame,
+        total_frames,
+    } = state;
     
     let zoom_controls = container(
         row![
@@ -27,14 +121,67 @@ pub fn view(
                 .on_press(Message::ZoomResetPressed)
                 .padding([8, 16])
                 .style(iced::theme::Button::Secondary),
+            button("Fit")
+                .on_press(Message::ZoomFitPressed)
+                .padding([8, 16])
+                .style(iced::theme::Button::Secondary),
+            button(text(format!("Rotate {}°", rotation)))
+                .on_press(Message::RotatePreviewPressed)
+                .padding([8, 16])
+                .style(iced::theme::Button::Secondary),
             Space::with_width(15),
             container(
                 text(format!("{:.0}%", zoom_factor * 100.0))
                     .size(14)
-                    .style(iced::theme::Text::Color(theme::TEXT_SECONDARY))
+                    .style(iced::theme::Text::Color(theme::palette().text_secondary))
+            )
+            .padding([8, 12])
+            .style(theme::inline_panel()),
+            container(
+                text(match hover_position {
+                    Some((x, y)) => format!("x {}, y {}", x, y),
+                    None => "x –, y –".to_string(),
+                })
+                    .size(14)
+                    .style(iced::theme::Text::Color(theme::palette().text_secondary))
             )
             .padding([8, 12])
             .style(theme::inline_panel()),
+            Space::with_width(15),
+            pick_list(
+                ClassType::all(),
+                pinned.map(|(class_type, _)| class_type),
+                |class_type| Message::PinnedClassSelected(Some(class_type)),
+            )
+            .placeholder("Pin class...")
+            .padding([8, 12]),
+            button("Unpin")
+                .on_press_maybe(pinned.map(|_| Message::PinnedClassSelected(None)))
+                .padding([8, 16])
+                .style(iced::theme::Button::Secondary),
+            Space::with_width(15),
+            pick_list(PreviewBackdrop::all(), Some(backdrop), Message::PreviewBackdropSelected)
+                .padding([8, 12]),
+            Space::with_width(15),
+            checkbox("Image region", image_region.is_some())
+                .on_toggle(Message::ImageRegionToggled)
+                .text_size(13),
+            checkbox("Pixelated", pixelated)
+                .on_toggle(Message::PreviewPixelatedToggled)
+                .text_size(13),
+            if is_regenerating {
+                Element::from(
+                    container(
+                        text("Updating…")
+                            .size(12)
+                            .style(iced::theme::Text::Color(theme::palette().accent))
+                    )
+                    .padding([6, 10])
+                    .style(theme::badge())
+                )
+            } else {
+                Space::with_width(0).into()
+            },
         ]
         .spacing(8)
         .align_items(iced::Alignment::Center)
@@ -60,14 +207,14 @@ pub fn view(
                 container(
                     text(format!("Frame {}/{}", current_frame + 1, total_frames))
                         .size(14)
-                        .style(iced::theme::Text::Color(theme::TEXT_PRIMARY))
+                        .style(iced::theme::Text::Color(theme::palette().text_primary))
                 )
                 .padding([8, 12])
                 .style(theme::inline_panel()),
                 container(
                     text("GIF Animation")
                         .size(12)
-                        .style(iced::theme::Text::Color(theme::ACCENT))
+                        .style(iced::theme::Text::Color(theme::palette().accent))
                 )
                 .padding([6, 10])
                 .style(theme::badge()),
@@ -82,18 +229,50 @@ pub fn view(
     };
 
     let preview_element = if let Some(handle) = preview {
-        let scaled_width = (512.0 * zoom_factor) as u16;
-        let scaled_height = (512.0 * zoom_factor) as u16;
-        
-        container(
-            container(
-                image(handle.clone())
-                    .width(scaled_width)
-                    .height(scaled_height)
+        let scaled_width = (PREVIEW_SIZE * zoom_factor) as u16;
+        let scaled_height = (PREVIEW_SIZE * zoom_factor) as u16;
+        let framed = |handle: &iced::widget::image::Handle, tracked: bool| {
+            let preview = PannedImage::new(
+                handle.clone(),
+                Size::new(scaled_width as f32, scaled_height as f32),
+                Vector::new(preview_offset.0, preview_offset.1),
             )
-            .padding(20)
-            .style(theme::preview_backdrop())
-        )
+            .filter_method(if pixelated { FilterMethod::Nearest } else { FilterMethod::Linear })
+            .on_drag(|delta| Message::PreviewDragged(delta.x, delta.y))
+            .on_scroll(|lines, cursor| Message::ScrollZoom(lines, (cursor.x, cursor.y)));
+            let preview: Element<'static, Message> = if tracked {
+                preview
+                    .on_hover(move |position| {
+                        Message::PreviewHovered(position.map(|p| to_label_space(p, zoom_factor, rotation, bleed_pixels, export_size)))
+                    })
+                    .into()
+            } else {
+                preview.into()
+            };
+            container(preview)
+            .padding(FRAME_PADDING as u16)
+            .style(theme::preview_backdrop(backdrop))
+        };
+
+        let previews: Element<'static, Message> = match pinned {
+            Some((class_type, pinned_handle)) => row![
+                column![caption("Current".to_string()), framed(handle, true)].spacing(8),
+                column![
+                    caption(format!("Pinned: {}", class_type)),
+                    match pinned_handle {
+                        Some(pinned_handle) => Element::from(framed(pinned_handle, false)),
+                        None => Space::new(scaled_width, scaled_height).into(),
+                    },
+                ]
+                .spacing(8),
+            ]
+            .spacing(PREVIEW_SPACING)
+            .into(),
+            None => framed(handle, true).into(),
+        };
+
+        container(previews)
+        .id(area_id())
         .center_x()
         .center_y()
         .width(Length::Fill)
@@ -103,11 +282,11 @@ pub fn view(
             column![
                 text("")
                     .size(48)
-                    .style(iced::theme::Text::Color(theme::ACCENT)),
+                    .style(iced::theme::Text::Color(theme::palette().accent)),
                 Space::with_height(10),
                 text("Generating preview...")
                     .size(16)
-                    .style(iced::theme::Text::Color(theme::TEXT_SECONDARY)),
+                    .style(iced::theme::Text::Color(theme::palette().text_secondary)),
             ]
             .align_items(iced::Alignment::Center)
         )
@@ -117,8 +296,19 @@ pub fn view(
         .height(Length::Fill)
     };
 
+    let region_note: Element<'static, Message> = if image_region == Some(false) {
+        column![
+            Space::with_height(8),
+            caption("Alternate style only places a user image when enabled in the Image card.".to_string()),
+        ]
+        .into()
+    } else {
+        column![].into()
+    };
+
     let content = column![
         zoom_controls,
+        region_note,
         if is_gif {
             Into::<Element<'static, Message>>::into(column![
                 Space::with_height(12),
@@ -139,4 +329,51 @@ pub fn view(
         .width(Length::Fill)
         .height(Length::Fill)
         .into()
-}
\ No newline at end of file
+}
+
+fn caption(label: String) -> Element<'static, Message> {
+    text(label)
+        .size(13)
+        .style(iced::theme::Text::Color(theme::palette().text_secondary))
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scroll_zoom_keeps_point_under_cursor() {
+        let (zoom, offset) = scroll_zoom(1.0, (0.0, 0.0), 5.0, (100.0, -50.0));
+        assert_eq!(zoom, 1.5);
+        assert_eq!(offset, (-50.0, 25.0));
+
+        let (zoom, offset) = scroll_zoom(MAX_ZOOM, (10.0, 10.0), 3.0, (0.0, 0.0));
+        assert_eq!((zoom, offset), (MAX_ZOOM, (10.0, 10.0)));
+
+        let (zoom, _) = scroll_zoom(1.0, (0.0, 0.0), -20.0, (0.0, 0.0));
+        assert_eq!(zoom, MIN_ZOOM);
+    }
+
+    #[test]
+    fn fit_zoom_fills_the_measured_area() {
+        assert_eq!(fit_zoom(iced::Size::new(552.0, 900.0), false), 1.0);
+        assert_eq!(fit_zoom(iced::Size::new(1064.0, 1064.0), false), 2.0);
+        assert_eq!(fit_zoom(iced::Size::new(1124.0, 582.0), true), 1.0);
+        assert_eq!(fit_zoom(iced::Size::new(100.0, 100.0), false), MIN_ZOOM);
+        assert_eq!(fit_zoom(iced::Size::new(10_000.0, 10_000.0), false), MAX_ZOOM);
+    }
+
+    #[test]
+    fn label_space_skips_the_bleed() {
+        assert_eq!(to_label_space(iced::Point::new(100.0, 200.0), 1.0, 0, 0, 1024), (100, 200));
+
+        // 1024px label with 64px bleed each side, shown 512px wide at 100% zoom.
+        let edge = PREVIEW_SIZE * 64.0 / 1152.0;
+        assert_eq!(to_label_space(iced::Point::new(edge, edge), 1.0, 0, 64, 1152), (0, 0));
+        assert_eq!(to_label_space(iced::Point::new(256.0, 256.0), 1.0, 0, 64, 1152), (256, 256));
+        assert_eq!(to_label_space(iced::Point::new(2.0, 2.0), 1.0, 0, 64, 1152), (0, 0));
+        assert_eq!(to_label_space(iced::Point::new(510.0, 510.0), 1.0, 0, 64, 1152), (LABEL_SIZE - 1, LABEL_SIZE - 1));
+        assert_eq!(to_label_space(iced::Point::new(edge * 2.0, edge), 2.0, 90, 64, 1152), (0, LABEL_SIZE - 1));
+    }
+}
@@ -1,23 +1,142 @@
-use iced::{Border, Color, Shadow};
+use iced::{Border, Color, Shadow, Theme};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU8, Ordering};
 use iced::widget::container;
 
-pub const BACKGROUND: Color = Color::from_rgb(0.08, 0.08, 0.12);
-pub const PANEL_BG: Color = Color::from_rgb(0.12, 0.13, 0.17);
-pub const CARD_BG: Color = Color::from_rgb(0.14, 0.15, 0.19);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum UiTheme {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+impl UiTheme {
+    pub fn all() -> Vec<Self> {
+        vec![Self::Dark, Self::Light, Self::HighContrast]
+    }
 
-pub const TEXT_PRIMARY: Color = Color::from_rgb(0.95, 0.96, 0.98);
-pub const TEXT_SECONDARY: Color = Color::from_rgb(0.65, 0.68, 0.75);
+    pub fn palette(&self) -> Palette {
+        match self {
+            UiTheme::Dark => DARK,
+            UiTheme::Light => LIGHT,
+            UiTheme::HighContrast => HIGH_CONTRAST,
+        }
+    }
 
-pub const ACCENT: Color = Color::from_rgb(0.25, 0.55, 0.95);
-pub const ACCENT_HOVER: Color = Color::from_rgb(0.35, 0.65, 1.0);
-pub const ACCENT_DARK: Color = Color::from_rgb(0.15, 0.45, 0.85);
+    pub fn iced_theme(&self) -> Theme {
+        let palette = self.palette();
+        match self {
+            UiTheme::Dark => Theme::Dark,
+            UiTheme::Light => Theme::Light,
+            UiTheme::HighContrast => Theme::custom(
+                self.to_string(),
+                iced::theme::Palette {
+                    background: palette.background,
+                    text: palette.text_primary,
+                    primary: palette.accent,
+                    success: palette.success,
+                    danger: palette.error,
+                },
+            ),
+        }
+    }
+}
 
-pub const BORDER: Color = Color::from_rgb(0.2, 0.22, 0.28);
-pub const BORDER_LIGHT: Color = Color::from_rgb(0.25, 0.28, 0.35);
+impl std::fmt::Display for UiTheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UiTheme::Dark => write!(f, "Dark"),
+            UiTheme::Light => write!(f, "Light"),
+            UiTheme::HighContrast => write!(f, "High Contrast"),
+        }
+    }
+}
 
-pub const SUCCESS: Color = Color::from_rgb(0.2, 0.8, 0.4);
-pub const WARNING: Color = Color::from_rgb(1.0, 0.65, 0.0);
-pub const ERROR: Color = Color::from_rgb(0.95, 0.3, 0.3);
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+    pub background: Color,
+    pub panel_bg: Color,
+    pub card_bg: Color,
+    pub field_bg: Color,
+    pub text_primary: Color,
+    pub text_secondary: Color,
+    pub text_disabled: Color,
+    pub accent: Color,
+    pub border: Color,
+    pub border_light: Color,
+    pub success: Color,
+    pub warning: Color,
+    pub error: Color,
+}
+
+const DARK: Palette = Palette {
+    background: Color::from_rgb(0.08, 0.08, 0.12),
+    panel_bg: Color::from_rgb(0.12, 0.13, 0.17),
+    card_bg: Color::from_rgb(0.14, 0.15, 0.19),
+    field_bg: Color::from_rgb(0.10, 0.11, 0.14),
+    text_primary: Color::from_rgb(0.95, 0.96, 0.98),
+    text_secondary: Color::from_rgb(0.65, 0.68, 0.75),
+    text_disabled: Color::from_rgb(0.45, 0.47, 0.52),
+    accent: Color::from_rgb(0.25, 0.55, 0.95),
+    border: Color::from_rgb(0.2, 0.22, 0.28),
+    border_light: Color::from_rgb(0.25, 0.28, 0.35),
+    success: Color::from_rgb(0.2, 0.8, 0.4),
+    warning: Color::from_rgb(1.0, 0.65, 0.0),
+    error: Color::from_rgb(0.95, 0.3, 0.3),
+};
+
+const LIGHT: Palette = Palette {
+    background: Color::from_rgb(0.95, 0.95, 0.97),
+    panel_bg: Color::from_rgb(0.92, 0.93, 0.95),
+    card_bg: Color::WHITE,
+    field_bg: Color::from_rgb(0.97, 0.97, 0.98),
+    text_primary: Color::from_rgb(0.1, 0.1, 0.13),
+    text_secondary: Color::from_rgb(0.33, 0.35, 0.4),
+    text_disabled: Color::from_rgb(0.55, 0.57, 0.6),
+    accent: Color::from_rgb(0.1, 0.4, 0.85),
+    border: Color::from_rgb(0.82, 0.84, 0.88),
+    border_light: Color::from_rgb(0.74, 0.77, 0.82),
+    success: Color::from_rgb(0.1, 0.55, 0.25),
+    warning: Color::from_rgb(0.72, 0.42, 0.0),
+    error: Color::from_rgb(0.8, 0.15, 0.15),
+};
+
+// Every text color here clears WCAG AA (4.5:1) against card_bg and panel_bg.
+const HIGH_CONTRAST: Palette = Palette {
+    background: Color::from_rgb(0.0, 0.0, 0.0),
+    panel_bg: Color::from_rgb(0.04, 0.04, 0.05),
+    card_bg: Color::from_rgb(0.06, 0.06, 0.08),
+    field_bg: Color::from_rgb(0.0, 0.0, 0.0),
+    text_primary: Color::WHITE,
+    text_secondary: Color::from_rgb(0.88, 0.9, 0.94),
+    text_disabled: Color::from_rgb(0.68, 0.7, 0.74),
+    accent: Color::from_rgb(0.45, 0.78, 1.0),
+    border: Color::from_rgb(0.7, 0.72, 0.78),
+    border_light: Color::from_rgb(0.85, 0.87, 0.92),
+    success: Color::from_rgb(0.35, 1.0, 0.55),
+    warning: Color::from_rgb(1.0, 0.82, 0.2),
+    error: Color::from_rgb(1.0, 0.5, 0.5),
+};
+
+static ACTIVE_THEME: AtomicU8 = AtomicU8::new(UiTheme::Dark as u8);
+
+// The style helpers are called from view code that has no access to App, so
+// the active choice lives here and App updates it when the user switches.
+pub fn set_ui_theme(theme: UiTheme) {
+    ACTIVE_THEME.store(theme as u8, Ordering::Relaxed);
+}
+
+pub fn ui_theme() -> UiTheme {
+    UiTheme::all()
+        .into_iter()
+        .find(|theme| *theme as u8 == ACTIVE_THEME.load(Ordering::Relaxed))
+        .unwrap_or_default()
+}
+
+pub fn palette() -> Palette {
+    ui_theme().palette()
+}
 
 pub fn panel() -> container::Appearance {
     card()
@@ -25,9 +144,9 @@ pub fn panel() -> container::Appearance {
 
 pub fn card() -> container::Appearance {
     container::Appearance {
-        background: Some(CARD_BG.into()),
+        background: Some(palette().card_bg.into()),
         border: Border {
-            color: BORDER,
+            color: palette().border,
             width: 1.0,
             radius: [8.0; 4].into(),
         },
@@ -42,9 +161,9 @@ pub fn card() -> container::Appearance {
 
 pub fn inline_panel() -> container::Appearance {
     container::Appearance {
-        background: Some(PANEL_BG.into()),
+        background: Some(palette().panel_bg.into()),
         border: Border {
-            color: BORDER_LIGHT,
+            color: palette().border_light,
             width: 1.0,
             radius: [6.0; 4].into(),
         },
@@ -53,15 +172,11 @@ pub fn inline_panel() -> container::Appearance {
 }
 
 pub fn badge() -> container::Appearance {
+    let accent = palette().accent;
     container::Appearance {
-        background: Some(Color::from_rgba(
-            ACCENT.r,
-            ACCENT.g,
-            ACCENT.b,
-            0.15
-        ).into()),
+        background: Some(Color { a: 0.15, ..accent }.into()),
         border: Border {
-            color: Color::from_rgba(ACCENT.r, ACCENT.g, ACCENT.b, 0.3),
+            color: Color { a: 0.3, ..accent },
             width: 1.0,
             radius: [12.0; 4].into(),
         },
@@ -69,11 +184,55 @@ pub fn badge() -> container::Appearance {
     }
 }
 
-pub fn preview_backdrop() -> container::Appearance {
+pub fn swatch(color: Color) -> container::Appearance {
+    container::Appearance {
+        background: Some(color.into()),
+        border: Border {
+            color: palette().border_light,
+            width: 1.0,
+            radius: [3.0; 4].into(),
+        },
+        ..Default::default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PreviewBackdrop {
+    #[default]
+    Dark,
+    Light,
+    WikiGray,
+}
+
+impl PreviewBackdrop {
+    pub fn all() -> Vec<Self> {
+        vec![Self::Dark, Self::Light, Self::WikiGray]
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            PreviewBackdrop::Dark => Color::from_rgb(0.05, 0.05, 0.08),
+            PreviewBackdrop::Light => Color::WHITE,
+            PreviewBackdrop::WikiGray => Color::from_rgb8(0xE9, 0xE9, 0xE9),
+        }
+    }
+}
+
+impl std::fmt::Display for PreviewBackdrop {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PreviewBackdrop::Dark => write!(f, "Dark"),
+            PreviewBackdrop::Light => write!(f, "Light"),
+            PreviewBackdrop::WikiGray => write!(f, "Wiki Gray"),
+        }
+    }
+}
+
+pub fn preview_backdrop(backdrop: PreviewBackdrop) -> container::Appearance {
     container::Appearance {
-        background: Some(Color::from_rgb(0.05, 0.05, 0.08).into()),
+        background: Some(backdrop.color().into()),
         border: Border {
-            color: BORDER,
+            color: palette().border,
             width: 2.0,
             radius: [8.0; 4].into(),
         },
@@ -88,9 +247,9 @@ pub fn preview_backdrop() -> container::Appearance {
 
 pub fn slider_container() -> container::Appearance {
     container::Appearance {
-        background: Some(Color::from_rgba(0.08, 0.09, 0.12, 0.6).into()),
+        background: Some(Color { a: 0.6, ..palette().field_bg }.into()),
         border: Border {
-            color: BORDER_LIGHT,
+            color: palette().border_light,
             width: 1.0,
             radius: [6.0; 4].into(),
         },
@@ -100,9 +259,9 @@ pub fn slider_container() -> container::Appearance {
 
 pub fn input_container() -> container::Appearance {
     container::Appearance {
-        background: Some(Color::from_rgb(0.10, 0.11, 0.14).into()),
+        background: Some(palette().field_bg.into()),
         border: Border {
-            color: Color::from_rgba(ACCENT.r, ACCENT.g, ACCENT.b, 0.3),
+            color: Color { a: 0.3, ..palette().accent },
             width: 1.0,
             radius: [4.0; 4].into(),
         },
@@ -110,11 +269,34 @@ pub fn input_container() -> container::Appearance {
     }
 }
 
+pub fn invalid_input_container() -> container::Appearance {
+    container::Appearance {
+        border: Border {
+            color: palette().error,
+            width: 1.5,
+            radius: [4.0; 4].into(),
+        },
+        ..Default::default()
+    }
+}
+
+pub fn disabled_section() -> container::Appearance {
+    container::Appearance {
+        background: Some(Color::from_rgba(0.0, 0.0, 0.0, 0.15).into()),
+        border: Border {
+            color: palette().border,
+            width: 1.0,
+            radius: [6.0; 4].into(),
+        },
+        ..Default::default()
+    }
+}
+
 pub fn dropdown_container() -> container::Appearance {
     container::Appearance {
-        background: Some(Color::from_rgb(0.10, 0.11, 0.14).into()),
+        background: Some(palette().field_bg.into()),
         border: Border {
-            color: BORDER_LIGHT,
+            color: palette().border_light,
             width: 1.0,
             radius: [6.0; 4].into(),
         },
@@ -125,4 +307,20 @@ pub fn dropdown_container() -> container::Appearance {
         },
         ..Default::default()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::legibility::{contrast_ratio, relative_luminance};
+
+    #[test]
+    fn high_contrast_text_meets_wcag_aa() {
+        let palette = UiTheme::HighContrast.palette();
+        for text in [palette.text_primary, palette.text_secondary, palette.text_disabled] {
+            for background in [palette.background, palette.panel_bg, palette.card_bg, palette.field_bg] {
+                let ratio = contrast_ratio(relative_luminance(text), relative_luminance(background));
+                assert!(ratio >= 4.5, "{:?} on {:?} is only {:.2}:1", text, background, ratio);
+            }
+        }
+    }
+}
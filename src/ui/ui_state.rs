@@ -0,0 +1,125 @@
+use crate::models::SerializableColor;
+use crate::ui::theme::UiTheme;
+use crate::utils::{user_config_dir, LabelError};
+use iced::Color;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const UI_STATE_FILE: &str = "ui_state.json";
+pub const MAX_RECENT_COLORS: usize = 8;
+pub const MAX_RECENT_FILES: usize = 10;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiState {
+    pub recent_colors: Vec<SerializableColor>,
+    pub ui_theme: UiTheme,
+    pub recent_images: Vec<PathBuf>,
+    pub recent_projects: Vec<PathBuf>,
+    pub texture_pack_dir: Option<PathBuf>,
+}
+
+fn state_path() -> Option<PathBuf> {
+    user_config_dir().map(|dir| dir.join(UI_STATE_FILE))
+}
+
+impl UiState {
+    pub fn load() -> Self {
+        let Some(path) = state_path() else {
+            return Self::default();
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                log::warn!("Ignoring unreadable UI state {}: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), LabelError> {
+        let path = state_path()
+            .ok_or_else(|| LabelError::Io("Could not determine a user config directory".to_string()))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| LabelError::Io(e.to_string()))?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| LabelError::ConfigLoading(e.to_string()))?;
+        std::fs::write(&path, json).map_err(|e| LabelError::Io(format!("Failed to write {}: {}", path.display(), e)))
+    }
+}
+
+/// Moves `color` to the front of the palette, dropping duplicates and the
+/// oldest entries beyond [`MAX_RECENT_COLORS`].
+pub fn remember_color(recent: &mut Vec<Color>, color: Color) {
+    let key = color.into_rgba8();
+    recent.retain(|existing| existing.into_rgba8() != key);
+    recent.insert(0, color);
+    recent.truncate(MAX_RECENT_COLORS);
+}
+
+/// Moves `path` to the front of the list, dropping duplicates, files that no
+/// longer exist and the oldest entries beyond [`MAX_RECENT_FILES`].
+pub fn remember_path(recent: &mut Vec<PathBuf>, path: &Path) {
+    recent.retain(|existing| existing != path && existing.is_file());
+    recent.insert(0, path.to_path_buf());
+    recent.truncate(MAX_RECENT_FILES);
+}
+
+/// Drops entries whose files were moved or deleted since they were recorded.
+pub fn existing_paths(recent: Vec<PathBuf>) -> Vec<PathBuf> {
+    recent.into_iter().filter(|path| path.is_file()).take(MAX_RECENT_FILES).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remembers_most_recent_first_without_duplicates() {
+        let mut recent = Vec::new();
+        remember_color(&mut recent, Color::BLACK);
+        remember_color(&mut recent, Color::WHITE);
+        remember_color(&mut recent, Color::BLACK);
+        assert_eq!(recent, vec![Color::BLACK, Color::WHITE]);
+
+        for i in 0..20u8 {
+            remember_color(&mut recent, Color::from_rgb8(i, 0, 0));
+        }
+        assert_eq!(recent.len(), MAX_RECENT_COLORS);
+        assert_eq!(recent[0], Color::from_rgb8(19, 0, 0));
+    }
+
+    #[test]
+    fn recent_files_skip_missing_files_and_cap_length() {
+        let dir = tempfile::tempdir().unwrap();
+        let files: Vec<PathBuf> = (0..12)
+            .map(|i| {
+                let path = dir.path().join(format!("{}.png", i));
+                std::fs::write(&path, b"").unwrap();
+                path
+            })
+            .collect();
+
+        let mut recent = Vec::new();
+        for path in &files {
+            remember_path(&mut recent, path);
+        }
+        remember_path(&mut recent, &files[5]);
+        assert_eq!(recent.len(), MAX_RECENT_FILES);
+        assert_eq!(recent[..2], [files[5].clone(), files[11].clone()]);
+
+        std::fs::remove_file(&files[11]).unwrap();
+        assert!(!existing_paths(recent).contains(&files[11]));
+    }
+
+    #[test]
+    fn texture_pack_dir_round_trips_and_defaults_to_none() {
+        let state = UiState { texture_pack_dir: Some(PathBuf::from("packs/worn")), ..UiState::default() };
+        let json = serde_json::to_string(&state).unwrap();
+        let restored: UiState = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.texture_pack_dir, state.texture_pack_dir);
+
+        let older: UiState = serde_json::from_str(r#"{"recent_images": []}"#).unwrap();
+        assert!(older.texture_pack_dir.is_none());
+    }
+}
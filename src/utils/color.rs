@@ -0,0 +1,88 @@
+use iced::Color;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ColorParseError {
+    #[error("Expected 3, 6 or 8 hex digits, got {0}")]
+    InvalidLength(usize),
+
+    #[error("Invalid hex digits in '{0}'")]
+    InvalidDigit(String),
+}
+
+pub fn parse_hex(hex: &str) -> Result<Color, ColorParseError> {
+    let hex = hex.trim().trim_start_matches('#');
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ColorParseError::InvalidDigit(hex.to_string()));
+    }
+
+    let invalid = || ColorParseError::InvalidDigit(hex.to_string());
+    let channel = |i: usize| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).map_err(|_| invalid());
+    let (r, g, b, a) = match hex.len() {
+        3 => {
+            let nibble = |i: usize| {
+                u8::from_str_radix(&hex[i..i + 1], 16)
+                    .map(|v| v * 17)
+                    .map_err(|_| invalid())
+            };
+            (nibble(0)?, nibble(1)?, nibble(2)?, 255)
+        }
+        6 => (channel(0)?, channel(1)?, channel(2)?, 255),
+        8 => (channel(0)?, channel(1)?, channel(2)?, channel(3)?),
+        len => return Err(ColorParseError::InvalidLength(len)),
+    };
+
+    Ok(Color::from_rgba8(r, g, b, a as f32 / 255.0))
+}
+
+pub fn to_hex(color: Color) -> String {
+    let [r, g, b, a] = color.into_rgba8();
+    if a == 255 {
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    } else {
+        format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgba8(color: Color) -> [u8; 4] {
+        color.into_rgba8()
+    }
+
+    #[test]
+    fn parses_shorthand() {
+        assert_eq!(rgba8(parse_hex("#f80").unwrap()), [255, 136, 0, 255]);
+        assert_eq!(rgba8(parse_hex("fff").unwrap()), [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn parses_six_digits() {
+        assert_eq!(rgba8(parse_hex("#1a2b3c").unwrap()), [0x1a, 0x2b, 0x3c, 255]);
+        assert_eq!(rgba8(parse_hex("000000").unwrap()), [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn parses_eight_digits_with_alpha() {
+        assert_eq!(rgba8(parse_hex("#ff0000cc").unwrap()), [255, 0, 0, 0xcc]);
+        assert_eq!(rgba8(parse_hex("00000000").unwrap()), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert_eq!(parse_hex(""), Err(ColorParseError::InvalidLength(0)));
+        assert_eq!(parse_hex("#12"), Err(ColorParseError::InvalidLength(2)));
+        assert_eq!(parse_hex("#12345"), Err(ColorParseError::InvalidLength(5)));
+        assert!(matches!(parse_hex("#gggggg"), Err(ColorParseError::InvalidDigit(_))));
+        assert!(matches!(parse_hex("#ééé"), Err(ColorParseError::InvalidDigit(_))));
+    }
+
+    #[test]
+    fn round_trips_through_hex() {
+        for hex in ["#000000", "#1a2b3c", "#ff0000cc"] {
+            assert_eq!(to_hex(parse_hex(hex).unwrap()), hex);
+        }
+    }
+}
@@ -0,0 +1,49 @@
+const MAX_FILENAME_LEN: usize = 120;
+
+pub fn sanitize_filename(name: &str) -> String {
+    let replaced: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+
+    let truncated: String = replaced.trim().chars().take(MAX_FILENAME_LEN).collect();
+    let cleaned = truncated.trim_end_matches(['.', ' ']);
+
+    if cleaned.is_empty() {
+        "label".to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ClassType;
+
+    #[test]
+    fn sanitizes_every_class_name() {
+        for class in ClassType::all() {
+            let sanitized = sanitize_filename(class.as_str());
+            assert!(!sanitized.is_empty());
+            assert!(
+                !sanitized.contains(['/', '\\', ':', '*', '?', '"', '<', '>', '|']),
+                "{} -> {}",
+                class.as_str(),
+                sanitized
+            );
+        }
+        assert_eq!(sanitize_filename(ClassType::EuclidPotentialKeter.as_str()), "EUCLID _ POTENTIAL KETER");
+    }
+
+    #[test]
+    fn trims_and_limits_length() {
+        assert_eq!(sanitize_filename("  ..  "), "label");
+        assert_eq!(sanitize_filename("name. "), "name");
+        assert_eq!(sanitize_filename(&"a".repeat(500)).len(), MAX_FILENAME_LEN);
+    }
+}
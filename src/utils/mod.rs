@@ -1,7 +1,12 @@
+pub mod color;
 mod error;
+mod filename;
 mod logger;
+mod paths;
 mod validation;
 
 pub use error::{LabelError, CliExitCode};
+pub use filename::sanitize_filename;
 pub use logger::setup_logger;
-pub use validation::{validate_user_image, load_image_robustly};
\ No newline at end of file
+pub use paths::{asset_path, set_asset_root, user_config_dir, user_texture_pack_dir, TEXTURE_PACK_DIR};
+pub use validation::{decode_image, validate_dimensions, validate_user_image, load_image_robustly};
\ No newline at end of file
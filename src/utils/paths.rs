@@ -0,0 +1,30 @@
+use directories::ProjectDirs;
+use once_cell::sync::OnceCell;
+use std::path::{Path, PathBuf};
+
+const ASSET_ROOT_ENV: &str = "ASSET_ROOT";
+pub const TEXTURE_PACK_DIR: &str = "texturepacks";
+
+static ASSET_ROOT: OnceCell<PathBuf> = OnceCell::new();
+
+pub fn set_asset_root(root: PathBuf) {
+    if ASSET_ROOT.set(root).is_err() {
+        log::warn!("Asset root was already resolved; ignoring override.");
+    }
+}
+
+pub fn asset_root() -> &'static Path {
+    ASSET_ROOT.get_or_init(|| std::env::var_os(ASSET_ROOT_ENV).map(PathBuf::from).unwrap_or_default())
+}
+
+pub fn asset_path(relative: impl AsRef<Path>) -> PathBuf {
+    asset_root().join(relative)
+}
+
+pub fn user_config_dir() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "scp-label-maker").map(|dirs| dirs.config_dir().to_path_buf())
+}
+
+pub fn user_texture_pack_dir() -> Option<PathBuf> {
+    user_config_dir().map(|dir| dir.join(TEXTURE_PACK_DIR))
+}
@@ -1,4 +1,4 @@
-use crate::models::{ImageValidation, ValidationStatus, NormalLayout};
+use crate::models::{ImageValidation, ValidationStatus, NormalLayout, ResizeMethod};
 use image::{DynamicImage, GenericImageView};
 use std::path::Path;
 use crate::utils::LabelError;
@@ -7,16 +7,23 @@ use crate::utils::LabelError;
 pub fn load_image_robustly(path: &Path) -> Result<DynamicImage, LabelError> {
     let bytes = std::fs::read(path)
         .map_err(|e| LabelError::ImageLoading(format!("Failed to read file: {}", e)))?;
+    decode_image(&bytes)
+}
 
-    let format = image::guess_format(&bytes)
+pub fn decode_image(bytes: &[u8]) -> Result<DynamicImage, LabelError> {
+    let format = image::guess_format(bytes)
         .map_err(|e| LabelError::ImageLoading(format!("Could not determine image format: {}", e)))?;
 
-    image::load_from_memory_with_format(&bytes, format)
+    image::load_from_memory_with_format(bytes, format)
         .map_err(|e| LabelError::ImageLoading(format!("Failed to decode image: {}", e)))
 }
 
-pub fn validate_user_image(image: &DynamicImage) -> ImageValidation {
+pub fn validate_user_image(image: &DynamicImage, method: ResizeMethod) -> ImageValidation {
     let (width, height) = image.dimensions();
+    validate_dimensions((width, height), method)
+}
+
+pub fn validate_dimensions((width, height): (u32, u32), method: ResizeMethod) -> ImageValidation {
     let target = (
         NormalLayout::USER_IMAGE.width,
         NormalLayout::USER_IMAGE.height,
@@ -27,32 +34,93 @@ pub fn validate_user_image(image: &DynamicImage) -> ImageValidation {
 
     let tolerance = 0.02;
     let ratio_diff = (source_ratio - target_ratio).abs();
+    let wider = source_ratio > target_ratio;
 
-    if ratio_diff < tolerance {
-        ImageValidation {
-            status: ValidationStatus::PerfectFit,
-            source_dimensions: (width, height),
-            target_dimensions: target,
-            message: format!("OK: Image is perfect ({}×{})", width, height),
-        }
+    let (status, message) = if ratio_diff < tolerance {
+        (ValidationStatus::PerfectFit, format!("OK: Image is perfect ({}×{})", width, height))
     } else {
-        let message = if source_ratio > target_ratio {
-            format!(
-                "Warning: Image will be cropped ({}×{} → {}×{})",
-                width, height, target.0, target.1
-            )
-        } else {
-            format!(
-                "Warning: Image will be cropped ({}×{} → {}×{})",
-                width, height, target.0, target.1
-            )
-        };
-
-        ImageValidation {
-            status: ValidationStatus::WillCrop,
-            source_dimensions: (width, height),
-            target_dimensions: target,
-            message,
+        match method {
+            ResizeMethod::CropToFit => {
+                let (lost, axis) = if wider {
+                    (1.0 - target_ratio / source_ratio, "width")
+                } else {
+                    (1.0 - source_ratio / target_ratio, "height")
+                };
+                (
+                    ValidationStatus::WillCrop,
+                    format!(
+                        "Warning: {:.0}% of the {} will be cropped ({}×{} → {}×{})",
+                        lost * 100.0, axis, width, height, target.0, target.1
+                    ),
+                )
+            }
+            ResizeMethod::Stretch => {
+                let (factor, axis) = if wider {
+                    (source_ratio / target_ratio, "horizontally")
+                } else {
+                    (target_ratio / source_ratio, "vertically")
+                };
+                (
+                    ValidationStatus::WillStretch,
+                    format!(
+                        "Warning: Image will be distorted, squeezed {} by {:.0}% ({}×{} → {}×{})",
+                        axis, (1.0 - 1.0 / factor) * 100.0, width, height, target.0, target.1
+                    ),
+                )
+            }
+            ResizeMethod::Letterbox | ResizeMethod::BlurFill => {
+                let scale = (target.0 as f32 / width as f32).min(target.1 as f32 / height as f32);
+                let (bar, sides) = if wider {
+                    ((target.1 as f32 - height as f32 * scale) / 2.0, "top and bottom")
+                } else {
+                    ((target.0 as f32 - width as f32 * scale) / 2.0, "left and right")
+                };
+                let fill = if method == ResizeMethod::BlurFill { "blurred fill" } else { "bars" };
+                (
+                    ValidationStatus::WillLetterbox,
+                    format!(
+                        "Note: {}px {} will be added on the {} ({}×{} → {}×{})",
+                        bar.round() as u32, fill, sides, width, height, target.0, target.1
+                    ),
+                )
+            }
+        }
+    };
+
+    ImageValidation {
+        status,
+        source_dimensions: (width, height),
+        target_dimensions: target,
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn perfect_fit_ignores_resize_method() {
+        let target = (NormalLayout::USER_IMAGE.width, NormalLayout::USER_IMAGE.height);
+        for method in [ResizeMethod::CropToFit, ResizeMethod::Stretch, ResizeMethod::Letterbox, ResizeMethod::BlurFill] {
+            assert_eq!(validate_dimensions(target, method).status, ValidationStatus::PerfectFit);
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn reports_outcome_for_each_method() {
+        let wide = (932, 480);
+
+        let crop = validate_dimensions(wide, ResizeMethod::CropToFit);
+        assert_eq!(crop.status, ValidationStatus::WillCrop);
+        assert!(crop.message.contains("50% of the width"), "{}", crop.message);
+
+        let stretch = validate_dimensions(wide, ResizeMethod::Stretch);
+        assert_eq!(stretch.status, ValidationStatus::WillStretch);
+        assert!(stretch.message.contains("horizontally"), "{}", stretch.message);
+
+        let letterbox = validate_dimensions(wide, ResizeMethod::Letterbox);
+        assert_eq!(letterbox.status, ValidationStatus::WillLetterbox);
+        assert!(letterbox.message.contains("top and bottom"), "{}", letterbox.message);
+    }
+}